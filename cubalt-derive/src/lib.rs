@@ -1,7 +1,101 @@
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+//! Derive macros for cubalt.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Expr, Type};
+
+/// Implements `cubalt::coord::Coordinate` from a rank/unrank pair and a
+/// size, and adds `move_table` and `read_move_table` to the type:
+///
+/// ```ignore
+/// #[derive(Coordinate)]
+/// #[coordinate(puzzle = Cube, size = 2187, rank = co_rank, unrank = co_unrank)]
+/// struct CornerOrient;
+/// ```
+///
+/// `rank` must be callable as `fn(&P) -> usize` and `unrank` as
+/// `fn(usize) -> P`, for `P` the puzzle.
+#[proc_macro_derive(Coordinate, attributes(coordinate))]
+pub fn derive_coordinate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match coordinate(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
     }
 }
+
+fn coordinate(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut puzzle: Option<Type> = None;
+    let mut size: Option<Expr> = None;
+    let mut rank: Option<Expr> = None;
+    let mut unrank: Option<Expr> = None;
+    for attr in input.attrs.iter() {
+        if !attr.path().is_ident("coordinate") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            let value = meta.value()?;
+            if meta.path.is_ident("puzzle") {
+                puzzle = Some(value.parse()?);
+            } else if meta.path.is_ident("size") {
+                size = Some(value.parse()?);
+            } else if meta.path.is_ident("rank") {
+                rank = Some(value.parse()?);
+            } else if meta.path.is_ident("unrank") {
+                unrank = Some(value.parse()?);
+            } else {
+                return Err(meta.error("expected puzzle, size, rank or unrank"));
+            }
+            Ok(())
+        })?;
+    }
+    let missing = |what: &str| {
+        syn::Error::new_spanned(
+            &input.ident,
+            format!("missing `#[coordinate({} = ...)]`", what),
+        )
+    };
+    let puzzle = puzzle.ok_or_else(|| missing("puzzle"))?;
+    let size = size.ok_or_else(|| missing("size"))?;
+    let rank = rank.ok_or_else(|| missing("rank"))?;
+    let unrank = unrank.ok_or_else(|| missing("unrank"))?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::cubalt::coord::Coordinate<#puzzle>
+            for #name #ty_generics #where_clause
+        {
+            const SIZE: usize = #size;
+
+            fn rank(p: &#puzzle) -> usize {
+                (#rank)(p)
+            }
+
+            fn unrank(c: usize) -> #puzzle {
+                (#unrank)(c)
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Transitions of this coordinate under all of the puzzle's
+            /// moves.
+            pub fn move_table() -> ::cubalt::movetable::MoveTable {
+                ::cubalt::movetable::MoveTable::build::<#puzzle, Self>()
+            }
+
+            /// Read a move table saved with `MoveTable::write_to`, checking
+            /// its dimensions against this coordinate.
+            pub fn read_move_table<R: ::std::io::Read>(
+                r: R,
+            ) -> ::std::io::Result<::cubalt::movetable::MoveTable> {
+                ::cubalt::movetable::MoveTable::read_from(
+                    r,
+                    <Self as ::cubalt::coord::Coordinate<#puzzle>>::SIZE,
+                    <#puzzle as ::cubalt::puzzle::Puzzle>::moves().len(),
+                )
+            }
+        }
+    })
+}
+
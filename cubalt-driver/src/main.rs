@@ -1,5 +0,0 @@
-extern crate cubalt;
-
-fn main() {
-    cubalt::toplevel();
-}
@@ -0,0 +1,314 @@
+//! Generates the move and symmetry tables of `Cube` from cycle notation.
+//!
+//! The face turns and the four generating symmetries are written out as
+//! cycles; everything else is derived from them and checked against the
+//! group axioms before anything is written.
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+#[allow(dead_code, unused_macros)]
+#[path = "src/cycles.rs"]
+mod cycles;
+
+/// Quarter turns, in face order U R F D L B.
+const FACES: [&str; 6] = [
+    "corners: (URF UFL ULB UBR), edges: (UR UF UL UB)",
+    "corners: (FRD URF BRU DRB), edges: (FR UR BR DR)",
+    "corners: (URF RDF DLF LUF), edges: (UF RF DF LF)",
+    "corners: (DLF DFR DRB DBL), edges: (DF DR DB DL)",
+    "corners: (ULB FLU DLF BLD), edges: (UL FL DL BL)",
+    "corners: (UBR LBU DBL RBD), edges: (UB LB DB RB)",
+];
+
+/// 120-degree clockwise rotation on the URF-DBL axis.
+const S_URF3: &str = "corners: (URF)+ (UFL RUB FRD) (ULB RBD FDL) (DBL)-, \
+     edges: (UR RF FU) (UL RB FD) (UB RD FL) (DL LB BD)";
+
+/// 90-degree clockwise rotation on the U-D axis.
+const S_U4: &str = "corners: (URF UFL ULB UBR) (DFR DLF DBL DRB), \
+     edges: (UR UF UL UB) (DR DF DL DB) (FR LF BL RB)";
+
+/// 180-degree rotation on the F-B axis.
+const S_F2: &str = "corners: (URF DLF) (UFL DFR) (ULB DRB) (UBR DBL), \
+     edges: (UR DL) (UF DF) (UL DR) (UB DB) (FR FL) (BL BR)";
+
+/// Reflection left to right.
+const S_LR2: &str = "corners: (URF ULF) (UBR ULB) (DFR DLF) (DRB DBL), \
+     edges: (UR UL) (FR FL) (DR DL) (BR BL)";
+
+/// A cube state, in the packing of `Cube`, with scalar arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cubie {
+    corners: [u8; 8],
+    edges: [u8; 12],
+    /// Reflections reverse corner twists; see `Cube::compose_mirror`.
+    mirror: bool,
+}
+
+impl Cubie {
+    fn identity() -> Self {
+        Self::parse("", false)
+    }
+
+    fn parse(spec: &str, mirror: bool) -> Self {
+        let [corners, edges_high, edges_low] = cycles::parse(spec);
+        let mut c = Cubie {
+            corners: [0; 8],
+            edges: [0; 12],
+            mirror,
+        };
+        for i in 0..8 {
+            c.corners[i] = (corners >> (8 * i)) as u8;
+            c.edges[i] = (edges_low >> (8 * i)) as u8;
+        }
+        for i in 8..12 {
+            c.edges[i] = (edges_high >> (8 * (i - 8))) as u8;
+        }
+        c
+    }
+
+    fn words(&self) -> [u64; 3] {
+        let mut w = [0; 3];
+        for i in 0..8 {
+            w[0] |= (self.corners[i] as u64) << (8 * i);
+            w[2] |= (self.edges[i] as u64) << (8 * i);
+        }
+        for i in 8..12 {
+            w[1] |= (self.edges[i] as u64) << (8 * (i - 8));
+        }
+        w
+    }
+
+    /// `self`, then `other`.
+    fn compose(&self, other: &Self) -> Self {
+        let mut c = *self;
+        for i in 0..8 {
+            let a = self.corners[(other.corners[i] & 0x07) as usize];
+            let (a_ori, b_ori) = (a >> 4, other.corners[i] >> 4);
+            let ori = if self.mirror {
+                (a_ori + 3 - b_ori) % 3
+            } else {
+                (a_ori + b_ori) % 3
+            };
+            c.corners[i] = ori << 4 | (a & 0x07);
+        }
+        for i in 0..12 {
+            let a = self.edges[(other.edges[i] & 0x0f) as usize];
+            c.edges[i] = a ^ (other.edges[i] & 0x10);
+        }
+        c.mirror = self.mirror != other.mirror;
+        c
+    }
+
+    fn pow(&self, n: usize) -> Self {
+        (0..n).fold(Self::identity(), |c, _| c.compose(self))
+    }
+
+    fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// Twists and flips sum to zero, and the permutations have equal
+    /// parity.
+    fn is_valid(&self) -> bool {
+        let twist: u8 = self.corners.iter().map(|c| c >> 4).sum();
+        let flip: u8 = self.edges.iter().map(|e| e >> 4).sum();
+        let parity = |p: &[u8], mask: u8| {
+            let mut odd = false;
+            for i in 0..p.len() {
+                for j in i + 1..p.len() {
+                    odd ^= p[i] & mask > p[j] & mask;
+                }
+            }
+            odd
+        };
+        twist.is_multiple_of(3)
+            && flip.is_multiple_of(2)
+            && parity(&self.corners, 0x07) == parity(&self.edges, 0x0f)
+    }
+}
+
+fn cube_literal(c: &Cubie) -> String {
+    let [corners, edges_high, edges_low] = c.words();
+    format!(
+        "Cube::new(0x{:016x}, 0x{:08x}, 0x{:016x})",
+        corners, edges_high, edges_low
+    )
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/cycles.rs");
+
+    let faces: Vec<Cubie> =
+        FACES.iter().map(|s| Cubie::parse(s, false)).collect();
+    let moves: Vec<Cubie> = faces
+        .iter()
+        .flat_map(|f| (1..=3).map(move |p| f.pow(p)))
+        .collect();
+    for (i, f) in faces.iter().enumerate() {
+        assert!(f.is_valid(), "face {} is not a legal state", i);
+        assert!(f.pow(4).is_identity(), "face {} does not have order 4", i);
+    }
+
+    let urf3 = Cubie::parse(S_URF3, false);
+    let u4 = Cubie::parse(S_U4, false);
+    let f2 = Cubie::parse(S_F2, false);
+    let lr2 = Cubie::parse(S_LR2, true);
+    assert!(urf3.pow(3).is_identity(), "S_URF3 does not have order 3");
+    assert!(u4.pow(4).is_identity(), "S_U4 does not have order 4");
+    assert!(f2.pow(2).is_identity(), "S_F2 does not have order 2");
+    assert!(lr2.pow(2).is_identity(), "S_LR2 does not have order 2");
+
+    // Symmetry 16a + 4b + 2c + d is S_URF3^a S_U4^b S_F2^c S_LR2^d.
+    let sym: Vec<Cubie> = (0..48)
+        .map(|i| {
+            urf3.pow(i >> 4)
+                .compose(&u4.pow(i >> 2 & 3))
+                .compose(&f2.pow(i >> 1 & 1))
+                .compose(&lr2.pow(i & 1))
+        })
+        .collect();
+    let index = |c: &Cubie| sym.iter().position(|s| s == c);
+    let move_index = |c: &Cubie| moves.iter().position(|m| m == c);
+    let mut sym_inv = Vec::with_capacity(48);
+    for (i, s) in sym.iter().enumerate() {
+        assert_eq!(s.mirror, i & 1 == 1, "symmetry {} has the wrong parity", i);
+        assert_eq!(index(s), Some(i), "symmetry {} is repeated", i);
+        for t in sym.iter() {
+            assert!(index(&s.compose(t)).is_some(), "symmetries not closed");
+        }
+        let inv = sym
+            .iter()
+            .position(|t| s.compose(t).is_identity())
+            .expect("symmetry without an inverse");
+        assert!(
+            sym[inv].compose(s).is_identity(),
+            "symmetry {} has no two-sided inverse",
+            i
+        );
+        // Conjugation is an automorphism: moves go to moves, inverses to
+        // inverses, and faces on one axis to faces on one axis.
+        let conj = |m: usize| {
+            move_index(&s.compose(&moves[m]).compose(&sym[inv])).unwrap_or_else(
+                || panic!("symmetry {} does not map move {} to a move", i, m),
+            )
+        };
+        for m in 0..18 {
+            assert_eq!(
+                conj(m / 3 * 3 + 2 - m % 3),
+                conj(m) / 3 * 3 + 2 - conj(m) % 3,
+                "symmetry {} does not commute with inversion",
+                i
+            );
+            assert_eq!(
+                conj((m + 9) % 18) / 3 % 3,
+                conj(m) / 3 % 3,
+                "symmetry {} splits an axis",
+                i
+            );
+        }
+        sym_inv.push(inv);
+    }
+    for (i, &inv) in sym_inv.iter().enumerate() {
+        assert_eq!(
+            sym_inv[inv], i,
+            "inverse of symmetry {} is not an involution",
+            i
+        );
+    }
+
+    // Each move conjugated by S_URF3^k, then the inverses of those.
+    let move_sym_6: Vec<[usize; 6]> = (0..18)
+        .map(|m| {
+            let mut row = [0; 6];
+            for k in 0..3 {
+                let s = &sym[16 * k];
+                let c = s.compose(&moves[m]).compose(&sym[sym_inv[16 * k]]);
+                let j = move_index(&c).unwrap();
+                row[k] = j;
+                row[k + 3] = j / 3 * 3 + 2 - j % 3;
+            }
+            row
+        })
+        .collect();
+
+    let mut out = String::new();
+    let mut named = |doc: &str, name: &str, c: &Cubie| {
+        write!(
+            out,
+            "    /// {}\n    pub fn {}() -> Self {{\n        Self::{}\n    }}\n\n",
+            doc,
+            name,
+            &cube_literal(c)["Cube::".len()..]
+        )
+        .unwrap();
+    };
+    named(
+        "S_URF3 - 120-degree clockwise rotation on URF-DBL axis (x y)",
+        "S_URF3",
+        &urf3,
+    );
+    named("The inverse of S_URF3", "S_URF3i", &urf3.pow(2));
+    named(
+        "S_U4   - 90-degree clockwise rotation on U-D axis (y)",
+        "S_U4",
+        &u4,
+    );
+    named("S_LR2  - Reflection left to right", "S_LR2", &lr2);
+    named("S_F2 - 180-degree rotation on F-B axis (z2)", "S_F2", &f2);
+    named(
+        "M_U - 90-degree clockwise twist of the U face",
+        "M_U",
+        &faces[0],
+    );
+
+    out.push_str("    /// Move table:\n");
+    out.push_str(
+        "    /// U, U2, U', R, R2, R', F, F2, F', D, D2, D', L, L2, L', B, B2, B'\n",
+    );
+    out.push_str("    pub const fn moves() -> [Self; 18] {\n        [\n");
+    for m in moves.iter() {
+        writeln!(out, "            {},", cube_literal(m)).unwrap();
+    }
+    out.push_str("        ]\n    }\n\n");
+
+    out.push_str(
+        "    /// Symmetries (0..47):\n    /// S_LR2  (0, 1)\n    /// S_F2   (0, 2)\n    \
+         /// S_U4   (0, 4, 8, 12)\n    /// S_URF3 (0, 16, 32)\n",
+    );
+    out.push_str("    pub const fn sym() -> [Self; 48] {\n        [\n");
+    for s in sym.iter() {
+        writeln!(out, "            {},", cube_literal(s)).unwrap();
+    }
+    out.push_str("        ]\n    }\n\n");
+
+    out.push_str(
+        "    /// Inverse symmetry map\n    pub const fn sym_inv() -> [u8; 48] {\n        [",
+    );
+    for (i, inv) in sym_inv.iter().enumerate() {
+        if i % 16 == 0 {
+            out.push_str("\n           ");
+        }
+        write!(out, " {},", inv).unwrap();
+    }
+    out.push_str("\n        ]\n    }\n\n");
+
+    out.push_str(
+        "    /// Each move conjugated by S_URF3^0, S_URF3^1, S_URF3^2, then the\n    \
+         /// inverses of those three; padded to 8.\n",
+    );
+    out.push_str(
+        "    pub const fn move_sym_6() -> [[u8; 8]; 18] {\n        [\n",
+    );
+    for row in move_sym_6.iter() {
+        let row: Vec<String> = row.iter().map(|j| j.to_string()).collect();
+        writeln!(out, "            [{}, 0, 0],", row.join(", ")).unwrap();
+    }
+    out.push_str("        ]\n    }\n");
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("tables.rs");
+    fs::write(path, format!("impl Cube {{\n{}}}\n", out)).unwrap();
+}
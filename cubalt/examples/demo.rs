@@ -0,0 +1,27 @@
+//! Prints the identity, its inverse, and the U move applied four times.
+use cubalt::prelude::*;
+
+fn show(cube: &Cube) {
+    println!("{:?}", cube);
+    for edge in cube.edges() {
+        print!("{:?} | ", edge.0);
+    }
+    println!();
+    for corner in cube.corners() {
+        print!("{:?} | ", corner.0);
+    }
+    println!();
+}
+
+fn main() {
+    let cube = Cube::identity();
+    show(&cube);
+    show(&!cube);
+
+    let mut c = cube;
+    show(&c);
+    for _ in 0..4 {
+        c = c * *Move::U.cube();
+        show(&c);
+    }
+}
@@ -0,0 +1,62 @@
+/* C interface to cubalt. Declarations mirror src/ffi.rs; tests/ffi.rs
+ * builds and runs tests/ffi.c against them. */
+#ifndef CUBALT_H
+#define CUBALT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handles; release with the matching _free function. */
+typedef struct CubaltCube CubaltCube;
+typedef struct CubaltSolver CubaltSolver;
+
+CubaltCube *cubalt_cube_new(void);
+void cubalt_cube_free(CubaltCube *cube);
+CubaltCube *cubalt_cube_clone(const CubaltCube *cube);
+
+/* a, then b. */
+CubaltCube *cubalt_cube_compose(const CubaltCube *a, const CubaltCube *b);
+CubaltCube *cubalt_cube_invert(const CubaltCube *cube);
+int cubalt_cube_equal(const CubaltCube *a, const CubaltCube *b);
+int cubalt_cube_is_solved(const CubaltCube *cube);
+
+/* NULL if the alg does not parse or the facelets are not a legal cube. */
+CubaltCube *cubalt_cube_from_alg(const char *alg);
+CubaltCube *cubalt_cube_from_facelets(const char *facelets);
+
+/* 0 on success, -1 if the alg does not parse. */
+int cubalt_cube_apply_alg(CubaltCube *cube, const char *alg);
+
+/*
+ * Buffer getters work like snprintf: at most len bytes are written,
+ * including a terminating NUL, and the full length is returned.
+ */
+size_t cubalt_cube_facelets(const CubaltCube *cube, char *buf, size_t len);
+
+/* Raw 32-byte state, not NUL-terminated. Returns 32. */
+size_t cubalt_cube_bytes(const CubaltCube *cube, uint8_t *buf, size_t len);
+
+/*
+ * One-hot encoding: 20x24 pieces (480 floats) if facelets is 0, else 54x6
+ * facelets (324 floats). Writes only if len is enough; returns the count.
+ */
+size_t cubalt_cube_one_hot(const CubaltCube *cube, int facelets, float *out,
+                           size_t len);
+
+/* Builds the pruning tables; takes a few seconds. */
+CubaltSolver *cubalt_solver_new(void);
+void cubalt_solver_free(CubaltSolver *solver);
+
+/* Returns (size_t)-1 if no solution of at most max_length moves is found. */
+size_t cubalt_solve(const CubaltSolver *solver, const CubaltCube *cube,
+                    unsigned max_length, char *buf, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CUBALT_H */
@@ -0,0 +1,487 @@
+//! Move sequences.
+use crate::canonical::CanonicalSeq;
+use crate::cube::Cube;
+use crate::notation::{Move, ParseMoveError};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A sequence of face turns, applied left to right.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Alg(pub Vec<Move>);
+
+impl Alg {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.0
+    }
+
+    pub fn push(&mut self, m: Move) {
+        self.0.push(m);
+    }
+
+    /// The alg undoing this one.
+    pub fn inverse(&self) -> Self {
+        Self(self.0.iter().rev().map(|m| m.inverse()).collect())
+    }
+
+    /// Merge adjacent turns of the same face, dropping those that cancel.
+    pub fn simplify(&self) -> Self {
+        let mut moves: Vec<Move> = Vec::with_capacity(self.0.len());
+        for &m in self.0.iter() {
+            match moves.last() {
+                Some(&last) if last.face() == m.face() => {
+                    moves.pop();
+                    let power = (last.power() + m.power()) % 4;
+                    if power != 0 {
+                        moves.push(Move::new(m.face(), power));
+                    }
+                }
+                _ => moves.push(m),
+            }
+        }
+        Self(moves)
+    }
+
+    /// As `simplify`, also merging turns of the same face across a turn of
+    /// the opposite face, which commutes with both: `U D U'` is `D`. A merged
+    /// turn stays where the first of its turns was.
+    pub fn simplify_commuting(&self) -> Self {
+        let mut moves: Vec<Move> = Vec::with_capacity(self.0.len());
+        for &m in self.0.iter() {
+            let same = moves
+                .iter()
+                .rev()
+                .take(2)
+                .take_while(|p| p.face().axis() == m.face().axis())
+                .position(|p| p.face() == m.face());
+            match same {
+                Some(k) => {
+                    let i = moves.len() - 1 - k;
+                    let power = (moves[i].power() + m.power()) % 4;
+                    if power == 0 {
+                        moves.remove(i);
+                    } else {
+                        moves[i] = Move::new(m.face(), power);
+                    }
+                }
+                None => moves.push(m),
+            }
+        }
+        Self(moves)
+    }
+
+    /// The state reached by applying the alg to `cube`.
+    pub fn apply(&self, cube: &Cube) -> Cube {
+        self.0.iter().fold(*cube, |c, m| c.compose(m.cube()))
+    }
+
+    /// The state reached by applying the alg to the solved cube.
+    pub fn cube(&self) -> Cube {
+        self.apply(&Cube::identity())
+    }
+
+    /// The state reached by applying `premoves`, then `scramble`, then the
+    /// alg to the solved cube.
+    pub fn apply_with_premoves(&self, premoves: &Alg, scramble: &Alg) -> Cube {
+        self.apply(&scramble.apply(&premoves.cube()))
+    }
+
+    /// The moves to play after the scramble, when premoves P before it and
+    /// this alg A after it solve it. P S A solved means S A P is too, so
+    /// the premoves go last.
+    pub fn with_premoves(&self, premoves: &Alg) -> Self {
+        let mut alg = self.clone();
+        alg.0.extend_from_slice(premoves.moves());
+        alg.simplify()
+    }
+}
+
+/// Number of whitespace-separated moves in `s`; the length `parse_moves`
+/// needs.
+pub const fn count_moves(s: &str) -> usize {
+    let s = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if !s[i].is_ascii_whitespace()
+            && (i == 0 || s[i - 1].is_ascii_whitespace())
+        {
+            n += 1;
+        }
+        i += 1;
+    }
+    n
+}
+
+/// The moves of `s`, read as `FromStr` reads them, in a const context.
+/// Panics if `s` does not hold exactly `N` moves.
+pub const fn parse_moves<const N: usize>(s: &str) -> [Move; N] {
+    let s = s.as_bytes();
+    let mut moves = [Move::U; N];
+    let mut n = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if s[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let face = match s[i] {
+            b'U' => 0,
+            b'R' => 1,
+            b'F' => 2,
+            b'D' => 3,
+            b'L' => 4,
+            b'B' => 5,
+            _ => panic!("not a move"),
+        };
+        i += 1;
+        let mut power = 1;
+        if i < s.len() && s[i] == b'2' {
+            power = 2;
+            i += 1;
+        }
+        if i < s.len() && s[i] == b'\'' {
+            if power == 1 {
+                power = 3;
+            }
+            i += 1;
+        }
+        if i < s.len() && !s[i].is_ascii_whitespace() {
+            panic!("not a move");
+        }
+        if n == N {
+            panic!("too many moves");
+        }
+        moves[n] = Move::ALL[face * 3 + power - 1];
+        n += 1;
+    }
+    if n != N {
+        panic!("too few moves");
+    }
+    moves
+}
+
+/// Why `parse_into` read no alg. It holds no text, so failing allocates
+/// nothing either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseIntoError {
+    /// The word at this byte offset of the input is not a move.
+    NotAMove(usize),
+    /// The input has more moves than the buffer holds.
+    TooLong,
+}
+
+impl fmt::Display for ParseIntoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseIntoError::NotAMove(at) => write!(f, "not a move at {}", at),
+            ParseIntoError::TooLong => write!(f, "too many moves"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseIntoError {}
+
+/// The whitespace-separated moves of `s`, as `FromStr` reads them, at the
+/// front of `buf`; returns how many there are. For callers without a heap,
+/// with `count_moves` to size the buffer.
+pub fn parse_into(s: &str, buf: &mut [Move]) -> Result<usize, ParseIntoError> {
+    let mut n = 0;
+    for word in s.split_whitespace() {
+        let at = word.as_ptr() as usize - s.as_ptr() as usize;
+        let m = Move::parse(word).ok_or(ParseIntoError::NotAMove(at))?;
+        *buf.get_mut(n).ok_or(ParseIntoError::TooLong)? = m;
+        n += 1;
+    }
+    Ok(n)
+}
+
+/// The state reached by applying `moves` to the solved cube, in a const
+/// context.
+pub const fn moves_cube(moves: &[Move]) -> Cube {
+    let table = Cube::moves();
+    let mut cube = Cube::IDENTITY;
+    let mut i = 0;
+    while i < moves.len() {
+        cube = cube.const_compose(&table[moves[i] as usize]);
+        i += 1;
+    }
+    cube
+}
+
+/// The moves of an alg, parsed at compile time, as a `&'static [Move]`:
+/// `alg!("R U R' U'")`. Bad notation is a compile error. `alg!(cube "...")`
+/// gives the state the alg reaches instead, also computed at compile time.
+#[macro_export]
+macro_rules! alg {
+    (cube $s:expr) => {{
+        const CUBE: $crate::cube::Cube =
+            $crate::alg::moves_cube($crate::alg!($s));
+        CUBE
+    }};
+    ($s:expr) => {{
+        const N: usize = $crate::alg::count_moves($s);
+        const MOVES: [$crate::notation::Move; N] = $crate::alg::parse_moves($s);
+        &MOVES
+    }};
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Alg {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Ok(Self(u.arbitrary()?))
+    }
+}
+
+/// In notation, as `R U R' U'`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Alg {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Alg {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        d: D,
+    ) -> Result<Self, D::Error> {
+        let s: alloc::string::String = serde::Deserialize::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Random move sequences of a fixed length.
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomAlg {
+    pub len: usize,
+    /// Never turn a face twice in a row, and turn commuting opposite faces
+    /// in one order only (U before D, R before L, F before B).
+    pub canonical: bool,
+}
+
+#[cfg(feature = "rand")]
+impl RandomAlg {
+    /// Canonical sequences of `len` moves.
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            canonical: true,
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Alg> for RandomAlg {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Alg {
+        let mut moves: Vec<Move> = Vec::with_capacity(self.len);
+        while moves.len() < self.len {
+            let m: Move = rng.gen();
+            let allowed = !self.canonical
+                || moves.last().is_none_or(|prev| {
+                    let (face, next) = (prev.face(), m.face());
+                    face.axis() != next.axis() || (next as u8) > (face as u8)
+                });
+            if allowed {
+                moves.push(m);
+            }
+        }
+        Alg(moves)
+    }
+}
+
+/// Every canonical sequence (see `canonical`) of at most `max_len` moves,
+/// shortest first, with the state it reaches from solved. There are about
+/// 13.35^k sequences of length k over all 18 moves.
+#[derive(Debug, Clone)]
+pub struct AllAlgs<'a> {
+    moves: &'a [Move],
+    canonical: CanonicalSeq,
+    max_len: usize,
+    /// Length being enumerated, or `None` before the empty sequence.
+    len: Option<usize>,
+    /// Indices into `moves` of the current sequence.
+    path: Vec<usize>,
+    /// The state after each prefix of `path`, starting with solved.
+    states: Vec<Cube>,
+}
+
+impl AllAlgs<'static> {
+    pub fn new(max_len: usize) -> Self {
+        AllAlgs::with_moves(&Move::ALL, max_len)
+    }
+}
+
+impl<'a> AllAlgs<'a> {
+    /// Sequences of `moves` only.
+    pub fn with_moves(moves: &'a [Move], max_len: usize) -> Self {
+        Self {
+            moves,
+            canonical: CanonicalSeq::new::<Cube>(moves),
+            max_len,
+            len: None,
+            path: Vec::with_capacity(max_len),
+            states: alloc::vec![Cube::identity()],
+        }
+    }
+
+    /// Move `path` to the next sequence of length `len`, or clear it and
+    /// return false if there is none.
+    fn advance(&mut self, len: usize) -> bool {
+        if len == 0 || len > self.max_len {
+            return false;
+        }
+        // The first move to try at the current depth is the one after this.
+        let mut after = self.path.pop();
+        self.states.truncate(self.path.len() + 1);
+        loop {
+            let mask = self.canonical.allowed(self.path.last().copied())
+                & after.map_or(u64::MAX, |i| {
+                    u64::MAX.checked_shl(i as u32 + 1).unwrap_or(0)
+                });
+            if mask != 0 {
+                let i = mask.trailing_zeros() as usize;
+                let state = self.states[self.path.len()];
+                self.path.push(i);
+                self.states.push(state.compose(self.moves[i].cube()));
+                if self.path.len() == len {
+                    return true;
+                }
+                after = None;
+            } else {
+                match self.path.pop() {
+                    Some(i) => after = Some(i),
+                    None => return false,
+                }
+                self.states.pop();
+            }
+        }
+    }
+}
+
+impl Iterator for AllAlgs<'_> {
+    type Item = (Alg, Cube);
+
+    fn next(&mut self) -> Option<(Alg, Cube)> {
+        match self.len {
+            None => self.len = Some(0),
+            Some(mut len) => {
+                while !self.advance(len) {
+                    if len >= self.max_len {
+                        self.len = Some(self.max_len + 1);
+                        return None;
+                    }
+                    len += 1;
+                    self.len = Some(len);
+                }
+            }
+        }
+        let alg = self.path.iter().map(|&i| self.moves[i]).collect();
+        Some((Alg(alg), *self.states.last().unwrap()))
+    }
+}
+
+impl From<Vec<Move>> for Alg {
+    fn from(moves: Vec<Move>) -> Self {
+        Self(moves)
+    }
+}
+
+impl fmt::Display for Alg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, m) in self.0.iter().enumerate() {
+            if i != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", m)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for Alg {
+    type Err = ParseMoveError;
+
+    /// Whitespace-separated moves, e.g. `R U R' U'`.
+    fn from_str(s: &str) -> Result<Self, ParseMoveError> {
+        s.split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alg(s: &str) -> Alg {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn simplify_merges_adjacent_turns() {
+        assert_eq!(alg("R R U2 U2 F F' L").simplify(), alg("R2 L"));
+        assert_eq!(alg("U D U'").simplify(), alg("U D U'"));
+    }
+
+    #[test]
+    fn parse_into_fills_the_front_of_the_buffer() {
+        let mut buf = [Move::U; 4];
+        assert_eq!(parse_into(" R U' F2 ", &mut buf), Ok(3));
+        assert_eq!(&buf[..3], alg("R U' F2").moves());
+        assert_eq!(parse_into("", &mut buf), Ok(0));
+        assert_eq!(
+            parse_into("R U x U'", &mut buf),
+            Err(ParseIntoError::NotAMove(4))
+        );
+        assert_eq!(
+            parse_into("R U R' U' R", &mut buf),
+            Err(ParseIntoError::TooLong)
+        );
+    }
+
+    #[test]
+    fn simplify_commuting_merges_across_the_opposite_face() {
+        assert_eq!(alg("U D U'").simplify_commuting(), alg("D"));
+        assert_eq!(alg("R L R L'").simplify_commuting(), alg("R2"));
+        assert_eq!(alg("U D F U").simplify_commuting(), alg("U D F U"));
+        for s in ["R U D R' U' D'", "F B F' R L2 R L2"] {
+            let a = alg(s);
+            assert_eq!(a.simplify_commuting().cube(), a.cube());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips() {
+        let a = alg("R U R' U'");
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, "\"R U R' U'\"");
+        assert_eq!(serde_json::from_str::<Alg>(&json).unwrap(), a);
+        let m: Move = serde_json::from_str("\"F2\"").unwrap();
+        assert_eq!(m, Move::F2);
+        assert_eq!(serde_json::to_string(&Move::Bi).unwrap(), "\"B'\"");
+        let cube = a.cube();
+        let json = serde_json::to_string(&cube).unwrap();
+        assert_eq!(serde_json::from_str::<Cube>(&json).unwrap(), cube);
+        assert!(serde_json::from_str::<Alg>("\"R X\"").is_err());
+        let twisted = json.replacen('U', "R", 1);
+        assert!(serde_json::from_str::<Cube>(&twisted).is_err());
+    }
+}
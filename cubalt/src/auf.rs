@@ -0,0 +1,75 @@
+//! Adjustments of the U face around last-layer algs.
+//!
+//! A last-layer alg solves its case from one angle only, and may leave the
+//! U face turned: stitched into a solution it needs a U turn before it (the
+//! pre-AUF) and one after it (the post-AUF). Two states are the same case
+//! when one is the other with U turns on either side.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::notation::Move;
+use crate::pattern::Goal;
+use alloc::vec::Vec;
+
+/// No turn, then U, U2 and U'.
+pub const AUFS: [Option<Move>; 4] =
+    [None, Some(Move::U), Some(Move::U2), Some(Move::Ui)];
+
+/// The state `auf` reaches from solved.
+pub fn cube(auf: Option<Move>) -> Cube {
+    auf.map_or_else(Cube::identity, |m| *m.cube())
+}
+
+/// `cube` with `pre` before it and `post` after it.
+pub fn around(pre: Option<Move>, c: &Cube, post: Option<Move>) -> Cube {
+    cube(pre).compose(c).compose(&cube(post))
+}
+
+/// The turns to make before and after `alg` on `state` to reach `goal`,
+/// the fewest first.
+pub fn find<G: Goal<Cube>>(
+    state: &Cube,
+    alg: &Cube,
+    goal: &G,
+) -> Option<(Option<Move>, Option<Move>)> {
+    let mut pairs: Vec<_> = AUFS
+        .iter()
+        .flat_map(|&pre| AUFS.iter().map(move |&post| (pre, post)))
+        .collect();
+    pairs
+        .sort_by_key(|&(pre, post)| pre.is_some() as u8 + post.is_some() as u8);
+    pairs.into_iter().find(|&(pre, post)| {
+        let c = state.compose(&cube(pre)).compose(alg).compose(&cube(post));
+        goal.is_goal(&c)
+    })
+}
+
+/// `alg` with the U turns that make it take `state` to `goal`, merged into
+/// its first and last moves where they cancel.
+pub fn stitch<G: Goal<Cube>>(state: &Cube, alg: &Alg, goal: &G) -> Option<Alg> {
+    let (pre, post) = find(state, &alg.cube(), goal)?;
+    let mut moves: Vec<Move> = pre.into_iter().collect();
+    moves.extend_from_slice(alg.moves());
+    moves.extend(post);
+    Some(Alg(moves).simplify())
+}
+
+/// The least of `cube`'s states up to AUF, with the turns before and after
+/// `cube` that give it: the same for every state of a case.
+pub fn normalize(cube: &Cube) -> (Cube, Option<Move>, Option<Move>) {
+    let key = |c: &Cube| {
+        let edges = c.edges().iter().map(|e| e.0);
+        edges
+            .chain(c.corners().iter().map(|c| c.0))
+            .collect::<Vec<_>>()
+    };
+    AUFS.iter()
+        .flat_map(|&pre| AUFS.iter().map(move |&post| (pre, post)))
+        .map(|(pre, post)| (around(pre, cube, post), pre, post))
+        .min_by_key(|(c, _, _)| key(c))
+        .expect("sixteen candidates")
+}
+
+/// Whether `a` and `b` differ only by U turns before and after.
+pub fn same_up_to_auf(a: &Cube, b: &Cube) -> bool {
+    normalize(a).0 == normalize(b).0
+}
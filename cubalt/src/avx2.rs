@@ -1,7 +1,7 @@
 #![cfg(all(target_feature = "avx", target_feature = "avx2",))]
 #![allow(overflowing_literals)]
 use crate::types::*;
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
 
 pub fn identity() -> m256i {
     unsafe {
@@ -98,7 +98,7 @@ pub fn compose_mirror(a: m256i, b: m256i) -> m256i {
 pub fn xor_edge_orient(v: m256i, eori: Eori) -> m256i {
     unsafe {
         let mut vori: m256i = _mm256_shuffle_epi8(
-            _mm256_set1_epi32(std::mem::transmute(eori.0)),
+            _mm256_set1_epi32(core::mem::transmute(eori.0)),
             _mm256_set_epi64x(-1, -1, 0xffffffff01010101, 0),
         );
         vori = _mm256_or_si256(vori, _mm256_set1_epi64x(!0x8040201008040201));
@@ -114,7 +114,7 @@ pub fn corner_orient_raw(v: m256i) -> Cori {
             _mm256_slli_epi32(v, 3),
             _mm256_slli_epi32(v, 2),
         );
-        Cori(std::mem::transmute::<i32, u32>(_mm256_movemask_epi8(vori)) >> 16)
+        Cori(core::mem::transmute::<i32, u32>(_mm256_movemask_epi8(vori)) >> 16)
     }
 }
 
@@ -169,7 +169,7 @@ pub fn unrank_corner_orient(cori: Cori) -> i64 {
         let vshift: m256i = _mm256_set_epi32(4, 4, 4, 4, 0, 0, 0, 0);
 
         // Divide by powers of 3 (1, 3, 9, ..., 729)
-        let vcorient: m256i = _mm256_set1_epi32(std::mem::transmute(cori.0));
+        let vcorient: m256i = _mm256_set1_epi32(core::mem::transmute(cori.0));
         let mut vco: m256i = _mm256_mulhi_epu16(vcorient, vpow3_reciprocal);
         vco = _mm256_srlv_epi32(vco, vshift);
 
@@ -194,8 +194,9 @@ pub fn unrank_corner_orient(cori: Cori) -> i64 {
         sum += sum >> 16;
         sum += sum >> 8;
 
-        // Insert the last corner
-        co |= (0x4924924924924924 >> sum) & 3;
+        // Insert the last corner; only the low byte of sum is meaningful,
+        // and unlike x86 Rust does not mask the shift count for us
+        co |= (0x4924924924924924 >> (sum & 0x3f)) & 3;
 
         co << 4
     }
@@ -230,3 +231,69 @@ pub fn parity(v: m256i) -> bool {
         return (_popcnt32(_mm256_movemask_epi8(parity) ^ 0x5f005f) & 1) != 0;
     }
 }
+
+/// Bytes 0..32 in order: the identity for `permute_bytes`.
+pub fn identity_bytes() -> m256i {
+    unsafe {
+        _mm256_set_epi64x(
+            0x1f1e1d1c1b1a1918,
+            0x1716151413121110,
+            0x0f0e0d0c0b0a0908,
+            0x0706050403020100,
+        )
+    }
+}
+
+/// Full-width byte permutation, r[i] = a[idx[i]] for idx[i] in 0..32.
+///
+/// `_mm256_shuffle_epi8` cannot cross the 128-bit lanes, so shuffle both
+/// halves of `a` broadcast to each lane and pick per byte.
+#[inline(always)]
+pub fn permute_bytes(a: m256i, idx: m256i) -> m256i {
+    unsafe {
+        let lo = _mm256_permute2x128_si256(a, a, 0x00);
+        let hi = _mm256_permute2x128_si256(a, a, 0x11);
+        let from_hi = _mm256_cmpgt_epi8(idx, _mm256_set1_epi8(15));
+        _mm256_blendv_epi8(
+            _mm256_shuffle_epi8(lo, idx),
+            _mm256_shuffle_epi8(hi, idx),
+            from_hi,
+        )
+    }
+}
+
+/// Inverse of a full-width byte permutation without orientation bits.
+pub fn invert_bytes(v: m256i) -> m256i {
+    unsafe {
+        let mut vi: m256i = _mm256_setzero_si256();
+        for i in 0..32 {
+            let vtrial: m256i = _mm256_set1_epi8(i);
+            let vcorrect: m256i =
+                _mm256_cmpeq_epi8(identity_bytes(), permute_bytes(v, vtrial));
+            vi = _mm256_or_si256(vi, _mm256_and_si256(vtrial, vcorrect));
+        }
+        vi
+    }
+}
+
+/// Whether a and b agree on every bit set in mask.
+pub fn masked_equals(a: m256i, b: m256i, mask: m256i) -> bool {
+    unsafe { _mm256_testz_si256(_mm256_xor_si256(a, b), mask) != 0 }
+}
+
+/// Whether every bit of v is zero.
+pub fn is_zero(v: m256i) -> bool {
+    unsafe { _mm256_testz_si256(v, v) != 0 }
+}
+
+/// Fast non-cryptographic hash of a cube. The fourth word is always
+/// identity padding and is skipped.
+#[inline(always)]
+pub fn hash(v: m256i) -> u64 {
+    let mut h = (edges_low(v) as u64).wrapping_mul(0x9e3779b97f4a7c15)
+        ^ (edges_high(v) as u64).wrapping_mul(0xc2b2ae3d27d4eb4f)
+        ^ (corners(v) as u64).wrapping_mul(0x165667b19e3779f9);
+    h ^= h >> 29;
+    h = h.wrapping_mul(0xbf58476d1ce4e5b9);
+    h ^ (h >> 32)
+}
@@ -0,0 +1,232 @@
+//! Axial moves: turns of two opposite faces at once, such as `U D'`.
+//!
+//! Robots with a motor per face, and some human methods, make these as
+//! one action. `AxialMove` has the 18 face turns and the 27 pairs;
+//! `AxialCube` is the 3x3 with all 45 as its moves, so that the generic
+//! searches and tables count a pair as one move; `AxialCoord` carries the
+//! coordinates of `Cube` over to it.
+use crate::alg::Alg;
+use crate::coord::{Coordinate, Corners};
+use crate::cube::Cube;
+use crate::notation::{Move, ParseMoveError};
+use crate::puzzle::Puzzle;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+/// A face turn, or turns of a U, R or F face and its opposite together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AxialMove {
+    Face(Move),
+    /// The U, R or F turn first.
+    Pair(Move, Move),
+}
+
+use AxialMove::Pair;
+use Move::*;
+
+impl AxialMove {
+    /// The face turns in `Move::ALL` order, then the pairs by axis and
+    /// powers.
+    #[rustfmt::skip]
+    pub const ALL: [AxialMove; 45] = [
+        AxialMove::Face(U), AxialMove::Face(U2), AxialMove::Face(Ui),
+        AxialMove::Face(R), AxialMove::Face(R2), AxialMove::Face(Ri),
+        AxialMove::Face(F), AxialMove::Face(F2), AxialMove::Face(Fi),
+        AxialMove::Face(D), AxialMove::Face(D2), AxialMove::Face(Di),
+        AxialMove::Face(L), AxialMove::Face(L2), AxialMove::Face(Li),
+        AxialMove::Face(B), AxialMove::Face(B2), AxialMove::Face(Bi),
+        Pair(U, D), Pair(U, D2), Pair(U, Di),
+        Pair(U2, D), Pair(U2, D2), Pair(U2, Di),
+        Pair(Ui, D), Pair(Ui, D2), Pair(Ui, Di),
+        Pair(R, L), Pair(R, L2), Pair(R, Li),
+        Pair(R2, L), Pair(R2, L2), Pair(R2, Li),
+        Pair(Ri, L), Pair(Ri, L2), Pair(Ri, Li),
+        Pair(F, B), Pair(F, B2), Pair(F, Bi),
+        Pair(F2, B), Pair(F2, B2), Pair(F2, Bi),
+        Pair(Fi, B), Pair(Fi, B2), Pair(Fi, Bi),
+    ];
+
+    /// Turns of `a` and `b` together, in either order, if they are on
+    /// opposite faces.
+    pub fn pair(a: Move, b: Move) -> Option<Self> {
+        if a.face().opposite() != b.face() {
+            return None;
+        }
+        Some(if (a.face() as u8) < 3 {
+            Pair(a, b)
+        } else {
+            Pair(b, a)
+        })
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            AxialMove::Face(m) => m.index(),
+            Pair(a, b) => {
+                18 + a.face() as usize * 9
+                    + (a.power() as usize - 1) * 3
+                    + (b.power() as usize - 1)
+            }
+        }
+    }
+
+    /// 0 for U/D, 1 for R/L, 2 for F/B.
+    pub fn axis(self) -> u8 {
+        match self {
+            AxialMove::Face(m) | Pair(m, _) => m.face().axis(),
+        }
+    }
+
+    pub fn inverse(self) -> Self {
+        match self {
+            AxialMove::Face(m) => AxialMove::Face(m.inverse()),
+            Pair(a, b) => Pair(a.inverse(), b.inverse()),
+        }
+    }
+
+    /// The face turns making up the move.
+    pub fn moves(self) -> impl Iterator<Item = Move> {
+        let (a, b) = match self {
+            AxialMove::Face(m) => (m, None),
+            Pair(a, b) => (a, Some(b)),
+        };
+        core::iter::once(a).chain(b)
+    }
+
+    /// The state reached by applying this move to the solved cube.
+    pub fn cube(self) -> &'static Cube {
+        &AXIAL_CUBES[self.index()]
+    }
+}
+
+/// Move cubes in `AxialMove::ALL` order.
+static AXIAL_CUBES: [Cube; 45] = axial_cubes();
+
+const fn axial_cubes() -> [Cube; 45] {
+    let moves = Cube::moves();
+    let mut cubes = [moves[0]; 45];
+    let mut i = 0;
+    while i < 18 {
+        cubes[i] = moves[i];
+        i += 1;
+    }
+    while i < 45 {
+        let (axis, a, b) = ((i - 18) / 9, (i - 18) / 3 % 3, (i - 18) % 3);
+        cubes[i] = moves[axis * 3 + a].const_compose(&moves[axis * 3 + 9 + b]);
+        i += 1;
+    }
+    cubes
+}
+
+impl fmt::Display for AxialMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AxialMove::Face(m) => write!(f, "{}", m),
+            Pair(a, b) => write!(f, "({} {})", a, b),
+        }
+    }
+}
+
+/// A face turn, or two in parentheses: `U`, `(U D')`.
+impl core::str::FromStr for AxialMove {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, ParseMoveError> {
+        let inner = match s.strip_prefix('(') {
+            Some(rest) => rest.strip_suffix(')'),
+            None => return Ok(AxialMove::Face(s.parse()?)),
+        };
+        let mut parts = inner.map(str::split_whitespace).into_iter().flatten();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(a), Some(b), None) => AxialMove::pair(a.parse()?, b.parse()?),
+            _ => None,
+        }
+        .ok_or_else(|| ParseMoveError(s.to_string()))
+    }
+}
+
+/// `alg` with each turn of a face and the next, on the opposite face,
+/// made one move.
+pub fn group(alg: &Alg) -> Vec<AxialMove> {
+    let mut out = Vec::with_capacity(alg.len());
+    let mut moves = alg.moves().iter().peekable();
+    while let Some(&m) = moves.next() {
+        match moves.peek().and_then(|&&n| AxialMove::pair(m, n)) {
+            Some(p) => {
+                moves.next();
+                out.push(p);
+            }
+            None => out.push(AxialMove::Face(m)),
+        }
+    }
+    out
+}
+
+/// Length of `alg` counting axial pairs as one move.
+pub fn length(alg: &Alg) -> usize {
+    group(alg).len()
+}
+
+/// The face turns of `moves`, each pair U, R or F first.
+pub fn to_alg(moves: &[AxialMove]) -> Alg {
+    Alg(moves.iter().flat_map(|m| m.moves()).collect())
+}
+
+/// The 3x3 moved by `AxialMove`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AxialCube(pub Cube);
+
+impl Puzzle for AxialCube {
+    type Move = AxialMove;
+    type Coord = AxialCoord<Corners>;
+
+    fn identity() -> Self {
+        Self(Cube::identity())
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Self(self.0.compose(&other.0))
+    }
+
+    fn invert(&self) -> Self {
+        Self(self.0.invert())
+    }
+
+    fn moves() -> &'static [AxialMove] {
+        &AxialMove::ALL
+    }
+
+    fn move_state(m: AxialMove) -> Self {
+        Self(*m.cube())
+    }
+
+    /// One layer per axis: any two moves on an axis make at most one.
+    fn move_axis_layer(m: AxialMove) -> (u8, u8) {
+        (m.axis(), 0)
+    }
+
+    fn sym_count() -> usize {
+        48
+    }
+
+    fn sym_conjugate(&self, s: usize) -> Self {
+        Self(self.0.sym_conjugate(s))
+    }
+}
+
+/// Coordinate `C` of `Cube`, on `AxialCube`.
+pub struct AxialCoord<C>(PhantomData<C>);
+
+impl<C: Coordinate<Cube>> Coordinate<AxialCube> for AxialCoord<C> {
+    const SIZE: usize = C::SIZE;
+
+    fn rank(p: &AxialCube) -> usize {
+        C::rank(&p.0)
+    }
+
+    fn unrank(c: usize) -> AxialCube {
+        AxialCube(C::unrank(c))
+    }
+}
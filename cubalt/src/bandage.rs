@@ -0,0 +1,131 @@
+//! Bandaged cubes: pieces fused into blocks that no turn may split.
+//!
+//! A block is written as the names of its pieces at their home slots,
+//! letters in any order: `U` for a center, `UR` for an edge, `URF` for a
+//! corner. Blocks are separated by commas, so a bandaged 1x2x2 and a
+//! fused pair are `UFR UF UR U, DF DFR`. A turn is allowed when each block
+//! lies wholly inside or wholly outside the turning layer.
+//!
+//! The same check keeps a solved block intact while searching: fuse the
+//! block, and the search never turns a layer through it.
+use crate::cube::Cube;
+use crate::cycles::{find_letters, CORNER_NAMES, EDGE_NAMES};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::notation::{Face, Move};
+use crate::search::Constraint;
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece {
+    Center(Face),
+    Edge(u8),
+    Corner(u8),
+}
+
+/// A set of blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bandage {
+    blocks: Vec<Vec<Piece>>,
+}
+
+/// A piece name in a bandage spec that names no piece.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBandageError(pub String);
+
+impl fmt::Display for ParseBandageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a piece: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBandageError {}
+
+fn parse_piece(name: &str) -> Option<Piece> {
+    let s = name.as_bytes();
+    match s.len() {
+        1 => Face::ALL
+            .iter()
+            .find(|f| f.to_char() as u8 == s[0])
+            .map(|&f| Piece::Center(f)),
+        2 => (0..12)
+            .find(|&i| find_letters(s, 0, 2, &EDGE_NAMES[i]).is_some())
+            .map(|i| Piece::Edge(i as u8)),
+        3 => (0..8)
+            .find(|&i| find_letters(s, 0, 3, &CORNER_NAMES[i]).is_some())
+            .map(|i| Piece::Corner(i as u8)),
+        _ => None,
+    }
+}
+
+impl Bandage {
+    /// No blocks: every turn is allowed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Whether piece `p` of `cube` is in the layer of `face`.
+    fn in_layer(cube: &Cube, p: Piece, face: Face) -> bool {
+        let c = face.to_char() as u8;
+        match p {
+            Piece::Center(f) => f == face,
+            Piece::Edge(e) => {
+                let slot = cube
+                    .edges()
+                    .iter()
+                    .position(|x| EDGES.piece(x.0) == e)
+                    .expect("every edge has a slot");
+                EDGE_NAMES[slot].contains(&c)
+            }
+            Piece::Corner(k) => {
+                let slot = cube
+                    .corners()
+                    .iter()
+                    .position(|x| CORNERS.piece(x.0) == k)
+                    .expect("every corner has a slot");
+                CORNER_NAMES[slot].contains(&c)
+            }
+        }
+    }
+
+    /// Whether turning `m` in `cube` keeps every block together.
+    pub fn allows(&self, cube: &Cube, m: Move) -> bool {
+        self.blocks.iter().all(|block| {
+            let inside = block
+                .iter()
+                .filter(|&&p| Self::in_layer(cube, p, m.face()))
+                .count();
+            inside == 0 || inside == block.len()
+        })
+    }
+}
+
+impl Constraint<Cube> for Bandage {
+    fn allows(&self, cube: &Cube, m: Move) -> bool {
+        Bandage::allows(self, cube, m)
+    }
+}
+
+impl core::str::FromStr for Bandage {
+    type Err = ParseBandageError;
+
+    fn from_str(s: &str) -> Result<Self, ParseBandageError> {
+        let mut blocks = Vec::new();
+        for block in s.split(',') {
+            let pieces = block
+                .split_whitespace()
+                .map(|name| {
+                    parse_piece(name)
+                        .ok_or_else(|| ParseBandageError(name.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if !pieces.is_empty() {
+                blocks.push(pieces);
+            }
+        }
+        Ok(Self { blocks })
+    }
+}
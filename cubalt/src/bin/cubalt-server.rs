@@ -0,0 +1,479 @@
+//! HTTP front end: the solver for programs in other languages.
+//!
+//! Every endpoint takes a POST with a JSON object and answers with one;
+//! errors are `{"error": "..."}` with a 4xx status. The tables are built
+//! once, before the server listens, and shared by all requests.
+use cubalt::alg::Alg;
+use cubalt::context::{Config, CubeContext};
+use cubalt::cube::Cube;
+use cubalt::facelet::Facelets;
+use cubalt::random::Rng;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+usage: cubalt-server [--addr HOST:PORT] [--threads N]
+
+endpoints (POST, JSON in and out):
+  /scramble  {\"seed\"?: N, \"count\"?: N}
+             -> {\"seed\", \"scrambles\": [...]}
+  /solve     {\"cube\": CUBE, \"max\"?: N, \"deadline_ms\"?: N}
+             -> {\"solution\", \"length\", \"time_ms\"}
+             max is 1 to 21, the default; deadline_ms is at most 10000,
+             and 5000 if not given. With a deadline the shortest solution
+             found in time is returned, else the first.
+  /convert   {\"cube\": CUBE} -> {\"facelets\", \"alg\"}
+  /health    (GET) -> {\"status\": \"ok\"}
+
+A CUBE is a 54-letter facelet string (URFDLB) or an alg applied to the
+solved cube. Defaults: 127.0.0.1:8080, 4 threads.";
+
+/// Largest request body read.
+const MAX_BODY: usize = 1 << 16;
+/// Most scrambles one request may ask for.
+const MAX_COUNT: u64 = 1000;
+/// Longest a solve may take, so that no request holds a worker for long.
+const MAX_DEADLINE_MS: u64 = 10_000;
+/// Time given to a solve that asks for none.
+const DEFAULT_DEADLINE_MS: u64 = 5_000;
+
+fn fail(msg: &str) -> ! {
+    eprintln!("cubalt-server: {}", msg);
+    process::exit(1)
+}
+
+/// A JSON value of a request: only flat objects are accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Json {
+    Null,
+    Bool(bool),
+    /// As written, so that seeds keep all 64 bits.
+    Number(String),
+    Str(String),
+}
+
+/// Parses a JSON object whose values are strings, numbers, booleans or
+/// null.
+fn parse_object(s: &str) -> Result<Vec<(String, Json)>, String> {
+    let mut p = Parser {
+        chars: s.chars().collect(),
+        i: 0,
+    };
+    let fields = p.object()?;
+    p.space();
+    if p.i != p.chars.len() {
+        return Err("trailing characters after the object".to_string());
+    }
+    Ok(fields)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    i: usize,
+}
+
+impl Parser {
+    fn space(&mut self) {
+        while self.chars.get(self.i).is_some_and(|c| c.is_whitespace()) {
+            self.i += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.space();
+        if self.chars.get(self.i) == Some(&c) {
+            self.i += 1;
+            Ok(())
+        } else {
+            Err(format!("expected {:?} at character {}", c, self.i))
+        }
+    }
+
+    fn object(&mut self) -> Result<Vec<(String, Json)>, String> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.space();
+        if self.chars.get(self.i) == Some(&'}') {
+            self.i += 1;
+            return Ok(fields);
+        }
+        loop {
+            self.space();
+            let name = self.string()?;
+            self.expect(':')?;
+            fields.push((name, self.value()?));
+            self.space();
+            match self.chars.get(self.i) {
+                Some(',') => self.i += 1,
+                Some('}') => {
+                    self.i += 1;
+                    return Ok(fields);
+                }
+                _ => return Err(format!("expected , or }} at {}", self.i)),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.space();
+        let rest: String = self.chars[self.i..].iter().take(5).collect();
+        for (word, v) in [
+            ("null", Json::Null),
+            ("true", Json::Bool(true)),
+            ("false", Json::Bool(false)),
+        ] {
+            if rest.starts_with(word) {
+                self.i += word.len();
+                return Ok(v);
+            }
+        }
+        match self.chars.get(self.i) {
+            Some('"') => self.string().map(Json::Str),
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let start = self.i;
+                while self
+                    .chars
+                    .get(self.i)
+                    .is_some_and(|c| "+-.eE".contains(*c) || c.is_ascii_digit())
+                {
+                    self.i += 1;
+                }
+                let n: String = self.chars[start..self.i].iter().collect();
+                match n.parse::<f64>() {
+                    Ok(_) => Ok(Json::Number(n)),
+                    Err(_) => Err(format!("bad number {:?}", n)),
+                }
+            }
+            _ => Err(format!("expected a value at character {}", self.i)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let c = *self.chars.get(self.i).ok_or("unterminated string")?;
+            self.i += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let e = *self.chars.get(self.i).ok_or("bad escape")?;
+                    self.i += 1;
+                    match e {
+                        '"' | '\\' | '/' => out.push(e),
+                        'n' => out.push('\n'),
+                        't' => out.push('\t'),
+                        'r' => out.push('\r'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let hex: String = self
+                                .chars
+                                .get(self.i..self.i + 4)
+                                .ok_or("bad \\u escape")?
+                                .iter()
+                                .collect();
+                            self.i += 4;
+                            let code = u32::from_str_radix(&hex, 16)
+                                .map_err(|_| "bad \\u escape")?;
+                            out.push(
+                                char::from_u32(code).unwrap_or('\u{fffd}'),
+                            );
+                        }
+                        _ => return Err(format!("bad escape \\{}", e)),
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A request's fields, with typed lookups.
+struct Fields(Vec<(String, Json)>);
+
+impl Fields {
+    fn get(&self, name: &str) -> Option<&Json> {
+        self.0.iter().find(|f| f.0 == name).map(|f| &f.1)
+    }
+
+    fn string(&self, name: &str) -> Result<&str, String> {
+        match self.get(name) {
+            Some(Json::Str(s)) => Ok(s),
+            Some(_) => Err(format!("{} must be a string", name)),
+            None => Err(format!("{} is required", name)),
+        }
+    }
+
+    fn number(&self, name: &str) -> Result<Option<u64>, String> {
+        match self.get(name) {
+            None | Some(Json::Null) => Ok(None),
+            Some(Json::Number(n)) if n.parse::<u64>().is_ok() => {
+                Ok(n.parse().ok())
+            }
+            Some(_) => Err(format!("{} must be a non-negative integer", name)),
+        }
+    }
+}
+
+fn is_facelets(s: &str) -> bool {
+    s.len() == 54 && s.chars().all(|c| "URFDLB".contains(c))
+}
+
+fn parse_cube(s: &str) -> Result<Cube, String> {
+    if is_facelets(s) {
+        s.parse::<Facelets>()
+            .and_then(|f| f.to_cube())
+            .map_err(|e| e.to_string())
+    } else {
+        s.parse::<Alg>()
+            .map(|a| a.cube())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A response: status and JSON body.
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, msg: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(msg)),
+        }
+    }
+}
+
+fn scramble(context: &CubeContext, f: &Fields) -> Result<String, String> {
+    let seed = f
+        .number("seed")?
+        .unwrap_or_else(|| Rng::from_entropy().next_u64());
+    let count = f.number("count")?.unwrap_or(1);
+    if count > MAX_COUNT {
+        return Err(format!("count is at most {}", MAX_COUNT));
+    }
+    let scrambles: Vec<String> = context
+        .scrambles(seed)
+        .take(count as usize)
+        .map(|a| json_string(&a.to_string()))
+        .collect();
+    Ok(format!(
+        "{{\"seed\":{},\"scrambles\":[{}]}}",
+        seed,
+        scrambles.join(",")
+    ))
+}
+
+fn solve(context: &CubeContext, f: &Fields) -> Result<String, String> {
+    let cube = parse_cube(f.string("cube")?)?;
+    let limit = context.config().max_length;
+    let max = f.number("max")?.unwrap_or(limit.into());
+    if !(1..=limit.into()).contains(&max) {
+        return Err(format!("max must be from 1 to {}", limit));
+    }
+    let max = max as u32;
+    let deadline = f.number("deadline_ms")?;
+    if deadline.is_some_and(|ms| ms > MAX_DEADLINE_MS) {
+        return Err(format!("deadline_ms is at most {}", MAX_DEADLINE_MS));
+    }
+    let start = Instant::now();
+    let alg = match deadline {
+        Some(ms) => context
+            .solve_with_deadline(&cube, Duration::from_millis(ms))
+            .filter(|alg| alg.len() <= max as usize),
+        None => context.solver().solve_until(
+            &cube,
+            max,
+            Duration::from_millis(DEFAULT_DEADLINE_MS),
+        ),
+    }
+    .ok_or_else(|| {
+        format!("no solution of at most {} moves found in time", max)
+    })?;
+    Ok(format!(
+        "{{\"solution\":{},\"length\":{},\"time_ms\":{:.3}}}",
+        json_string(&alg.to_string()),
+        alg.len(),
+        start.elapsed().as_secs_f64() * 1000.0
+    ))
+}
+
+fn convert(context: &CubeContext, f: &Fields) -> Result<String, String> {
+    let s = f.string("cube")?;
+    let (facelets, alg) = if is_facelets(s) {
+        let cube = parse_cube(s)?;
+        let alg = context.solve(&cube.invert()).ok_or("no solution found")?;
+        (s.to_string(), alg)
+    } else {
+        let alg = s.parse::<Alg>().map_err(|e| e.to_string())?;
+        (Facelets::from_cube(&alg.cube()).to_string(), alg)
+    };
+    Ok(format!(
+        "{{\"facelets\":{},\"alg\":{}}}",
+        json_string(&facelets),
+        json_string(&alg.to_string())
+    ))
+}
+
+fn route(
+    context: &CubeContext,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> Response {
+    let handler = match (method, path) {
+        ("GET", "/health") => {
+            return Response::ok("{\"status\":\"ok\"}".to_string())
+        }
+        ("POST", "/scramble") => scramble,
+        ("POST", "/solve") => solve,
+        ("POST", "/convert") => convert,
+        (_, "/health" | "/scramble" | "/solve" | "/convert") => {
+            return Response::error(405, "method not allowed")
+        }
+        _ => return Response::error(404, "no such endpoint"),
+    };
+    let fields =
+        match parse_object(if body.trim().is_empty() { "{}" } else { body }) {
+            Ok(fields) => Fields(fields),
+            Err(e) => return Response::error(400, &format!("bad JSON: {}", e)),
+        };
+    match handler(context, &fields) {
+        Ok(body) => Response::ok(body),
+        Err(e) => Response::error(400, &e),
+    }
+}
+
+/// Reads one request, answers it and closes the connection.
+fn handle(context: &CubeContext, stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or("").to_string(),
+        parts.next().unwrap_or("").to_string(),
+    );
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(usize::MAX);
+            }
+        }
+    }
+    let response = if length > MAX_BODY {
+        Response::error(413, "request body too large")
+    } else {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        match String::from_utf8(body) {
+            Ok(body) => route(context, &method, &path, &body),
+            Err(_) => Response::error(400, "body is not UTF-8"),
+        }
+    };
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Payload Too Large",
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Takes `--name value` out of `args`.
+fn take_string_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let i = args.iter().position(|a| a == name)?;
+    if i + 1 >= args.len() {
+        fail(&format!("{} needs a value", name));
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Some(value)
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let addr = take_string_option(&mut args, "--addr")
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let threads = take_string_option(&mut args, "--threads").map_or(4, |t| {
+        t.parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .unwrap_or_else(|| fail(&format!("bad thread count {:?}", t)))
+    });
+    if let Some(arg) = args.first() {
+        fail(&format!("unexpected argument {:?}\n\n{}", arg, USAGE));
+    }
+    let context = Arc::new(CubeContext::new(Config::default()));
+    let start = Instant::now();
+    context.solver();
+    eprintln!("cubalt-server: built tables in {:.2?}", start.elapsed());
+    let listener = TcpListener::bind(&addr)
+        .unwrap_or_else(|e| fail(&format!("cannot listen on {}: {}", addr, e)));
+    eprintln!("cubalt-server: listening on {}", addr);
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let listener = listener.try_clone().expect("cloning the listener");
+            let context = Arc::clone(&context);
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    if let Err(e) = handle(&context, stream) {
+                        eprintln!("cubalt-server: {}", e);
+                    }
+                }
+            })
+        })
+        .collect();
+    for w in workers {
+        let _ = w.join();
+    }
+}
@@ -0,0 +1,663 @@
+//! Command-line front end.
+use cubalt::alg::Alg;
+use cubalt::context::{Config, CubeContext, Parallelism};
+use cubalt::coord::{
+    Coordinate, CornerSlicePerm, EdgeSlicePerm, FlipSlice, TwistSlice,
+};
+use cubalt::cube::Cube;
+use cubalt::facelet::{Color, FaceletError, Facelets, PartialFacelets};
+use cubalt::prune::PruneTable;
+use cubalt::random::Rng;
+use cubalt::render::{self, ColorScheme};
+use cubalt::solver::{Solver, PHASE2_MOVES};
+use cubalt::tablecache::TableCache;
+use std::io::{self, BufRead};
+use std::process;
+use std::time::Instant;
+
+const USAGE: &str = "\
+usage: cubalt <command> [args]
+
+commands:
+  scramble [--seed N] [--count N]   random-state scrambles
+  solve [--max N] <cube>            two-phase solution
+  invert <alg>                      the inverse alg
+  simplify <alg>                    merge and cancel adjacent turns
+  render [--color SCHEME] <cube>    sticker net, in color with a scheme
+                                    (western, japanese or color-blind)
+  convert <cube>                    facelets to an alg, or an alg to facelets
+  tables [generate [DIR]]           build the pruning tables into the table
+                                    cache, or DIR, and report them
+  tables inspect                    build the pruning tables and show how
+                                    many entries are at each depth
+  tables cache                      fill the table cache and list it (in
+                                    $CUBALT_CACHE_DIR, or the user cache)
+  batch [--max N] [--threads N]     solve the <cube> on each line of stdin,
+                                    a solution per line (blank on errors)
+  enter [--max N]                   type in a real cube's stickers face by
+                                    face, checked as you go, and solve it
+
+options:
+  --json                            one JSON object per result line
+  --csv                             a header line, then one row per result
+                                    (not for render)
+
+A <cube> is either a 54-letter facelet string (URFDLB) or an alg applied
+to the solved cube. Scrambles with the same --seed are the same everywhere.";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("cubalt: {}", msg);
+    process::exit(1)
+}
+
+/// Takes `--name value` out of `args`.
+fn take_string_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let i = args.iter().position(|a| a == name)?;
+    if i + 1 >= args.len() {
+        fail(&format!("{} needs a value", name));
+    }
+    let value = args.remove(i + 1);
+    args.remove(i);
+    Some(value)
+}
+
+/// Takes `--name` out of `args`, saying whether it was there.
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    let i = args.iter().position(|a| a == name);
+    if let Some(i) = i {
+        args.remove(i);
+    }
+    i.is_some()
+}
+
+/// Takes `--name N` out of `args`.
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<u64> {
+    let value = take_string_option(args, name)?;
+    Some(value.parse().unwrap_or_else(|_| {
+        fail(&format!("{} needs a number, not {:?}", name, value))
+    }))
+}
+
+/// How results are printed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// A field of a result.
+enum Value {
+    Str(String),
+    Int(u64),
+    Float(f64),
+    List(Vec<u64>),
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Int(n as u64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+
+impl Value {
+    /// The value as text, strings quoted by `quote` and list items
+    /// separated by `sep`.
+    fn render(&self, quote: fn(&str) -> String, sep: &str) -> String {
+        match self {
+            Value::Str(s) => quote(s),
+            Value::Int(n) => n.to_string(),
+            Value::Float(x) => format!("{:.3}", x),
+            Value::List(l) => {
+                l.iter().map(u64::to_string).collect::<Vec<_>>().join(sep)
+            }
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Prints results as one line each: JSON objects, or CSV rows under a
+/// header taken from the first result's field names. Text is printed by
+/// the commands themselves.
+struct Output {
+    format: Format,
+    header_done: bool,
+}
+
+impl Output {
+    fn new(format: Format) -> Self {
+        Self {
+            format,
+            header_done: false,
+        }
+    }
+
+    fn is_text(&self) -> bool {
+        self.format == Format::Text
+    }
+
+    fn record(&mut self, fields: &[(&str, Value)]) {
+        match self.format {
+            Format::Text => {}
+            Format::Json => {
+                let body: Vec<String> = fields
+                    .iter()
+                    .map(|(name, v)| {
+                        let v = match v {
+                            Value::List(_) => {
+                                format!("[{}]", v.render(json_string, ","))
+                            }
+                            _ => v.render(json_string, ","),
+                        };
+                        format!("{}:{}", json_string(name), v)
+                    })
+                    .collect();
+                println!("{{{}}}", body.join(","));
+            }
+            Format::Csv => {
+                if !self.header_done {
+                    let names: Vec<_> = fields.iter().map(|f| f.0).collect();
+                    println!("{}", names.join(","));
+                    self.header_done = true;
+                }
+                let row: Vec<String> = fields
+                    .iter()
+                    .map(|(_, v)| v.render(csv_field, " "))
+                    .collect();
+                println!("{}", row.join(","));
+            }
+        }
+    }
+}
+
+fn millis(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn is_facelets(s: &str) -> bool {
+    s.len() == 54 && s.chars().all(|c| "URFDLB".contains(c))
+}
+
+fn parse_alg(args: &[String]) -> Alg {
+    args.join(" ")
+        .parse::<Alg>()
+        .unwrap_or_else(|e| fail(&e.to_string()))
+}
+
+fn cube_from_str(s: &str) -> Result<Cube, String> {
+    if is_facelets(s) {
+        s.parse::<Facelets>()
+            .and_then(|f| f.to_cube())
+            .map_err(|e| e.to_string())
+    } else {
+        s.parse::<Alg>()
+            .map(|a| a.cube())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn parse_cube(args: &[String]) -> Cube {
+    cube_from_str(&args.join(" ")).unwrap_or_else(|e| fail(&e))
+}
+
+/// Lines of `batch` input solved together, bounding the memory held.
+const BATCH_CHUNK: usize = 1024;
+
+/// Solves stdin a chunk at a time, keeping output in input order.
+fn batch(context: &CubeContext, out: &mut Output) {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut number = 0;
+    loop {
+        let chunk: Vec<String> = lines
+            .by_ref()
+            .take(BATCH_CHUNK)
+            .map(|l| l.unwrap_or_else(|e| fail(&e.to_string())))
+            .collect();
+        if chunk.is_empty() {
+            break;
+        }
+        let parsed: Vec<_> =
+            chunk.iter().map(|l| cube_from_str(l.trim())).collect();
+        let cubes: Vec<Cube> = parsed
+            .iter()
+            .filter_map(|c| c.as_ref().ok())
+            .copied()
+            .collect();
+        let mut solutions = context.solve_batch(&cubes).into_iter();
+        for (line, cube) in chunk.iter().zip(parsed) {
+            number += 1;
+            let result = cube.and_then(|_| {
+                solutions.next().flatten().ok_or_else(|| {
+                    format!(
+                        "no solution within {} moves",
+                        context.config().max_length
+                    )
+                })
+            });
+            if let Err(e) = &result {
+                eprintln!("cubalt: line {}: {}", number, e);
+            }
+            if out.is_text() {
+                println!(
+                    "{}",
+                    result.as_ref().map_or(String::new(), Alg::to_string)
+                );
+            }
+            let (solution, length, error) = match result {
+                Ok(alg) => (alg.to_string(), alg.len(), String::new()),
+                Err(e) => (String::new(), 0, e),
+            };
+            out.record(&[
+                ("line", (number as u64).into()),
+                ("input", line.trim().into()),
+                ("solution", solution.into()),
+                ("length", length.into()),
+                ("error", error.into()),
+            ]);
+        }
+    }
+}
+
+/// How to hold the cube to read each face, in `Color::ALL` order, so the
+/// stickers come in facelet order.
+const FACE_VIEWS: [&str; 6] = [
+    "top face (U), from above, back edge farthest",
+    "right face (R), top edge up",
+    "front face (F), top edge up",
+    "bottom face (D), from below, front edge farthest",
+    "left face (L), top edge up",
+    "back face (B), top edge up",
+];
+
+/// The stickers typed so far, with each letter read as the color of the
+/// center it matches; letters of centers not yet typed are unknown.
+fn entered(faces: &[Option<Vec<char>>; 6]) -> Result<PartialFacelets, String> {
+    let mut colors = Vec::new();
+    for (i, f) in faces.iter().enumerate() {
+        if let Some(f) = f {
+            if let Some(&(_, other)) = colors.iter().find(|(c, _)| *c == f[4]) {
+                return Err(format!(
+                    "the {} and {} centers are the same color",
+                    Color::ALL[other as usize].to_char(),
+                    Color::ALL[i].to_char()
+                ));
+            }
+            colors.push((f[4], Color::ALL[i]));
+        }
+    }
+    let mut p = PartialFacelets::default();
+    for (i, f) in faces.iter().enumerate() {
+        for (k, &letter) in f.iter().flatten().enumerate() {
+            let color = colors.iter().find(|(c, _)| *c == letter);
+            if color.is_none() && colors.len() == 6 {
+                return Err(format!("{} is not the color of a center", letter));
+            }
+            p.0[9 * i + k] = color.map(|&(_, c)| c);
+        }
+    }
+    p.check().map_err(|e| e.hint())?;
+    Ok(p)
+}
+
+/// Reads a physical cube face by face, checking each face as it comes.
+fn enter() -> Cube {
+    eprintln!(
+        "Type the nine stickers of each face, row by row from the top left, \
+         one letter per color: any letters, as long as each color always \
+         gets the same one. Type a face's letter (U R F D L B) to enter it \
+         again."
+    );
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut faces: [Option<Vec<char>>; 6] = Default::default();
+    loop {
+        let next = faces.iter().position(Option::is_none);
+        match next {
+            Some(i) => eprint!("{}: ", FACE_VIEWS[i]),
+            None => eprint!("face to enter again: "),
+        }
+        let line = match lines.next() {
+            Some(line) => line.unwrap_or_else(|e| fail(&e.to_string())),
+            None => fail("input ended before the cube was complete"),
+        };
+        let typed: Vec<char> = line
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if let [face] = typed[..] {
+            match Color::from_char(face) {
+                Some(c) => faces[c as usize] = None,
+                None => eprintln!("  no face {}", face),
+            }
+            continue;
+        }
+        let Some(i) = next else {
+            eprintln!("  type a face: U R F D L B");
+            continue;
+        };
+        if typed.len() != 9 {
+            eprintln!("  {}", FaceletError::Syntax.hint());
+            continue;
+        }
+        faces[i] = Some(typed);
+        let p = match entered(&faces) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("  {}. Enter this face again.", e);
+                faces[i] = None;
+                continue;
+            }
+        };
+        if let Some(f) = p.complete() {
+            match f.to_cube() {
+                Ok(cube) => {
+                    eprint!("{}", f.net());
+                    return cube;
+                }
+                Err(e) => eprintln!("  {}", e.hint()),
+            }
+        }
+    }
+}
+
+fn solve(context: &CubeContext, cube: &Cube) -> Alg {
+    context.solve(cube).unwrap_or_else(|| {
+        fail(&format!(
+            "no solution within {} moves",
+            context.config().max_length
+        ))
+    })
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args[0] == "-h" || args[0] == "--help" {
+        println!("{}", USAGE);
+        return;
+    }
+    let command = args.remove(0);
+    let mut config = Config::default();
+    if let Some(max) = take_option(&mut args, "--max") {
+        config.max_length = max as u32;
+    }
+    if let Some(threads) = take_option(&mut args, "--threads") {
+        config.parallelism = Parallelism::Threads(threads as usize);
+    }
+    let format = match (
+        take_flag(&mut args, "--json"),
+        take_flag(&mut args, "--csv"),
+    ) {
+        (true, true) => fail("--json and --csv are exclusive"),
+        (true, false) => Format::Json,
+        (false, true) => Format::Csv,
+        (false, false) => Format::Text,
+    };
+    let mut out = Output::new(format);
+    let context = CubeContext::new(config);
+    match command.as_str() {
+        "scramble" => {
+            let seed = take_option(&mut args, "--seed")
+                .unwrap_or_else(|| Rng::from_entropy().next_u64());
+            let count = take_option(&mut args, "--count").unwrap_or(1);
+            // Build the tables first, so they are not in the first time.
+            let scrambles = context.scrambles(seed).take(count as usize);
+            let mut start = Instant::now();
+            for (i, alg) in scrambles.enumerate() {
+                if out.is_text() {
+                    println!("{}", alg);
+                }
+                out.record(&[
+                    ("seed", seed.into()),
+                    ("index", i.into()),
+                    ("scramble", alg.to_string().into()),
+                    ("length", alg.len().into()),
+                    ("time_ms", millis(start).into()),
+                ]);
+                start = Instant::now();
+            }
+        }
+        "solve" => {
+            let cube = parse_cube(&args);
+            let solver = context.solver();
+            let max_length = context.config().max_length;
+            let start = Instant::now();
+            let (alg, stats) = solver.solve_with_stats(&cube, max_length);
+            let time = millis(start);
+            let alg = alg.unwrap_or_else(|| {
+                fail(&format!("no solution within {} moves", max_length))
+            });
+            if out.is_text() {
+                println!("{}", alg);
+            }
+            out.record(&[
+                ("cube", Facelets::from_cube(&cube).to_string().into()),
+                ("solution", alg.to_string().into()),
+                ("length", alg.len().into()),
+                ("time_ms", time.into()),
+                ("phase1_nodes", stats.phase1_nodes.into()),
+                ("phase2_nodes", stats.phase2_nodes.into()),
+            ]);
+        }
+        "invert" | "simplify" => {
+            let input = parse_alg(&args);
+            let alg = match command.as_str() {
+                "invert" => input.inverse(),
+                _ => input.simplify(),
+            };
+            if out.is_text() {
+                println!("{}", alg);
+            }
+            out.record(&[
+                ("input", input.to_string().into()),
+                ("alg", alg.to_string().into()),
+                ("length", alg.len().into()),
+            ]);
+        }
+        "render" => {
+            let scheme = take_string_option(&mut args, "--color").map(|s| {
+                s.parse::<ColorScheme>()
+                    .unwrap_or_else(|e| fail(&e.to_string()))
+            });
+            let f = Facelets::from_cube(&parse_cube(&args));
+            match (format, scheme) {
+                (Format::Csv, _) => fail("render has no CSV output"),
+                (Format::Json, _) => {
+                    out.record(&[("facelets", f.to_string().into())])
+                }
+                (Format::Text, Some(scheme)) => {
+                    print!("{}", render::ansi(&f, &scheme))
+                }
+                (Format::Text, None) => print!("{}", f.net()),
+            }
+        }
+        "convert" => {
+            let (facelets, alg) = match args.as_slice() {
+                [s] if is_facelets(s) => {
+                    let cube = parse_cube(&args);
+                    (s.clone(), solve(&context, &cube.invert()))
+                }
+                _ => {
+                    let alg = parse_alg(&args);
+                    (Facelets::from_cube(&alg.cube()).to_string(), alg)
+                }
+            };
+            if out.is_text() {
+                match args.as_slice() {
+                    [s] if is_facelets(s) => println!("{}", alg),
+                    _ => println!("{}", facelets),
+                }
+            }
+            out.record(&[
+                ("facelets", facelets.into()),
+                ("alg", alg.to_string().into()),
+            ]);
+        }
+        "tables" if args.first().map(String::as_str) == Some("cache") => {
+            let cache = TableCache::open_default()
+                .unwrap_or_else(|e| fail(&e.to_string()));
+            Solver::from_cache(&cache).unwrap_or_else(|e| fail(&e.to_string()));
+            let entries =
+                cache.entries().unwrap_or_else(|e| fail(&e.to_string()));
+            if out.is_text() {
+                println!("{}", cache.dir().display());
+            }
+            for e in entries.iter() {
+                if out.is_text() {
+                    println!("  {} ({} bytes)", e.name, e.bytes);
+                }
+                out.record(&[
+                    ("dir", cache.dir().display().to_string().into()),
+                    ("table", e.name.as_str().into()),
+                    ("bytes", e.bytes.into()),
+                ]);
+            }
+            if out.is_text() {
+                let total: u64 = entries.iter().map(|e| e.bytes).sum();
+                println!("{} bytes in all", total);
+            }
+        }
+        "tables" => {
+            let action = args.first().map(String::as_str);
+            let cache = match action {
+                None | Some("generate") => Some(
+                    match args.get(1) {
+                        Some(dir) => TableCache::new(dir),
+                        None => TableCache::open_default(),
+                    }
+                    .unwrap_or_else(|e| fail(&e.to_string())),
+                ),
+                Some("inspect") => None,
+                Some(other) => {
+                    fail(&format!("unknown tables action {:?}", other))
+                }
+            };
+            let start = Instant::now();
+            let mut times = Vec::new();
+            let mut timed = |name, size, build: &dyn Fn() -> PruneTable| {
+                let start = Instant::now();
+                let table = match &cache {
+                    Some(c) => c
+                        .prune_table(name, size, build)
+                        .unwrap_or_else(|e| fail(&e.to_string())),
+                    None => build(),
+                };
+                times.push(millis(start));
+                (name, table)
+            };
+            let tables = [
+                timed(
+                    "twist-slice",
+                    TwistSlice::SIZE,
+                    &PruneTable::build::<Cube, TwistSlice>,
+                ),
+                timed(
+                    "flip-slice",
+                    FlipSlice::SIZE,
+                    &PruneTable::build::<Cube, FlipSlice>,
+                ),
+                timed("corner-slice-perm", CornerSlicePerm::SIZE, &|| {
+                    PruneTable::build_with_moves::<Cube, CornerSlicePerm>(
+                        &PHASE2_MOVES,
+                    )
+                }),
+                timed("edge-slice-perm", EdgeSlicePerm::SIZE, &|| {
+                    PruneTable::build_with_moves::<Cube, EdgeSlicePerm>(
+                        &PHASE2_MOVES,
+                    )
+                }),
+            ];
+            if !out.is_text() {
+                for ((name, t), &time) in tables.iter().zip(times.iter()) {
+                    let counts =
+                        t.depth_counts().iter().map(|&n| n as u64).collect();
+                    out.record(&[
+                        ("table", (*name).into()),
+                        ("entries", t.len().into()),
+                        ("time_ms", time.into()),
+                        ("depth_counts", Value::List(counts)),
+                    ]);
+                }
+            } else if action == Some("inspect") {
+                for (name, t) in tables.iter() {
+                    println!(
+                        "{} ({} entries): {:?}",
+                        name,
+                        t.len(),
+                        t.depth_counts()
+                    );
+                }
+            } else if let Some(cache) = &cache {
+                println!(
+                    "{} tables in {} after {:.2?}",
+                    tables.len(),
+                    cache.dir().display(),
+                    start.elapsed()
+                );
+            }
+        }
+        "batch" => batch(&context, &mut out),
+        "enter" => {
+            let cube = enter();
+            let alg = solve(&context, &cube);
+            if out.is_text() {
+                println!("{}", alg);
+            }
+            out.record(&[
+                ("cube", Facelets::from_cube(&cube).to_string().into()),
+                ("solution", alg.to_string().into()),
+                ("length", alg.len().into()),
+            ]);
+        }
+        _ => fail(&format!("unknown command {:?}\n\n{}", command, USAGE)),
+    }
+}
@@ -0,0 +1,458 @@
+//! Blindfold solutions: Old Pochmann for corners and M2 for edges.
+//!
+//! Both methods solve one piece at a time by swapping the buffer with a
+//! target. For corners the buffer is ULB and the swap is the Y permutation
+//! between ULB and DFR; a short setup of R, D and F turns brings each
+//! target to DFR and is undone afterwards. For edges the buffer is DF and
+//! the swap is M2, between DF and UB; setups bring each target to UB.
+//!
+//! M2 also swaps UF and DB, so after an odd number of edge targets those
+//! two are exchanged, and targets there are shot to the other slot with
+//! their own algs. Corners never touch them. An odd number of targets
+//! (edges and corners always agree) leaves UF-DB and UL-UB swapped, which
+//! the parity alg fixes.
+//!
+//! Pieces home but flipped or twisted are not traced: each is oriented by a
+//! pure flip or twist alg, which turns the buffer the other way and is set
+//! up like a target of its slot. The buffer ends oriented because the
+//! orientations of all the pieces sum to zero.
+use crate::alg::Alg;
+use crate::cube::{Corner, Cube, Edge};
+use crate::cycles::{CORNER_NAMES, EDGE_NAMES};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::notation::{Move, ParseMoveError};
+use std::fmt;
+
+/// A move of a blindfold solution: a face turn, or a turn of the M slice
+/// (between L and R, turned like L) by 1, 2 or 3 quarter turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Face(Move),
+    M(u8),
+}
+
+const M: Cube = crate::cycles!(edges: (UF FD DB BU));
+
+impl Turn {
+    pub fn inverse(self) -> Self {
+        match self {
+            Turn::Face(m) => Turn::Face(m.inverse()),
+            Turn::M(p) => Turn::M(4 - p),
+        }
+    }
+
+    /// The state reached from solved, ignoring the centers.
+    pub fn cube(self) -> Cube {
+        match self {
+            Turn::Face(m) => *m.cube(),
+            Turn::M(p) => (1..p).fold(M, |c, _| c.compose(&M)),
+        }
+    }
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Turn::Face(m) => write!(f, "{}", m),
+            Turn::M(p) => write!(f, "M{}", ["", "2", "'"][*p as usize - 1]),
+        }
+    }
+}
+
+impl core::str::FromStr for Turn {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, ParseMoveError> {
+        match s {
+            "M" => Ok(Turn::M(1)),
+            "M2" => Ok(Turn::M(2)),
+            "M'" => Ok(Turn::M(3)),
+            _ => s.parse().map(Turn::Face),
+        }
+    }
+}
+
+fn parse_turns(s: &str) -> Vec<Turn> {
+    s.split_whitespace()
+        .map(|t| t.parse().expect("a valid turn"))
+        .collect()
+}
+
+/// `cube` after `turns`, and the quarter turns the centers have made with
+/// the M slice, mod 4. The pieces are solved relative to the centers only
+/// when that is 0.
+pub fn execute(cube: &Cube, turns: &[Turn]) -> (Cube, u8) {
+    turns.iter().fold((*cube, 0), |(c, k), &t| {
+        let k = match t {
+            Turn::Face(_) => k,
+            Turn::M(p) => (k + p) % 4,
+        };
+        (c.compose(&t.cube()), k)
+    })
+}
+
+/// Edge slots of the M2 buffer and the slots M2 swaps as a side effect.
+const EDGE_BUFFER: usize = 5;
+const UF: usize = 1;
+const DB: usize = 7;
+
+/// Corner slot of the Old Pochmann buffer.
+const CORNER_BUFFER: usize = 2;
+
+/// Setups bringing each edge sticker to the U side of UB, by slot and then
+/// orientation. The buffer and the M-slice slots have none.
+#[rustfmt::skip]
+const EDGE_SETUPS: [[&str; 2]; 12] = [
+    ["F U' F'", "B' R B"],   // UR
+    ["", ""],                // UF
+    ["F U F'", "B L' B'"],   // UL
+    ["", "U B' R U' B"],     // UB
+    ["U R2 U'", "B' R' B"],  // DR
+    ["", ""],                // DF
+    ["U' L2 U", "B L B'"],   // DL
+    ["", ""],                // DB
+    ["U R U'", "F' U2 F"],   // FR
+    ["U' L' U", "F U2 F'"],  // FL
+    ["U' L U", "D B' D'"],   // BL
+    ["U R' U'", "D B D'"],   // BR
+];
+
+/// Whole algs for the stickers of UF and DB, by orientation.
+const UF_ALGS: [&str; 2] = ["U2 M' U2 M'", "M2 U' M' U' F2 U M U' F2 U2"];
+const DB_ALGS: [&str; 2] = ["M U2 M U2", "D2 F2 U' M' U F2 U D2 M' U'"];
+
+/// Setups bringing each corner sticker to the R side of DFR, by slot and
+/// then twist.
+#[rustfmt::skip]
+const CORNER_SETUPS: [[&str; 3]; 8] = [
+    ["F", "R2 D'", "R'"],    // URF
+    ["F R'", "F2", "F' D"],  // UFL
+    ["", "", ""],            // ULB
+    ["R D'", "R2", "R' F"],  // UBR
+    ["R' D'", "", "R F"],    // DFR
+    ["F'", "D", "F2 R'"],    // DLF
+    ["D F'", "D2", "D' R"],  // DBL
+    ["R2 F", "D'", "R"],     // DRB
+];
+
+const OP_SWAP: &str = "R U' R' U' R U R' F' R U R' U' R' F R";
+const PARITY: &str = "D' L2 D M2 D' L2 D";
+
+/// Flips of UB, UF and DB, each with DF.
+const FLIP_UB: &str = "U F U F U' F2 U2 F B L F' L' B' U";
+const FLIP_UF: &str = "U' F U F U' F2 U2 F B L F' L' B' U'";
+const FLIP_DB: &str = "B2 U F U F U' F2 U2 F B L F' L' B' U B2";
+
+/// Twists of DFR undoing a twist of 1 and of 2, each twisting ULB the
+/// other way.
+const TWISTS: [&str; 2] = [
+    "U F' R' D' R D R' D' R U R' D R D' R' D R U' F U'",
+    "F2 U2 R' D' R D R' D' R U R' D R D' R' D R U F2",
+];
+
+/// `setup`, then `alg`, then `setup` undone.
+fn conjugate(setup: &str, alg: &str) -> Vec<Turn> {
+    let setup: Alg = setup.parse().expect("a valid setup");
+    let mut turns: Vec<_> =
+        setup.moves().iter().map(|&m| Turn::Face(m)).collect();
+    turns.extend(parse_turns(alg));
+    turns.extend(setup.inverse().moves().iter().map(|&m| Turn::Face(m)));
+    turns
+}
+
+/// The name of a sticker: its face first, then the rest of its piece.
+fn sticker<const N: usize>(name: &[u8; N], ori: u8) -> String {
+    (0..N)
+        .map(|i| name[(i + ori as usize) % N] as char)
+        .collect()
+}
+
+/// One target: a swap of the buffer with `target`, a sticker name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub target: String,
+    pub turns: Vec<Turn>,
+}
+
+/// A complete blindfold solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BldSolution {
+    pub edges: Vec<Step>,
+    pub corners: Vec<Step>,
+    /// Empty unless the number of targets is odd.
+    pub parity: Vec<Turn>,
+    /// Edges home but flipped, each flipped with the buffer.
+    pub flips: Vec<Step>,
+    /// Corners home but twisted, each twisted with the buffer.
+    pub twists: Vec<Step>,
+}
+
+impl BldSolution {
+    /// Memorizes and solves `cube`.
+    pub fn new(cube: &Cube) -> Self {
+        let (edges, flips) = edge_steps(cube);
+        let (corners, twists) = corner_steps(cube);
+        let parity = if edges.len() % 2 == 1 {
+            parse_turns(PARITY)
+        } else {
+            Vec::new()
+        };
+        Self {
+            edges,
+            corners,
+            parity,
+            flips,
+            twists,
+        }
+    }
+
+    /// Every turn, in order.
+    pub fn turns(&self) -> Vec<Turn> {
+        self.edges
+            .iter()
+            .chain(&self.corners)
+            .flat_map(|s| s.turns.iter().copied())
+            .chain(self.parity.iter().copied())
+            .chain(
+                self.flips
+                    .iter()
+                    .chain(&self.twists)
+                    .flat_map(|s| s.turns.iter().copied()),
+            )
+            .collect()
+    }
+
+    /// The targets in pairs, edges then corners, and the pieces to orient:
+    /// `UR-FL BU-DR / RDF-UFL / flip: FR / twist: DBL`.
+    pub fn memo(&self) -> String {
+        let pairs = |steps: &[Step]| {
+            steps
+                .chunks(2)
+                .map(|p| {
+                    p.iter()
+                        .map(|s| s.target.as_str())
+                        .collect::<Vec<_>>()
+                        .join("-")
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut s =
+            format!("{} / {}", pairs(&self.edges), pairs(&self.corners));
+        for (name, steps) in [("flip", &self.flips), ("twist", &self.twists)] {
+            if !steps.is_empty() {
+                let pieces: Vec<_> =
+                    steps.iter().map(|s| s.target.as_str()).collect();
+                s.push_str(&format!(" / {}: {}", name, pieces.join(" ")));
+            }
+        }
+        s
+    }
+}
+
+impl fmt::Display for BldSolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for s in self.edges.iter().chain(&self.corners) {
+            write!(f, "{}:", s.target)?;
+            for t in s.turns.iter() {
+                write!(f, " {}", t)?;
+            }
+            writeln!(f)?;
+        }
+        if !self.parity.is_empty() {
+            write!(f, "parity:")?;
+            for t in self.parity.iter() {
+                write!(f, " {}", t)?;
+            }
+            writeln!(f)?;
+        }
+        for (name, steps) in [("flip", &self.flips), ("twist", &self.twists)] {
+            for s in steps.iter() {
+                write!(f, "{} {}:", name, s.target)?;
+                for t in s.turns.iter() {
+                    write!(f, " {}", t)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The first slot, other than `buffer`, that does not hold its own piece.
+fn unsolved(pieces: impl Iterator<Item = u8>, buffer: usize) -> Option<usize> {
+    pieces
+        .enumerate()
+        .position(|(i, v)| i != buffer && v != i as u8)
+}
+
+/// Traces the edges, swapping them in a copy of `cube` as the targets are
+/// shot, then flips those left flipped. The copy stays one M2 side effect
+/// behind after an odd number of targets, so every target in it is a plain
+/// swap with the buffer.
+fn edge_steps(cube: &Cube) -> (Vec<Step>, Vec<Step>) {
+    let mut cube = *cube;
+    let mut steps = Vec::new();
+    loop {
+        let v = cube.edges()[EDGE_BUFFER].0;
+        let (piece, ori) = if EDGES.piece(v) as usize == EDGE_BUFFER {
+            // Start a new cycle.
+            let pieces = cube.edges().iter().map(|e| EDGES.piece(e.0));
+            match unsolved(pieces, EDGE_BUFFER) {
+                Some(i) => (i, 0),
+                None => break,
+            }
+        } else {
+            (EDGES.piece(v) as usize, EDGES.ori(v))
+        };
+        let mut swap = Cube::identity();
+        swap.edges_mut()[piece] = Edge(EDGES.pack(EDGE_BUFFER as u8, ori));
+        swap.edges_mut()[EDGE_BUFFER] = Edge(EDGES.pack(piece as u8, ori));
+        cube = cube.compose(&swap);
+
+        let slot = match piece {
+            UF if steps.len() % 2 == 1 => DB,
+            DB if steps.len() % 2 == 1 => UF,
+            _ => piece,
+        };
+        let turns = match slot {
+            UF => parse_turns(UF_ALGS[ori as usize]),
+            DB => parse_turns(DB_ALGS[ori as usize]),
+            _ => conjugate(EDGE_SETUPS[slot][ori as usize], "M2"),
+        };
+        steps.push(Step {
+            target: sticker(&EDGE_NAMES[slot], ori),
+            turns,
+        });
+    }
+    let flips = (0..12)
+        .filter(|&i| i != EDGE_BUFFER && EDGES.ori(cube.edges()[i].0) != 0)
+        .map(|i| Step {
+            target: sticker(&EDGE_NAMES[i], 0),
+            turns: match i {
+                UF => parse_turns(FLIP_UF),
+                DB => parse_turns(FLIP_DB),
+                _ => conjugate(EDGE_SETUPS[i][0], FLIP_UB),
+            },
+        })
+        .collect();
+    (steps, flips)
+}
+
+/// Traces the corners, swapping them in a copy of `cube` as the targets
+/// are shot, then twists those left twisted.
+fn corner_steps(cube: &Cube) -> (Vec<Step>, Vec<Step>) {
+    let mut cube = *cube;
+    let mut steps = Vec::new();
+    loop {
+        let v = cube.corners()[CORNER_BUFFER].0;
+        let (piece, twist) = if CORNERS.piece(v) as usize == CORNER_BUFFER {
+            let pieces = cube.corners().iter().map(|c| CORNERS.piece(c.0));
+            match unsolved(pieces, CORNER_BUFFER) {
+                Some(i) => (i, 0),
+                None => break,
+            }
+        } else {
+            (CORNERS.piece(v) as usize, CORNERS.ori(v))
+        };
+        let mut swap = Cube::identity();
+        swap.corners_mut()[piece] =
+            Corner(CORNERS.pack(CORNER_BUFFER as u8, (3 - twist) % 3));
+        swap.corners_mut()[CORNER_BUFFER] =
+            Corner(CORNERS.pack(piece as u8, twist));
+        cube = cube.compose(&swap);
+
+        steps.push(Step {
+            target: sticker(&CORNER_NAMES[piece], (3 - twist) % 3),
+            turns: conjugate(CORNER_SETUPS[piece][twist as usize], OP_SWAP),
+        });
+    }
+    let twists = (0..8)
+        .filter(|&i| i != CORNER_BUFFER)
+        .filter_map(|i| match CORNERS.ori(cube.corners()[i].0) {
+            0 => None,
+            twist => Some(Step {
+                target: sticker(&CORNER_NAMES[i], 0),
+                turns: conjugate(
+                    CORNER_SETUPS[i][0],
+                    TWISTS[twist as usize - 1],
+                ),
+            }),
+        })
+        .collect();
+    (steps, twists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{random_cube, Rng};
+
+    #[test]
+    fn solutions_solve_random_cubes() {
+        let mut rng = Rng::new(1);
+        let mut parities = 0;
+        for _ in 0..2000 {
+            let cube = random_cube(&mut rng);
+            let solution = BldSolution::new(&cube);
+            assert_eq!(
+                execute(&cube, &solution.turns()),
+                (Cube::identity(), 0),
+                "{}",
+                solution.memo()
+            );
+            assert_eq!(solution.edges.len() % 2, solution.corners.len() % 2);
+            parities += !solution.parity.is_empty() as usize;
+        }
+        assert!(parities > 0 && parities < 2000);
+    }
+
+    #[test]
+    fn flipped_pairs_are_flipped_in_place() {
+        for i in 0..12 {
+            for j in i + 1..12 {
+                let mut cube = Cube::identity();
+                for k in [i, j] {
+                    cube.edges_mut()[k] = Edge(EDGES.pack(k as u8, 1));
+                }
+                let solution = BldSolution::new(&cube);
+                assert!(solution.edges.is_empty());
+                let home = [i, j].iter().filter(|&&k| k != EDGE_BUFFER).count();
+                assert_eq!(solution.flips.len(), home);
+                assert_eq!(
+                    execute(&cube, &solution.turns()),
+                    (Cube::identity(), 0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn twisted_pairs_are_twisted_in_place() {
+        for i in 0..8 {
+            for j in 0..8 {
+                if i == j {
+                    continue;
+                }
+                let mut cube = Cube::identity();
+                cube.corners_mut()[i] = Corner(CORNERS.pack(i as u8, 1));
+                cube.corners_mut()[j] = Corner(CORNERS.pack(j as u8, 2));
+                let solution = BldSolution::new(&cube);
+                assert!(solution.corners.is_empty());
+                let home =
+                    [i, j].iter().filter(|&&k| k != CORNER_BUFFER).count();
+                assert_eq!(solution.twists.len(), home);
+                assert_eq!(
+                    execute(&cube, &solution.turns()),
+                    (Cube::identity(), 0)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn solved_needs_no_turns() {
+        let solution = BldSolution::new(&Cube::identity());
+        assert!(solution.turns().is_empty());
+    }
+}
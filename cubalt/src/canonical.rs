@@ -0,0 +1,108 @@
+//! Canonical move sequences.
+//!
+//! Many move sequences reach the same state in the same number of moves:
+//! `R R` is `R2`, and `U D` is `D U`. Searches only need one of each, and
+//! keeping to canonical sequences cuts the branching factor of the 3x3 from
+//! 18 to about 13.3.
+use crate::puzzle::Puzzle;
+use alloc::vec::Vec;
+
+/// Whether `next` may follow `prev` in a canonical sequence: never two
+/// turns of the same layer in a row, and turns on a common axis (which
+/// commute) only in increasing layer order.
+pub fn is_canonical<P: Puzzle>(prev: Option<P::Move>, next: P::Move) -> bool {
+    match prev {
+        None => true,
+        Some(prev) => {
+            let (axis, layer) = P::move_axis_layer(prev);
+            let (next_axis, next_layer) = P::move_axis_layer(next);
+            axis != next_axis || next_layer > layer
+        }
+    }
+}
+
+/// `is_canonical` for the quarter-turn metric, where a half turn is two
+/// moves: a quarter turn may also follow itself. (Three in a row are let
+/// through too; they are never optimal.)
+pub fn is_canonical_qtm<P: Puzzle>(
+    prev: Option<P::Move>,
+    next: P::Move,
+) -> bool {
+    prev == Some(next) || is_canonical::<P>(prev, next)
+}
+
+/// `is_canonical` as a table over a list of moves, which are referred to
+/// by index. The state after a sequence is its last move, or `None` for
+/// the empty sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalSeq {
+    /// Bitmask of the moves allowed first, then after each move.
+    allowed: Vec<u64>,
+}
+
+impl CanonicalSeq {
+    /// The table for `moves`. Panics with more than 64 moves.
+    pub fn new<P: Puzzle>(moves: &[P::Move]) -> Self {
+        Self::with_rule::<P>(moves, is_canonical::<P>)
+    }
+
+    /// The table of another rule than `is_canonical`, such as
+    /// `is_canonical_qtm`.
+    pub fn with_rule<P: Puzzle>(
+        moves: &[P::Move],
+        rule: fn(Option<P::Move>, P::Move) -> bool,
+    ) -> Self {
+        assert!(moves.len() <= 64, "at most 64 moves");
+        let mask = |prev: Option<P::Move>| {
+            moves
+                .iter()
+                .enumerate()
+                .fold(0u64, |mask, (i, &m)| mask | (rule(prev, m) as u64) << i)
+        };
+        let mut allowed = Vec::with_capacity(moves.len() + 1);
+        allowed.push(mask(None));
+        allowed.extend(moves.iter().map(|&m| mask(Some(m))));
+        Self { allowed }
+    }
+
+    /// The table for all of `P::moves()`.
+    pub fn for_puzzle<P: Puzzle>() -> Self {
+        Self::new::<P>(P::moves())
+    }
+
+    /// Number of moves.
+    pub fn len(&self) -> usize {
+        self.allowed.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bitmask of the moves that may follow `prev`.
+    #[inline]
+    pub fn allowed(&self, prev: Option<usize>) -> u64 {
+        self.allowed[prev.map_or(0, |i| i + 1)]
+    }
+
+    #[inline]
+    pub fn is_allowed(&self, prev: Option<usize>, next: usize) -> bool {
+        self.allowed(prev) >> next & 1 == 1
+    }
+
+    /// The moves that may follow `prev`, in order.
+    pub fn next_moves(
+        &self,
+        prev: Option<usize>,
+    ) -> impl Iterator<Item = usize> {
+        let mut mask = self.allowed(prev);
+        core::iter::from_fn(move || {
+            if mask == 0 {
+                return None;
+            }
+            let i = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            Some(i)
+        })
+    }
+}
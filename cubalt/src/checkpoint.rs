@@ -0,0 +1,168 @@
+//! Checkpoints of long optimal searches, to stop them and resume them in
+//! another process.
+//!
+//! The search at each depth is split into work units, one for each
+//! canonical sequence of its first two moves. A checkpoint records the
+//! depth, the next unit and the nodes visited so far, as lines of text:
+//!
+//! ```text
+//! cubalt checkpoint 1
+//! state UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB
+//! depth 14
+//! unit 97
+//! nodes 1234567890
+//! ```
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::facelet::Facelets;
+use crate::notation::Move;
+use crate::optimal::Optimal;
+use std::io::{self, BufRead, Write};
+
+/// How far an optimal search of `state` has got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub state: Cube,
+    /// The depth being searched; none shorter has a solution.
+    pub depth: u32,
+    /// Work units of `depth` searched so far.
+    pub unit: usize,
+    pub nodes: u64,
+}
+
+/// Where `Optimal::solve_resumable` stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchStatus {
+    /// A shortest solution.
+    Solved(Alg),
+    /// No solution within the maximum length.
+    Exhausted,
+    /// Stopped at the checkpoint, to be resumed from it.
+    Stopped,
+}
+
+impl Checkpoint {
+    /// The start of a search of `state`.
+    pub fn new(state: Cube) -> Self {
+        Self {
+            state,
+            depth: 0,
+            unit: 0,
+            nodes: 0,
+        }
+    }
+
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "cubalt checkpoint 1")?;
+        writeln!(w, "state {}", Facelets::from_cube(&self.state))?;
+        writeln!(w, "depth {}", self.depth)?;
+        writeln!(w, "unit {}", self.unit)?;
+        writeln!(w, "nodes {}", self.nodes)
+    }
+
+    /// Read a checkpoint written by `write_to`.
+    pub fn read_from<R: BufRead>(r: R) -> io::Result<Self> {
+        let invalid =
+            |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut lines = r.lines();
+        let mut field = |name: &str| -> io::Result<String> {
+            let line = lines.next().ok_or_else(|| {
+                invalid(format!("checkpoint has no {} line", name))
+            })??;
+            match line.split_once(' ') {
+                Some((n, value)) if n == name => Ok(value.to_string()),
+                _ => {
+                    Err(invalid(format!("expected {}, found {:?}", name, line)))
+                }
+            }
+        };
+        if field("cubalt")? != "checkpoint 1" {
+            return Err(invalid("not a version 1 checkpoint".to_string()));
+        }
+        let state = field("state")?
+            .parse::<Facelets>()
+            .and_then(|f| f.to_cube())
+            .map_err(|e| invalid(e.to_string()))?;
+        let mut number = |name: &str| {
+            let value = field(name)?;
+            value.parse::<u64>().map_err(|_| {
+                invalid(format!("{} {:?} is not a number", name, value))
+            })
+        };
+        Ok(Self {
+            state,
+            depth: number("depth")? as u32,
+            unit: number("unit")? as usize,
+            nodes: number("nodes")?,
+        })
+    }
+}
+
+impl Optimal {
+    /// The work units of a search at `depth`: the canonical sequences of
+    /// its first moves, two of them or all of them if fewer.
+    fn work_units(&self, depth: u32) -> Vec<Vec<Move>> {
+        let moves = self.options().metric.moves();
+        let canonical = self.canonical();
+        match depth {
+            0 => vec![vec![]],
+            1 => canonical.next_moves(None).map(|i| vec![moves[i]]).collect(),
+            _ => canonical
+                .next_moves(None)
+                .flat_map(|i| {
+                    canonical
+                        .next_moves(Some(i))
+                        .map(move |j| vec![moves[i], moves[j]])
+                })
+                .collect(),
+        }
+    }
+
+    /// Carry on the search of `checkpoint`, up to `max_length` moves,
+    /// updating it after each work unit. `stop` is asked after each unit
+    /// whether to stop there; the checkpoint then resumes the search.
+    pub fn solve_resumable<F>(
+        &self,
+        checkpoint: &mut Checkpoint,
+        max_length: u32,
+        mut stop: F,
+    ) -> SearchStatus
+    where
+        F: FnMut(&Checkpoint) -> bool,
+    {
+        let metric = self.options().metric;
+        while checkpoint.depth <= max_length {
+            let units = self.work_units(checkpoint.depth);
+            while checkpoint.unit < units.len() {
+                let prefix = &units[checkpoint.unit];
+                let start = Alg(prefix.clone()).apply(&checkpoint.state);
+                let mut ida = self.ida(max_length);
+                let mut solution = None;
+                let rest = checkpoint.depth - prefix.len() as u32;
+                ida.search_depth_after(
+                    &start,
+                    rest,
+                    prefix.last().copied(),
+                    |path, _| {
+                        let mut moves = prefix.clone();
+                        moves.extend_from_slice(path);
+                        solution = Some(Alg(moves).simplify());
+                        true
+                    },
+                );
+                checkpoint.nodes += ida.nodes;
+                checkpoint.unit += 1;
+                if let Some(alg) = solution {
+                    debug_assert!(metric.length(&alg) <= max_length);
+                    return SearchStatus::Solved(alg);
+                }
+                if stop(checkpoint) {
+                    return SearchStatus::Stopped;
+                }
+            }
+            checkpoint.depth += 1;
+            checkpoint.unit = 0;
+        }
+        SearchStatus::Exhausted
+    }
+}
@@ -0,0 +1,333 @@
+//! Commutators and conjugates, and a search for 3-style algs.
+//!
+//! `[A, B]` is `A B A' B'` and `[S: X]` is `S X S'`. A pure commutator
+//! cycles three pieces when `A` swaps one piece in a layer for another (the
+//! interchange) and `B` moves a third into that layer and back out (the
+//! insertion); a setup `S` first brings the pieces to where such a pair
+//! exists.
+//!
+//! `decompose` goes the other way, finding such a structure in a written
+//! alg.
+use crate::alg::{Alg, AllAlgs};
+use crate::cube::Cube;
+use crate::cycles::{CORNER_NAMES, EDGE_NAMES};
+use crate::notation::Face;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// `[setup: [a, b]]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Commutator {
+    pub setup: Alg,
+    pub a: Alg,
+    pub b: Alg,
+}
+
+impl Commutator {
+    /// The moves, with cancellations between the parts merged.
+    pub fn alg(&self) -> Alg {
+        let mut moves = self.setup.0.clone();
+        moves.extend_from_slice(self.a.moves());
+        moves.extend_from_slice(self.b.moves());
+        moves.extend(self.a.inverse().0);
+        moves.extend(self.b.inverse().0);
+        moves.extend(self.setup.inverse().0);
+        Alg(moves).simplify()
+    }
+
+    pub fn cube(&self) -> Cube {
+        self.alg().cube()
+    }
+
+    /// Number of moves of `alg`.
+    pub fn len(&self) -> usize {
+        self.alg().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alg().is_empty()
+    }
+
+    /// Number of B and L turns in `alg`, the faces hardest to turn without
+    /// regripping.
+    pub fn awkward_moves(&self) -> usize {
+        self.alg()
+            .moves()
+            .iter()
+            .filter(|m| matches!(m.face(), Face::B | Face::L))
+            .count()
+    }
+}
+
+impl fmt::Display for Commutator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.setup.is_empty() {
+            write!(f, "[{}, {}]", self.a, self.b)
+        } else {
+            write!(f, "[{}: [{}, {}]]", self.setup, self.a, self.b)
+        }
+    }
+}
+
+/// The sticker named `name`: its face first, then the rest of its piece
+/// in any order. Written with the piece's letters in `cycles` order, from
+/// that face.
+fn sticker(name: &str) -> Option<(bool, String)> {
+    let name = name.as_bytes();
+    let find = |slot: &[u8]| {
+        let same = slot.len() == name.len()
+            && slot.iter().all(|c| name.contains(c))
+            && name.iter().all(|c| slot.contains(c));
+        let k = slot.iter().position(|&c| Some(&c) == name.first())?;
+        same.then(|| {
+            (0..slot.len())
+                .map(|i| slot[(i + k) % slot.len()] as char)
+                .collect::<String>()
+        })
+    };
+    match name.len() {
+        2 => EDGE_NAMES.iter().find_map(|n| find(n)).map(|s| (false, s)),
+        3 => CORNER_NAMES.iter().find_map(|n| find(n)).map(|s| (true, s)),
+        _ => None,
+    }
+}
+
+/// The 3-cycle moving the sticker at `a` to `b`, `b` to `c` and `c` to
+/// `a`, all of edges or all of corners: `three_cycle("UF", "UB", "RD")`.
+/// `None` unless the names are stickers of three different pieces.
+pub fn three_cycle(a: &str, b: &str, c: &str) -> Option<Cube> {
+    let [a, b, c] = [sticker(a)?, sticker(b)?, sticker(c)?];
+    let piece = |s: &String| {
+        let mut p: Vec<char> = s.chars().collect();
+        p.sort_unstable();
+        p
+    };
+    if a.0 != b.0
+        || b.0 != c.0
+        || piece(&a.1) == piece(&b.1)
+        || piece(&b.1) == piece(&c.1)
+        || piece(&a.1) == piece(&c.1)
+    {
+        return None;
+    }
+    let kind = if a.0 { "corners" } else { "edges" };
+    let spec = alloc::format!("{}: ({} {} {})", kind, a.1, b.1, c.1);
+    Some(Cube::from_cycles(&spec))
+}
+
+/// Limits of a commutator search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommutatorSearch {
+    pub max_setup: usize,
+    pub max_interchange: usize,
+    pub max_insertion: usize,
+}
+
+impl Default for CommutatorSearch {
+    fn default() -> Self {
+        Self {
+            max_setup: 1,
+            max_interchange: 1,
+            max_insertion: 3,
+        }
+    }
+}
+
+impl CommutatorSearch {
+    /// Every commutator within the limits reaching `target`, shortest
+    /// first, then with the fewest awkward moves. Each alg is listed once.
+    pub fn find(&self, target: &Cube) -> Vec<Commutator> {
+        let interchanges: Vec<(Alg, Cube)> =
+            AllAlgs::new(self.max_interchange).skip(1).collect();
+        let insertions: Vec<(Alg, Cube)> =
+            AllAlgs::new(self.max_insertion).skip(1).collect();
+        let mut found: Vec<(usize, usize, Alg, Commutator)> = Vec::new();
+        for (setup, s) in AllAlgs::new(self.max_setup) {
+            // S [A, B] S' reaches the target when [A, B] reaches this.
+            let inner = s.invert().compose(target).compose(&s);
+            for (a, ac) in interchanges.iter() {
+                let ai = ac.invert();
+                for (b, bc) in insertions.iter() {
+                    let bi = bc.invert();
+                    let pairs = [
+                        (a, b, ac.compose(bc).compose(&ai).compose(&bi)),
+                        (b, a, bc.compose(ac).compose(&bi).compose(&ai)),
+                    ];
+                    for (x, y, c) in pairs {
+                        if c != inner {
+                            continue;
+                        }
+                        let comm = Commutator {
+                            setup: setup.clone(),
+                            a: x.clone(),
+                            b: y.clone(),
+                        };
+                        let alg = comm.alg();
+                        if found.iter().all(|f| f.2 != alg) {
+                            found.push((
+                                alg.len(),
+                                comm.awkward_moves(),
+                                alg,
+                                comm,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        found.sort_by_key(|f| (f.0, f.1));
+        found.into_iter().map(|f| f.3).collect()
+    }
+}
+
+/// The structure of an alg: a commutator, with or without a setup, or a
+/// conjugate of something that is not one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Decomposition {
+    Commutator(Commutator),
+    Conjugate { setup: Alg, body: Alg },
+}
+
+impl fmt::Display for Decomposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Decomposition::Commutator(c) => write!(f, "{}", c),
+            Decomposition::Conjugate { setup, body } => {
+                write!(f, "[{}: {}]", setup, body)
+            }
+        }
+    }
+}
+
+/// `alg` written as `[S: [A, B]]` or `[S: X]`, if it is one once
+/// cancellations are merged. Commutators come first, with the fewest moves
+/// in their parts; conjugates take the longest setup.
+pub fn decompose(alg: &Alg) -> Option<Decomposition> {
+    find_commutator(alg)
+        .map(Decomposition::Commutator)
+        .or_else(|| find_conjugate(alg))
+}
+
+/// The commutator with the fewest moves in its setup, `a` and `b` whose
+/// merged expansion is `alg`. Each part is a run of `alg`'s moves.
+pub fn find_commutator(alg: &Alg) -> Option<Commutator> {
+    let alg = alg.simplify();
+    let m = alg.moves();
+    let n = m.len();
+    let part = |from: usize, to: usize| Alg(m[from..to].to_vec());
+    let mut best: Option<(usize, Commutator)> = None;
+    for s in 0..=n / 2 {
+        for i in 1..n - s {
+            for j in 1..=n - s - i {
+                let size = s + i + j;
+                // The expansion is never longer than its parts twice over.
+                if 2 * size < n || best.as_ref().is_some_and(|b| b.0 <= size) {
+                    continue;
+                }
+                let c = Commutator {
+                    setup: part(0, s),
+                    a: part(s, s + i),
+                    b: part(s + i, s + i + j),
+                };
+                if c.alg() == alg {
+                    best = Some((size, c));
+                }
+            }
+        }
+    }
+    best.map(|b| b.1)
+}
+
+/// `alg` as `[S: X]` with the longest setup, and `X` not empty.
+pub fn find_conjugate(alg: &Alg) -> Option<Decomposition> {
+    let alg = alg.simplify();
+    let m = alg.moves();
+    let n = m.len();
+    let k = (0..n / 2)
+        .take_while(|&k| m[n - 1 - k] == m[k].inverse())
+        .count();
+    if k == 0 || 2 * k == n {
+        return None;
+    }
+    Some(Decomposition::Conjugate {
+        setup: Alg(m[..k].to_vec()),
+        body: Alg(m[k..n - k].to_vec()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alg(s: &str) -> Alg {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn three_cycles() {
+        let c = three_cycle("UF", "UB", "RD").unwrap();
+        assert_ne!(c, Cube::identity());
+        assert_eq!(c.compose(&c).compose(&c), Cube::identity());
+        assert_eq!(
+            three_cycle("UB", "RD", "UF"),
+            Some(c),
+            "rotating the names gives the same cycle"
+        );
+        let c = three_cycle("UFR", "DBL", "LDF").unwrap();
+        assert_eq!(c.compose(&c).compose(&c), Cube::identity());
+        assert_eq!(three_cycle("UF", "FU", "RD"), None);
+        assert_eq!(three_cycle("UF", "UB", "UFR"), None);
+        assert_eq!(three_cycle("UF", "UB", "UX"), None);
+    }
+
+    #[test]
+    fn found_commutators_reach_the_target() {
+        let search = CommutatorSearch::default();
+        for [a, b, c] in [["UFR", "UBR", "DFL"], ["UF", "UB", "DF"]] {
+            let target = three_cycle(a, b, c).unwrap();
+            let found = search.find(&target);
+            assert!(!found.is_empty(), "{} {} {}", a, b, c);
+            for pair in found.windows(2) {
+                assert!(pair[0].len() <= pair[1].len());
+            }
+            for comm in found.iter() {
+                assert_eq!(comm.cube(), target, "{}", comm);
+            }
+        }
+    }
+
+    #[test]
+    fn decompositions_give_back_the_alg() {
+        let sexy = Commutator {
+            setup: Alg::default(),
+            a: alg("R"),
+            b: alg("U"),
+        };
+        assert_eq!(
+            decompose(&alg("R U R' U'")),
+            Some(Decomposition::Commutator(sexy.clone()))
+        );
+        let set_up = Commutator {
+            setup: alg("F"),
+            ..sexy
+        };
+        assert_eq!(
+            decompose(&alg("F R U R' U' F'")),
+            Some(Decomposition::Commutator(set_up))
+        );
+        assert_eq!(
+            decompose(&alg("R U2 R'")),
+            Some(Decomposition::Conjugate {
+                setup: alg("R"),
+                body: alg("U2"),
+            })
+        );
+        assert_eq!(decompose(&alg("R U")), None);
+        let a9 = alg("R' D' R U R' D R U'");
+        match decompose(&a9) {
+            Some(Decomposition::Commutator(c)) => assert_eq!(c.alg(), a9),
+            d => panic!("{:?}", d),
+        }
+    }
+}
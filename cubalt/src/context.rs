@@ -0,0 +1,255 @@
+//! One place for the state that solving needs.
+//!
+//! The solver's pruning tables take seconds to build, and an application
+//! that solves more than once wants to build them once. A `CubeContext`
+//! owns them, along with the backend and the search settings, and is
+//! passed (or shared by reference) to whatever solves.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::scramble::Scrambles;
+use crate::solver::Solver;
+use core::fmt;
+use core::ops::Range;
+use std::sync::OnceLock;
+use std::thread;
+
+/// The SIMD implementation of the cube operations.
+///
+/// It is chosen at compile time by the enabled target features; this
+/// records which one a build uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    Avx2,
+}
+
+impl Backend {
+    /// The backend of this build.
+    pub const fn current() -> Self {
+        Backend::Avx2
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Backend::Avx2 => "avx2",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Where work that can run in parallel runs. Nothing in the crate starts
+/// a thread but as this allows.
+#[derive(Debug, Clone, Default)]
+pub enum Parallelism {
+    /// On the calling thread.
+    #[default]
+    Sequential,
+    /// On at most this many scoped threads, joined before returning.
+    Threads(usize),
+    /// On a rayon pool the caller owns.
+    #[cfg(feature = "rayon")]
+    Pool(std::sync::Arc<rayon::ThreadPool>),
+}
+
+impl PartialEq for Parallelism {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Parallelism::Sequential, Parallelism::Sequential) => true,
+            (Parallelism::Threads(a), Parallelism::Threads(b)) => a == b,
+            #[cfg(feature = "rayon")]
+            (Parallelism::Pool(a), Parallelism::Pool(b)) => {
+                std::sync::Arc::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Parallelism {}
+
+impl Parallelism {
+    /// As many threads as the machine runs at once.
+    pub fn available() -> Self {
+        Parallelism::Threads(
+            thread::available_parallelism().map_or(1, |n| n.get()),
+        )
+    }
+
+    /// The most tasks that run at once.
+    pub fn threads(&self) -> usize {
+        match self {
+            Parallelism::Sequential => 1,
+            Parallelism::Threads(n) => (*n).max(1),
+            #[cfg(feature = "rayon")]
+            Parallelism::Pool(pool) => pool.current_num_threads(),
+        }
+    }
+
+    /// `f` of every index of `range`, in order.
+    pub fn map<T, F>(&self, range: Range<u64>, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(u64) -> T + Sync,
+    {
+        match self {
+            Parallelism::Sequential => range.map(f).collect(),
+            Parallelism::Threads(n) => {
+                let threads = (*n).max(1);
+                let f = &f;
+                let mut results: Vec<(u64, T)> = thread::scope(|s| {
+                    let handles: Vec<_> = (0..threads)
+                        .map(|t| {
+                            let range = range.clone();
+                            s.spawn(move || {
+                                range
+                                    .skip(t)
+                                    .step_by(threads)
+                                    .map(|i| (i, f(i)))
+                                    .collect::<Vec<_>>()
+                            })
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .flat_map(|h| h.join().expect("worker thread panicked"))
+                        .collect()
+                });
+                results.sort_by_key(|r| r.0);
+                results.into_iter().map(|r| r.1).collect()
+            }
+            #[cfg(feature = "rayon")]
+            Parallelism::Pool(pool) => {
+                use rayon::prelude::*;
+                let len = (range.end.saturating_sub(range.start)) as usize;
+                pool.install(|| {
+                    (0..len)
+                        .into_par_iter()
+                        .map(|i| f(range.start + i as u64))
+                        .collect()
+                })
+            }
+        }
+    }
+}
+
+/// The solvers are handles to immutable tables, for any thread to use.
+const _: () = {
+    fn shareable<T: Send + Sync + Clone>() {}
+    let _ = shareable::<Solver>;
+    let _ = shareable::<CubeContext>;
+    let _ = shareable::<crate::optimal::Optimal>;
+    let _ = shareable::<crate::cross::CrossSolver>;
+    let _ = shareable::<crate::twogen::TwoGenSolver>;
+};
+
+/// Search settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Longest solution `CubeContext::solve` looks for.
+    pub max_length: u32,
+    /// Where batches of solves run.
+    pub parallelism: Parallelism,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_length: 21,
+            parallelism: Parallelism::default(),
+        }
+    }
+}
+
+/// Tables, backend and configuration, shared by the calls that use them.
+///
+/// The tables are built on first use, so creating a context is cheap. A
+/// context is `Sync`; share one between threads by reference, or clone it:
+/// clones share the tables once built.
+#[derive(Clone)]
+pub struct CubeContext {
+    config: Config,
+    backend: Backend,
+    solver: OnceLock<Solver>,
+}
+
+impl Default for CubeContext {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl CubeContext {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            backend: Backend::current(),
+            solver: OnceLock::new(),
+        }
+    }
+
+    /// A context around tables that are already built.
+    pub fn with_solver(config: Config, solver: Solver) -> Self {
+        let context = Self::new(config);
+        let _ = context.solver.set(solver);
+        context
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// The two-phase solver, building its tables if this is the first call.
+    pub fn solver(&self) -> &Solver {
+        self.solver.get_or_init(Solver::new)
+    }
+
+    /// A solution of at most `config().max_length` moves; see
+    /// `Solver::solve`.
+    pub fn solve(&self, cube: &Cube) -> Option<Alg> {
+        self.solver().solve(cube, self.config.max_length)
+    }
+
+    /// The shortest solution found in `time`; see
+    /// `Solver::solve_with_deadline`. Building the tables, on first use, is
+    /// not counted in `time`.
+    pub fn solve_with_deadline(
+        &self,
+        cube: &Cube,
+        time: std::time::Duration,
+    ) -> Option<Alg> {
+        self.solver().solve_with_deadline(cube, time)
+    }
+
+    /// `solve_with_deadline` warm-started from `hint`; see
+    /// `Solver::solve_with_hint`.
+    pub fn solve_with_hint(
+        &self,
+        cube: &Cube,
+        hint: &Alg,
+        time: std::time::Duration,
+    ) -> Option<Alg> {
+        self.solver().solve_with_hint(cube, hint, time)
+    }
+
+    /// `solve` of each of `cubes`, in order, run as
+    /// `config().parallelism` allows.
+    pub fn solve_batch(&self, cubes: &[Cube]) -> Vec<Option<Alg>> {
+        let solver = self.solver();
+        self.config.parallelism.map(0..cubes.len() as u64, |i| {
+            solver.solve(&cubes[i as usize], self.config.max_length)
+        })
+    }
+
+    /// The scrambles of `seed`; see `scramble`.
+    pub fn scrambles(&self, seed: u64) -> Scrambles<'_> {
+        Scrambles::new(self.solver(), seed)
+    }
+}
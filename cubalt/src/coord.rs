@@ -0,0 +1,421 @@
+//! Coordinates: small integer summaries of part of a puzzle state.
+use crate::cube::{Corner, Cube, Edge};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::layout::PieceLayout;
+use crate::puzzle::Puzzle;
+use crate::types::*;
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "derive")]
+pub use cubalt_derive::Coordinate;
+
+/// A coordinate on puzzle `P`.
+///
+/// For table building the coordinate of `p.apply(m)` must depend only on
+/// the coordinate of `p` and on `m`. With the `derive` feature this can be
+/// derived from a rank/unrank pair; see `cubalt_derive::Coordinate`.
+pub trait Coordinate<P: Puzzle> {
+    /// Number of distinct values; coordinates are 0..SIZE.
+    const SIZE: usize;
+
+    fn rank(p: &P) -> usize;
+
+    /// Some state with coordinate `c`.
+    fn unrank(c: usize) -> P;
+}
+
+/// Corner orientation, 0..3^7.
+pub struct CornerOrient;
+
+impl Coordinate<Cube> for CornerOrient {
+    const SIZE: usize = 2187;
+
+    fn rank(p: &Cube) -> usize {
+        p.corner_orient().0 as usize
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let co = crate::avx2::unrank_corner_orient(Cori(c as u32)) as u64;
+        Cube::new(co | 0x0706050403020100, 0x0b0a0908, 0x0706050403020100)
+    }
+}
+
+/// Edge orientation, 0..2^11. The last edge's flip is implied.
+pub struct EdgeOrient;
+
+impl Coordinate<Cube> for EdgeOrient {
+    const SIZE: usize = 2048;
+
+    fn rank(p: &Cube) -> usize {
+        (p.edge_bitmask(4) & 0x7ff) as usize
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let parity = (c as u32).count_ones() & 1;
+        let mut cube = Cube::identity();
+        cube.xor_edge_orient(Eori(c as u32 | parity << 11));
+        cube
+    }
+}
+
+/// Binomial coefficient, 0 when k > n.
+const fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let mut r = 1;
+    let mut i = 0;
+    while i < k {
+        r = r * (n - i) / (i + 1);
+        i += 1;
+    }
+    r
+}
+
+/// Lehmer-code rank of a permutation of 0..n.
+pub(crate) fn rank_perm(p: &[u8]) -> usize {
+    let mut r = 0;
+    for i in 0..p.len() {
+        let smaller = p[i + 1..].iter().filter(|&&x| x < p[i]).count();
+        r = r * (p.len() - i) + smaller;
+    }
+    r
+}
+
+/// Inverse of `rank_perm`.
+pub(crate) fn unrank_perm(mut r: usize, p: &mut [u8]) {
+    let n = p.len();
+    let mut left: Vec<u8> = (0..n as u8).collect();
+    let mut digits = vec![0; n];
+    for i in (0..n).rev() {
+        digits[i] = r % (n - i);
+        r /= n - i;
+    }
+    for i in 0..n {
+        p[i] = left.remove(digits[i]);
+    }
+}
+
+fn edge_pieces(c: &Cube) -> [u8; 12] {
+    let mut p = [0; 12];
+    for (x, e) in p.iter_mut().zip(c.edges()) {
+        *x = EDGES.piece(e.0);
+    }
+    p
+}
+
+fn set_edge_pieces(c: &mut Cube, p: &[u8; 12]) {
+    for (e, &x) in c.edges_mut().iter_mut().zip(p.iter()) {
+        *e = Edge(EDGES.pack(x, 0));
+    }
+}
+
+/// Positions of the four E-slice edges (FR FL BL BR), 0..C(12,4). Zero
+/// when they are in the slice, in any order.
+pub struct UDSlice;
+
+impl Coordinate<Cube> for UDSlice {
+    const SIZE: usize = 495;
+
+    fn rank(p: &Cube) -> usize {
+        let mut r = 0;
+        let mut seen = 0;
+        for (j, &x) in edge_pieces(p).iter().enumerate().rev() {
+            if x >= 8 {
+                seen += 1;
+                r += choose(11 - j, seen);
+            }
+        }
+        r
+    }
+
+    fn unrank(mut c: usize) -> Cube {
+        let mut pieces = [0; 12];
+        let (mut slice, mut other) = (8, 0);
+        let mut left = 4;
+        for (j, x) in pieces.iter_mut().enumerate() {
+            if left > 0 && c >= choose(11 - j, left) {
+                c -= choose(11 - j, left);
+                left -= 1;
+                *x = slice;
+                slice += 1;
+            } else {
+                *x = other;
+                other += 1;
+            }
+        }
+        let mut cube = Cube::identity();
+        set_edge_pieces(&mut cube, &pieces);
+        cube
+    }
+}
+
+/// Corner permutation, 0..8!.
+pub struct CornerPerm;
+
+impl Coordinate<Cube> for CornerPerm {
+    const SIZE: usize = 40320;
+
+    fn rank(p: &Cube) -> usize {
+        let mut pieces = [0; 8];
+        for (x, c) in pieces.iter_mut().zip(p.corners()) {
+            *x = CORNERS.piece(c.0);
+        }
+        rank_perm(&pieces)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut pieces = [0; 8];
+        unrank_perm(c, &mut pieces);
+        let mut cube = Cube::identity();
+        for (x, &p) in cube.corners_mut().iter_mut().zip(pieces.iter()) {
+            *x = Corner(CORNERS.pack(p, 0));
+        }
+        cube
+    }
+}
+
+/// Permutation of the eight U and D edges, 0..8!. Only meaningful when
+/// they are all in the U and D layers.
+pub struct UDEdgePerm;
+
+impl Coordinate<Cube> for UDEdgePerm {
+    const SIZE: usize = 40320;
+
+    fn rank(p: &Cube) -> usize {
+        rank_perm(&edge_pieces(p)[..8])
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut pieces = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        unrank_perm(c, &mut pieces[..8]);
+        let mut cube = Cube::identity();
+        set_edge_pieces(&mut cube, &pieces);
+        cube
+    }
+}
+
+/// Permutation of the four E-slice edges, 0..4!. Only meaningful when
+/// they are all in the slice.
+pub struct SlicePerm;
+
+impl Coordinate<Cube> for SlicePerm {
+    const SIZE: usize = 24;
+
+    fn rank(p: &Cube) -> usize {
+        let mut pieces = [0; 4];
+        for (x, &e) in pieces.iter_mut().zip(edge_pieces(p)[8..].iter()) {
+            *x = e.wrapping_sub(8);
+        }
+        rank_perm(&pieces)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut pieces = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        unrank_perm(c, &mut pieces[8..]);
+        for x in pieces[8..].iter_mut() {
+            *x += 8;
+        }
+        let mut cube = Cube::identity();
+        set_edge_pieces(&mut cube, &pieces);
+        cube
+    }
+}
+
+/// `CornerOrient` and `UDSlice` together, twist-major.
+pub struct TwistSlice;
+
+impl Coordinate<Cube> for TwistSlice {
+    const SIZE: usize = CornerOrient::SIZE * UDSlice::SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        CornerOrient::rank(p) * UDSlice::SIZE + UDSlice::rank(p)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut cube = UDSlice::unrank(c % UDSlice::SIZE);
+        let corners = CornerOrient::unrank(c / UDSlice::SIZE);
+        cube.corners_mut().copy_from_slice(corners.corners());
+        cube
+    }
+}
+
+/// `EdgeOrient` and `UDSlice` together, flip-major.
+pub struct FlipSlice;
+
+impl Coordinate<Cube> for FlipSlice {
+    const SIZE: usize = EdgeOrient::SIZE * UDSlice::SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        EdgeOrient::rank(p) * UDSlice::SIZE + UDSlice::rank(p)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut cube = UDSlice::unrank(c % UDSlice::SIZE);
+        let flip = (c / UDSlice::SIZE) as u32;
+        cube.xor_edge_orient(Eori(flip | (flip.count_ones() & 1) << 11));
+        cube
+    }
+}
+
+/// `CornerPerm` and `SlicePerm` together, for states in
+/// <U, D, R2, L2, F2, B2>.
+pub struct CornerSlicePerm;
+
+impl Coordinate<Cube> for CornerSlicePerm {
+    const SIZE: usize = CornerPerm::SIZE * SlicePerm::SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        CornerPerm::rank(p) * SlicePerm::SIZE + SlicePerm::rank(p)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut cube = SlicePerm::unrank(c % SlicePerm::SIZE);
+        let corners = CornerPerm::unrank(c / SlicePerm::SIZE);
+        cube.corners_mut().copy_from_slice(corners.corners());
+        cube
+    }
+}
+
+/// `UDEdgePerm` and `SlicePerm` together, for states in
+/// <U, D, R2, L2, F2, B2>.
+pub struct EdgeSlicePerm;
+
+impl Coordinate<Cube> for EdgeSlicePerm {
+    const SIZE: usize = UDEdgePerm::SIZE * SlicePerm::SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        UDEdgePerm::rank(p) * SlicePerm::SIZE + SlicePerm::rank(p)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let mut cube = UDEdgePerm::unrank(c / SlicePerm::SIZE);
+        let slice = SlicePerm::unrank(c % SlicePerm::SIZE);
+        cube.edges_mut()[8..].copy_from_slice(&slice.edges()[8..]);
+        cube
+    }
+}
+
+/// `CornerPerm` and `CornerOrient` together, perm-major: everything about
+/// the corners.
+pub struct Corners;
+
+impl Coordinate<Cube> for Corners {
+    const SIZE: usize = CornerPerm::SIZE * CornerOrient::SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        CornerPerm::rank(p) * CornerOrient::SIZE + CornerOrient::rank(p)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let perm = CornerPerm::unrank(c / CornerOrient::SIZE);
+        let orient = CornerOrient::unrank(c % CornerOrient::SIZE);
+        perm.compose(&orient)
+    }
+}
+
+/// Number of ways to put `k` of `n` pieces in `n` slots, each in one of
+/// `oris` orientations.
+const fn placements(n: usize, k: u32, oris: usize) -> usize {
+    let mut r = 1;
+    let mut i = 0;
+    while i < k as usize {
+        r *= (n - i) * oris;
+        i += 1;
+    }
+    r
+}
+
+/// Slot and orientation of each piece of `set`, lowest piece first.
+fn rank_pieces(layout: &PieceLayout, bytes: &[u8], set: u16) -> usize {
+    let (mut r, mut used) = (0, 0u16);
+    let pieces = (0..layout.count as u8).filter(|p| set & 1 << p != 0);
+    for (k, piece) in pieces.enumerate() {
+        let slot = bytes
+            .iter()
+            .position(|&v| layout.piece(v) == piece)
+            .expect("every piece is somewhere");
+        // The slot's index among those not yet taken.
+        let free = (0..slot).filter(|&s| used & 1 << s == 0).count();
+        r = r * (layout.count - k) + free;
+        r = r * layout.ori_modulus as usize + layout.ori(bytes[slot]) as usize;
+        used |= 1 << slot;
+    }
+    r
+}
+
+/// Inverse of `rank_pieces`; the other pieces fill the other slots in
+/// order, unoriented.
+fn unrank_pieces(
+    layout: &PieceLayout,
+    mut r: usize,
+    set: u16,
+    bytes: &mut [u8],
+) {
+    let pieces: Vec<u8> = (0..layout.count as u8)
+        .filter(|p| set & 1 << p != 0)
+        .collect();
+    let m = layout.ori_modulus as usize;
+    let mut digits = vec![(0, 0); pieces.len()];
+    for k in (0..pieces.len()).rev() {
+        let ori = (r % m) as u8;
+        r /= m;
+        digits[k] = (r % (layout.count - k), ori);
+        r /= layout.count - k;
+    }
+    let mut used = 0u16;
+    for (&piece, &(free, ori)) in pieces.iter().zip(digits.iter()) {
+        let slot = (0..layout.count)
+            .filter(|&s| used & 1 << s == 0)
+            .nth(free)
+            .expect("digit in range");
+        bytes[slot] = layout.pack(piece, ori);
+        used |= 1 << slot;
+    }
+    let mut others = (0..layout.count as u8).filter(|p| set & 1 << p == 0);
+    for (s, b) in bytes.iter_mut().enumerate() {
+        if used & 1 << s == 0 {
+            *b = layout.pack(others.next().expect("a piece per slot"), 0);
+        }
+    }
+}
+
+/// Slots and orientations of the edges in `E` and the corners in `C` (bit
+/// i = piece i), wherever the rest are: the coordinate of a block, whose
+/// pruning table gives the exact length of solving it.
+pub struct Pieces<const E: u16, const C: u8>;
+
+impl<const E: u16, const C: u8> Pieces<E, C> {
+    const CORNER_SIZE: usize = placements(8, C.count_ones(), 3);
+}
+
+impl<const E: u16, const C: u8> Coordinate<Cube> for Pieces<E, C> {
+    const SIZE: usize = placements(12, E.count_ones(), 2) * Self::CORNER_SIZE;
+
+    fn rank(p: &Cube) -> usize {
+        let mut edges = [0; 12];
+        for (x, e) in edges.iter_mut().zip(p.edges()) {
+            *x = e.0;
+        }
+        let mut corners = [0; 8];
+        for (x, c) in corners.iter_mut().zip(p.corners()) {
+            *x = c.0;
+        }
+        rank_pieces(&EDGES, &edges, E) * Self::CORNER_SIZE
+            + rank_pieces(&CORNERS, &corners, C as u16)
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let (mut edges, mut corners) = ([0; 12], [0; 8]);
+        unrank_pieces(&EDGES, c / Self::CORNER_SIZE, E, &mut edges);
+        unrank_pieces(&CORNERS, c % Self::CORNER_SIZE, C as u16, &mut corners);
+        let mut cube = Cube::identity();
+        for (x, &e) in cube.edges_mut().iter_mut().zip(edges.iter()) {
+            *x = Edge(e);
+        }
+        for (x, &c) in cube.corners_mut().iter_mut().zip(corners.iter()) {
+            *x = Corner(c);
+        }
+        cube
+    }
+}
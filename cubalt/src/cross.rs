@@ -0,0 +1,126 @@
+//! Optimal solutions of the D cross.
+//!
+//! The cross is four edges, so a coordinate holding just their slots and
+//! flips is small enough (190080 values) for its pruning table to give the
+//! exact length of every cross.
+use crate::alg::Alg;
+use crate::coord::Coordinate;
+use crate::cube::{Cube, Edge};
+use crate::layout::packed3::EDGES;
+use crate::notation::Face;
+use crate::pattern::{AnyOf, Pattern};
+use crate::rotation::CubeRotation;
+use crate::search::{Heuristic, Ida, MinOf, Rotated, TableHeuristic};
+
+/// First of the cross edges DR, DF, DL, DB.
+const FIRST: u8 = 4;
+
+/// Slots and flips of the four cross edges.
+pub struct CrossEdges;
+
+impl Coordinate<Cube> for CrossEdges {
+    const SIZE: usize = 12 * 11 * 10 * 9 * 16;
+
+    fn rank(p: &Cube) -> usize {
+        let (mut r, mut flips, mut used) = (0, 0, 0u16);
+        for k in 0..4 {
+            let slot = p
+                .edges()
+                .iter()
+                .position(|e| EDGES.piece(e.0) == FIRST + k)
+                .expect("every edge is somewhere");
+            // The slot's index among those not yet taken.
+            let free = (0..slot).filter(|&s| used & 1 << s == 0).count();
+            r = r * (12 - k as usize) + free;
+            flips = flips << 1 | EDGES.ori(p.edges()[slot].0) as usize;
+            used |= 1 << slot;
+        }
+        r << 4 | flips
+    }
+
+    fn unrank(c: usize) -> Cube {
+        let (mut r, flips) = (c >> 4, c & 15);
+        let mut digits = [0; 4];
+        for k in (0..4).rev() {
+            digits[k] = r % (12 - k);
+            r /= 12 - k;
+        }
+        let mut slots = [None; 12];
+        for (k, &d) in digits.iter().enumerate() {
+            let slot = (0..12)
+                .filter(|&s| slots[s].is_none())
+                .nth(d)
+                .expect("digit in range");
+            let flip = (flips >> (3 - k) & 1) as u8;
+            slots[slot] = Some(Edge(EDGES.pack(FIRST + k as u8, flip)));
+        }
+        let mut others = (0..12u8).filter(|p| !(FIRST..FIRST + 4).contains(p));
+        let mut cube = Cube::identity();
+        for (e, s) in cube.edges_mut().iter_mut().zip(slots) {
+            *e = s
+                .unwrap_or_else(|| Edge(EDGES.pack(others.next().unwrap(), 0)));
+        }
+        cube
+    }
+}
+
+/// Optimal D crosses, from a pruning table built in a fraction of a second.
+/// Clones share the table.
+#[derive(Clone)]
+pub struct CrossSolver {
+    table: TableHeuristic<Cube, CrossEdges>,
+}
+
+impl Default for CrossSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrossSolver {
+    pub fn new() -> Self {
+        Self {
+            table: TableHeuristic::build(),
+        }
+    }
+
+    /// Moves in the shortest cross of `cube`, at most 8.
+    pub fn length(&self, cube: &Cube) -> u32 {
+        self.table.estimate(cube)
+    }
+
+    /// A shortest cross of `cube`.
+    pub fn solve(&self, cube: &Cube) -> Alg {
+        let goal = Pattern::cross();
+        let mut ida = Ida::new(&self.table, &goal, 8);
+        Alg(ida.solve(cube).expect("every cross is within 8 moves"))
+    }
+
+    /// A shortest cross on any face of `cube`, and that face: the colour
+    /// neutral solver's first step.
+    pub fn solve_any(&self, cube: &Cube) -> (Face, Alg) {
+        let rotations = Face::ALL.map(|f| CubeRotation::taking(f, Face::D));
+        let goals = AnyOf(
+            rotations
+                .iter()
+                .map(|&rotation| Rotated {
+                    inner: Pattern::cross(),
+                    rotation,
+                })
+                .collect(),
+        );
+        let bound = MinOf(
+            rotations
+                .iter()
+                .map(|&rotation| Rotated {
+                    inner: &self.table,
+                    rotation,
+                })
+                .collect(),
+        );
+        let mut ida = Ida::new(&bound, &goals, 8);
+        let (face, moves) =
+            ida.solve_any(cube).expect("every cross is within 8 moves");
+        (Face::ALL[face], Alg(moves))
+    }
+}
@@ -1,8 +1,12 @@
 #![allow(non_snake_case)]
 use crate::avx2;
+use crate::cycles;
+use crate::layout::packed3::{self, CORNERS, EDGES};
+use crate::layout::PieceLayout;
+use crate::notation::Move;
 use crate::sse;
 use crate::types::*;
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
 
 /// The basic SIMD-friendly cube representation.
 ///
@@ -28,9 +32,12 @@ use std::arch::x86_64::*;
 ///   - = unused (zero)
 ///   O = orientation (0..=2)
 ///   C = corner index (0..=7)
+///
+/// The register itself is private so that the layout can change; use
+/// `to_bytes`/`from_bytes` and the lane accessors instead.
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy)]
-pub struct Cube(pub m256i);
+pub struct Cube(pub(crate) m256i);
 
 /// The low 128-bit lane of the m256 that stores edge state.
 #[repr(transparent)]
@@ -52,26 +59,78 @@ pub struct Edge(pub u8);
 #[derive(Debug, Clone, Copy)]
 pub struct Corner(pub u8);
 
+/// Whether `lane` holds a permutation of `layout`'s pieces with
+/// orientations in range, and its unused bytes their identity values.
+fn lane_ok(layout: &PieceLayout, lane: &[u8]) -> bool {
+    let mut seen = 0u16;
+    lane.iter().enumerate().all(|(i, &v)| {
+        let (piece, ori) = (layout.piece(v), layout.ori(v));
+        let ok = if i < layout.count {
+            layout.pack(piece, ori) == v
+                && (piece as usize) < layout.count
+                && ori < layout.ori_modulus
+                && seen & 1 << piece == 0
+        } else {
+            v == i as u8
+        };
+        seen |= 1 << piece;
+        ok
+    })
+}
+
 impl Cube {
+    /// The solved state, for constants; `identity` is faster at run time.
+    pub const IDENTITY: Self =
+        Self::new(0x0706050403020100, 0x0b0a0908, 0x0706050403020100);
+
     #[inline(always)]
     pub fn identity() -> Self {
         Self(avx2::identity())
     }
 
-    pub fn new(corners: u64, edges_high: u64, edges_low: u64) -> Self {
-        Self(unsafe {
-            avx2::literal(
-                std::mem::transmute(corners),
-                std::mem::transmute(edges_high),
-                std::mem::transmute(edges_low),
-            )
-        })
+    /// Build a cube from the packed literal words used by the generated
+    /// tables (see `layout::packed3::pack`).
+    pub const fn new(corners: u64, edges_high: u64, edges_low: u64) -> Self {
+        let words = packed3::pack(corners, edges_high, edges_low);
+        Self(unsafe { core::mem::transmute::<[u64; 4], m256i>(words) })
+    }
+
+    /// The state given in cycle notation; see `cycles`.
+    pub const fn from_cycles(spec: &str) -> Self {
+        let [corners, edges_high, edges_low] = cycles::parse(spec);
+        Self::new(corners, edges_high, edges_low)
     }
 
     fn from_raw_m256(v: m256i) -> Self {
         Self(v)
     }
 
+    /// The register as bytes, in the layout described above.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        // m256i is 32 plain bytes, and more strictly aligned than [u8; 32].
+        unsafe { &*(&self.0 as *const m256i as *const [u8; 32]) }
+    }
+
+    /// A copy of the register as bytes, as `as_bytes` has them. Byte order
+    /// is that of the layout, not of the machine: byte 0 is always the edge
+    /// in slot 0, so a snapshot reads back the same anywhere with
+    /// `from_bytes`.
+    pub const fn to_bytes(&self) -> [u8; 32] {
+        unsafe { core::mem::transmute::<m256i, [u8; 32]>(self.0) }
+    }
+
+    /// A cube from bytes in the layout described above, if they are one:
+    /// each lane a permutation of its pieces with orientations in range,
+    /// and the unused bytes holding their identity values. The state need
+    /// not be reachable.
+    pub fn from_bytes(bytes: [u8; 32]) -> Option<Self> {
+        if lane_ok(&EDGES, &bytes[..16]) && lane_ok(&CORNERS, &bytes[16..]) {
+            Some(Self::from_bytes_unchecked(bytes))
+        } else {
+            None
+        }
+    }
+
     /// Parity of the edge + corner permutation
     #[inline(always)]
     pub fn parity(&self) -> bool {
@@ -80,7 +139,7 @@ impl Cube {
 
     pub fn edge_bitmask(&self, bit: u8) -> u32 {
         unsafe {
-            std::mem::transmute::<i32, u32>(avx2::bitmask(self.0, bit as i32))
+            core::mem::transmute::<i32, u32>(avx2::bitmask(self.0, bit as i32))
                 & 0xffff
         }
     }
@@ -88,7 +147,7 @@ impl Cube {
     // this can return a u16
     pub fn corner_bitmask(&self, bit: u8) -> u32 {
         unsafe {
-            std::mem::transmute::<i32, u32>(avx2::bitmask(self.0, bit as i32))
+            core::mem::transmute::<i32, u32>(avx2::bitmask(self.0, bit as i32))
                 >> 16
         }
     }
@@ -116,22 +175,245 @@ impl Cube {
     pub fn invert(&self) -> Self {
         Cube::from_raw_m256(avx2::invert(self.0))
     }
+
+    /// A fast 64-bit hash of the state.
+    #[inline(always)]
+    pub fn hash_fast(&self) -> u64 {
+        avx2::hash(self.0)
+    }
+
+    /// Conjugate by symmetry `s` (an index into `sym()`): S * self * S^-1.
+    /// Odd symmetries are reflections, which reverse corner twists, so they
+    /// compose mirrored.
+    pub fn sym_conjugate(&self, s: usize) -> Self {
+        let (sym, sym_inv) = (&SYM[s], &SYM[SYM_INV[s] as usize]);
+        if s & 1 == 1 {
+            sym.compose_mirror(self).compose_mirror(sym_inv)
+        } else {
+            sym.compose(self).compose(sym_inv)
+        }
+    }
+
+    /// Conjugate by symmetry `s % 48`, inverting first if `s >= 48`: the
+    /// group of 96 symmetries and antisymmetries. Each keeps the distance
+    /// from solved, so a table of distances need only hold one state of
+    /// each class.
+    pub fn antisym_conjugate(&self, s: usize) -> Self {
+        if s < 48 {
+            self.sym_conjugate(s)
+        } else {
+            self.invert().sym_conjugate(s - 48)
+        }
+    }
+
+    /// The representative of the state's class under `antisym_conjugate`:
+    /// the image with the smallest `to_key`, and an index giving it.
+    pub fn antisym_canonical(&self) -> (Self, usize) {
+        let inverse = self.invert();
+        (0..96)
+            .map(|s| {
+                let c = if s < 48 { self } else { &inverse };
+                (c.sym_conjugate(s % 48), s)
+            })
+            .min_by_key(|(c, _)| c.to_key())
+            .expect("96 symmetries")
+    }
+
+    /// Bitmask of the indices of `antisym_conjugate` that fix the state.
+    /// Its class has `96 / stabilizer.count_ones()` states.
+    pub fn antisym_stabilizer(&self) -> u128 {
+        let inverse = self.invert();
+        (0..96).fold(0, |mask, s| {
+            let c = if s < 48 { self } else { &inverse };
+            mask | ((c.sym_conjugate(s % 48) == *self) as u128) << s
+        })
+    }
 }
 
-impl std::ops::Not for Cube {
+// Scalar group operations, for computing tables in constants
+impl Cube {
+    const fn from_bytes_unchecked(bytes: [u8; 32]) -> Self {
+        Self(unsafe { core::mem::transmute::<[u8; 32], m256i>(bytes) })
+    }
+
+    /// Byte for byte what `avx2::compose_perhaps_mirror` does.
+    const fn compose_scalar(&self, other: &Self, mirror: bool) -> Self {
+        let (a, b) = (self.to_bytes(), other.to_bytes());
+        let mut out = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            let lane = i & 16;
+            let x = a[lane | (b[i] & 0x0f) as usize];
+            out[i] = if lane == 0 {
+                x ^ (b[i] & 0x10)
+            } else {
+                let (x_ori, y_ori) = (x >> 4, b[i] >> 4);
+                let ori = if mirror {
+                    (x_ori + 3 - y_ori) % 3
+                } else {
+                    (x_ori + y_ori) % 3
+                };
+                ori << 4 | (x & 0x0f)
+            };
+            i += 1;
+        }
+        Self::from_bytes_unchecked(out)
+    }
+
+    /// `compose`, evaluable in constants.
+    pub const fn const_compose(&self, other: &Self) -> Self {
+        self.compose_scalar(other, false)
+    }
+
+    /// `compose_mirror`, evaluable in constants.
+    pub const fn const_compose_mirror(&self, other: &Self) -> Self {
+        self.compose_scalar(other, true)
+    }
+
+    /// `invert`, evaluable in constants.
+    pub const fn const_invert(&self) -> Self {
+        let a = self.to_bytes();
+        let mut out = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            let lane = i & 16;
+            let ori = a[i] >> 4;
+            let ori = if lane == 0 { ori } else { (3 - ori) % 3 };
+            out[lane | (a[i] & 0x0f) as usize] = ori << 4 | (i & 0x0f) as u8;
+            i += 1;
+        }
+        Self::from_bytes_unchecked(out)
+    }
+
+    /// `sym_conjugate`, evaluable in constants.
+    pub const fn const_sym_conjugate(&self, s: usize) -> Self {
+        let (sym, sym_inv) = (Cube::sym(), Cube::sym_inv());
+        let mirror = s & 1 == 1;
+        sym[s]
+            .compose_scalar(self, mirror)
+            .compose_scalar(&sym[sym_inv[s] as usize], mirror)
+    }
+}
+
+static SYM: [Cube; 48] = Cube::sym();
+static SYM_INV: [u8; 48] = Cube::sym_inv();
+
+impl PartialEq for Cube {
+    fn eq(&self, other: &Self) -> bool {
+        avx2::equals(self.0, other.0)
+    }
+}
+
+impl Eq for Cube {}
+
+impl core::hash::Hash for Cube {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash_fast());
+    }
+}
+
+impl core::ops::Not for Cube {
     type Output = Self;
     fn not(self) -> Self {
         self.invert()
     }
 }
 
-impl std::ops::Mul for Cube {
+impl core::ops::Mul for Cube {
     type Output = Self;
     fn mul(self, other: Self) -> Self {
         self.compose(&other)
     }
 }
 
+impl Cube {
+    /// A reachable state built from random choices: `below(n)` must give a
+    /// value in `0..n`. Uniform over the cube group if `below` is uniform.
+    pub(crate) fn from_choices<E>(
+        mut below: impl FnMut(u8) -> Result<u8, E>,
+    ) -> Result<Self, E> {
+        // Fisher-Yates; returns whether the permutation is odd.
+        let mut shuffle = |items: &mut [u8]| {
+            let mut odd = false;
+            for i in (1..items.len()).rev() {
+                let j = below(i as u8 + 1)? as usize;
+                if i != j {
+                    items.swap(i, j);
+                    odd = !odd;
+                }
+            }
+            Ok(odd)
+        };
+        let mut corners = [0, 1, 2, 3, 4, 5, 6, 7];
+        let mut edges = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        if shuffle(&mut corners)? != shuffle(&mut edges)? {
+            edges.swap(0, 1);
+        }
+        let mut cube = Cube::identity();
+        let mut twist = 0;
+        for (i, &p) in corners.iter().enumerate() {
+            let ori = if i == 7 {
+                (3 - twist % 3) % 3
+            } else {
+                below(3)?
+            };
+            twist += ori;
+            cube.corners_mut()[i] = Corner(CORNERS.pack(p, ori));
+        }
+        let mut flip = 0;
+        for (i, &p) in edges.iter().enumerate() {
+            let ori = if i == 11 { flip & 1 } else { below(2)? };
+            flip += ori;
+            cube.edges_mut()[i] = Edge(EDGES.pack(p, ori));
+        }
+        Ok(cube)
+    }
+}
+
+/// Only reachable states: permutation parities agree, twists and flips
+/// sum to zero.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Cube {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Cube::from_choices(|n| u.int_in_range(0..=n - 1))
+    }
+}
+
+/// As its 54-letter facelet string; only reachable states are read back.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Cube {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(&crate::facelet::Facelets::from_cube(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Cube {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        d: D,
+    ) -> Result<Self, D::Error> {
+        let s: alloc::string::String = serde::Deserialize::deserialize(d)?;
+        s.parse::<crate::facelet::Facelets>()
+            .and_then(|f| f.to_cube())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Uniform over the reachable states.
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Cube> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Cube {
+        let result: Result<Cube, core::convert::Infallible> =
+            Cube::from_choices(|n| Ok(rng.gen_range(0..n)));
+        match result {
+            Ok(cube) => cube,
+            Err(e) => match e {},
+        }
+    }
+}
+
 // Edge/corner accessors
 impl Cube {
     /// uint8_t *edge = reinterpret_cast<uint8_t*>(&ev());
@@ -140,8 +422,8 @@ impl Cube {
         unsafe {
             let edge_lane = self.edge_lane_ref();
             let edge_arr =
-                std::mem::transmute::<&EdgeLane, &[Edge; 16]>(&edge_lane);
-            &edge_arr[0..=11]
+                core::mem::transmute::<&EdgeLane, &[Edge; 16]>(&edge_lane);
+            &edge_arr[..EDGES.count]
         }
     }
 
@@ -150,10 +432,10 @@ impl Cube {
     pub fn edges_mut(&mut self) -> &mut [Edge] {
         unsafe {
             let mut edge_lane = self.edge_lane_ref_mut();
-            let edge_arr = std::mem::transmute::<&mut EdgeLane, &mut [Edge; 16]>(
+            let edge_arr = core::mem::transmute::<&mut EdgeLane, &mut [Edge; 16]>(
                 &mut edge_lane,
             );
-            &mut edge_arr[0..=11]
+            &mut edge_arr[..EDGES.count]
         }
     }
 
@@ -161,8 +443,9 @@ impl Cube {
     #[inline(always)]
     pub fn edge_lane_ref(&self) -> &EdgeLane {
         unsafe {
-            let arr = std::mem::transmute::<&m256i, &[m128i; 2]>(&self.0);
-            let ret = std::mem::transmute::<&m128i, &EdgeLane>(&arr[0]);
+            let arr = core::mem::transmute::<&m256i, &[m128i; 2]>(&self.0);
+            let ret =
+                core::mem::transmute::<&m128i, &EdgeLane>(&arr[EDGES.lane]);
             ret
         }
     }
@@ -172,9 +455,10 @@ impl Cube {
     pub fn edge_lane_ref_mut(&mut self) -> &mut EdgeLane {
         unsafe {
             let arr =
-                std::mem::transmute::<&mut m256i, &mut [m128i; 2]>(&mut self.0);
-            let ret =
-                std::mem::transmute::<&mut m128i, &mut EdgeLane>(&mut arr[0]);
+                core::mem::transmute::<&mut m256i, &mut [m128i; 2]>(&mut self.0);
+            let ret = core::mem::transmute::<&mut m128i, &mut EdgeLane>(
+                &mut arr[EDGES.lane],
+            );
             ret
         }
     }
@@ -187,8 +471,8 @@ impl Cube {
             // let corner_lane = self.corner_lane().0;
             let corner_lane = self.corner_lane_ref();
             let corner_arr =
-                std::mem::transmute::<&CornerLane, &[Corner; 16]>(&corner_lane);
-            &corner_arr[0..=7]
+                core::mem::transmute::<&CornerLane, &[Corner; 16]>(&corner_lane);
+            &corner_arr[..CORNERS.count]
         }
     }
 
@@ -197,11 +481,11 @@ impl Cube {
     pub fn corners_mut(&mut self) -> &mut [Corner] {
         unsafe {
             let mut corner_lane = self.corner_lane_ref_mut();
-            let corner_arr = std::mem::transmute::<
+            let corner_arr = core::mem::transmute::<
                 &mut CornerLane,
                 &mut [Corner; 16],
             >(&mut corner_lane);
-            &mut corner_arr[0..=7]
+            &mut corner_arr[..CORNERS.count]
         }
     }
 
@@ -209,11 +493,11 @@ impl Cube {
     #[inline(always)]
     pub fn corner_lane_ref(&self) -> &CornerLane {
         unsafe {
-            // let arr = std::mem::transmute::<&m256i, &[m128i; 2]>(&self.0);
-            // let ret = std::mem::transmute::<&m128i, &CornerLane>(&arr[1]);
+            // let arr = core::mem::transmute::<&m256i, &[m128i; 2]>(&self.0);
+            // let ret = core::mem::transmute::<&m128i, &CornerLane>(&arr[1]);
             // ret
             let arr = &self.0 as *const _ as *const [m128i; 2];
-            let ret = &(*arr)[1] as *const _ as *const CornerLane;
+            let ret = &(*arr)[CORNERS.lane] as *const _ as *const CornerLane;
             &*ret
         }
     }
@@ -222,25 +506,167 @@ impl Cube {
     #[inline(always)]
     pub fn corner_lane_ref_mut(&mut self) -> &mut CornerLane {
         unsafe {
-            // let arr = std::mem::transmute::<&mut m256i, &mut [m128i; 2]>(&mut self.0);
-            // let ret = std::mem::transmute::<&mut m128i, &mut CornerLane>(&mut arr[1]);
+            // let arr = core::mem::transmute::<&mut m256i, &mut [m128i; 2]>(&mut self.0);
+            // let ret = core::mem::transmute::<&mut m128i, &mut CornerLane>(&mut arr[1]);
             // ret
             let arr = &mut self.0 as *mut _ as *mut [m128i; 2];
-            let ret = &mut (*arr)[1] as *mut _ as *mut CornerLane;
+            let ret = &mut (*arr)[CORNERS.lane] as *mut _ as *mut CornerLane;
             &mut *ret
         }
     }
 
     /// A mutable reference to the low half of the m128 that actually stores
-    /// corner state.
+    /// corner state. Nothing checks what is written; `set_corners_64`
+    /// does in debug builds.
     /// u64()[2]
     pub fn corners_64_mut(&mut self) -> &mut u64 {
-        // let arr = unsafe { std::mem::transmute::<&mut Cube, &mut [u64; 4]>(self) };
+        // let arr = unsafe { core::mem::transmute::<&mut Cube, &mut [u64; 4]>(self) };
         // &mut arr[2]
-        unsafe { &mut (*(self as *mut _ as *mut [u64; 4]))[2] }
+        unsafe { &mut (*(self as *mut _ as *mut [u64; 4]))[CORNERS.lane * 2] }
+    }
+
+    /// The corners as a little-endian word: byte i is the corner in slot i.
+    pub fn corners_64(&self) -> u64 {
+        self.corners_128() as u64
+    }
+
+    /// Set the corners from a word as `corners_64` gives. Debug builds
+    /// check that it is a permutation with orientations in range.
+    pub fn set_corners_64(&mut self, corners: u64) {
+        let padding = 0x0f0e0d0c0b0a0908u128 << 64;
+        self.set_corners_128(corners as u128 | padding);
+    }
+
+    /// The whole corner lane, unused bytes included, little-endian.
+    pub fn corners_128(&self) -> u128 {
+        unsafe { core::mem::transmute::<m128i, u128>(self.corner_lane_ref().0) }
+    }
+
+    /// Set the corner lane from a value as `corners_128` gives. Debug
+    /// builds check it, as `from_bytes` does.
+    pub fn set_corners_128(&mut self, corners: u128) {
+        debug_assert!(
+            lane_ok(&CORNERS, &corners.to_le_bytes()),
+            "not a corner lane: {:#034x}",
+            corners
+        );
+        let lane = unsafe { core::mem::transmute::<u128, m128i>(corners) };
+        *self.corner_lane_ref_mut() = CornerLane(lane);
+    }
+
+    /// The whole edge lane, unused bytes included, little-endian: byte i
+    /// is the edge in slot i.
+    pub fn edges_128(&self) -> u128 {
+        unsafe { core::mem::transmute::<m128i, u128>(self.edge_lane_ref().0) }
+    }
+
+    /// Set the edge lane from a value as `edges_128` gives. Debug builds
+    /// check it, as `from_bytes` does.
+    pub fn set_edges_128(&mut self, edges: u128) {
+        debug_assert!(
+            lane_ok(&EDGES, &edges.to_le_bytes()),
+            "not an edge lane: {:#034x}",
+            edges
+        );
+        let lane = unsafe { core::mem::transmute::<u128, m128i>(edges) };
+        *self.edge_lane_ref_mut() = EdgeLane(lane);
+    }
+
+    /// A copy of the edge lane.
+    pub fn edge_lane(&self) -> EdgeLane {
+        *self.edge_lane_ref()
+    }
+
+    /// A copy of the corner lane.
+    pub fn corner_lane(&self) -> CornerLane {
+        *self.corner_lane_ref()
+    }
+
+    /// The cube with these edges and corners.
+    pub fn from_lanes(edges: EdgeLane, corners: CornerLane) -> Self {
+        let mut lanes = [edges.0; 2];
+        lanes[EDGES.lane] = edges.0;
+        lanes[CORNERS.lane] = corners.0;
+        Self(unsafe { core::mem::transmute::<[m128i; 2], m256i>(lanes) })
+    }
+}
+
+/// The edges alone, for work that never needs the corners. Operations
+/// match those of `Cube` on its edge lane.
+impl EdgeLane {
+    pub fn identity() -> Self {
+        Cube::identity().edge_lane()
+    }
+
+    pub fn compose(&self, other: &Self) -> Self {
+        Self(sse::compose_edge(self.0, other.0))
+    }
+
+    pub fn invert(&self) -> Self {
+        Self(sse::invert_edge(self.0))
+    }
+
+    /// Bit i set if the edge in slot i is flipped.
+    pub fn orientation(&self) -> Eori {
+        Eori(sse::bitmask(self.0, 4) as u32 & 0xfff)
+    }
+
+    /// Flip the edges in the slots set in `eori`.
+    pub fn xor_orientation(&mut self, eori: Eori) {
+        self.0 = sse::xor_edge_orient(self.0, eori);
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        // Edge is a transparent byte.
+        let bytes = unsafe { &*(&self.0 as *const m128i as *const [Edge; 16]) };
+        &bytes[..EDGES.count]
+    }
+}
+
+impl PartialEq for EdgeLane {
+    fn eq(&self, other: &Self) -> bool {
+        sse::equals(self.0, other.0)
+    }
+}
+
+impl Eq for EdgeLane {}
+
+/// The corners alone, for work that never needs the edges. Operations
+/// match those of `Cube` on its corner lane.
+impl CornerLane {
+    pub fn identity() -> Self {
+        Cube::identity().corner_lane()
+    }
+
+    pub fn compose(&self, other: &Self) -> Self {
+        Self(sse::compose_corner(self.0, other.0))
+    }
+
+    pub fn invert(&self) -> Self {
+        Self(sse::invert_corner(self.0))
+    }
+
+    /// The corner orientation coordinate, as `Cube::corner_orient`.
+    pub fn orientation(&self) -> Cori {
+        sse::corner_orient(self.0)
+    }
+
+    pub fn corners(&self) -> &[Corner] {
+        // Corner is a transparent byte.
+        let bytes =
+            unsafe { &*(&self.0 as *const m128i as *const [Corner; 16]) };
+        &bytes[..CORNERS.count]
+    }
+}
+
+impl PartialEq for CornerLane {
+    fn eq(&self, other: &Self) -> bool {
+        sse::equals(self.0, other.0)
     }
 }
 
+impl Eq for CornerLane {}
+
 impl Cube {
     /// Set full edge permutation coordinate 0..479001599, and reset edge orientation.
     /// We set only the low 4 bits of every edge and zero the rest, so
@@ -270,156 +696,50 @@ impl Cube {
 
         edges[11] = Edge(table as u8);
     }
-}
 
-// -----------------------------------------------------------------------------------------------
-// Generated code: move and symmetry maps
-// -----------------------------------------------------------------------------------------------
+    /// Pack the state into 100 bits: 5 bits per edge, then 5 per corner.
+    /// Keys order the same way for the same layout, so sorted runs of keys
+    /// can be merged and searched.
+    pub fn to_key(&self) -> u128 {
+        let v = self.0;
+        unsafe {
+            let el = _pext_u64(avx2::edges_low(v) as u64, 0x1f1f1f1f1f1f1f1f);
+            let eh = _pext_u64(avx2::edges_high(v) as u64, 0x1f1f1f1f);
+            let c = _pext_u64(avx2::corners(v) as u64, 0x3737373737373737);
+            el as u128 | (eh as u128) << 40 | (c as u128) << 60
+        }
+    }
+
+    /// Inverse of `to_key`.
+    pub fn from_key(key: u128) -> Self {
+        unsafe {
+            let el = _pdep_u64(key as u64 & 0xff_ffff_ffff, 0x1f1f1f1f1f1f1f1f);
+            let eh = _pdep_u64((key >> 40) as u64 & 0xfffff, 0x1f1f1f1f);
+            let c = _pdep_u64((key >> 60) as u64, 0x3737373737373737);
+            Cube::new(c, eh, el)
+        }
+    }
+}
 
 impl Cube {
-    /// S_URF3 - 120-degree clockwise rotation on URF-DBL axis (x y)
-    pub fn S_URF3() -> Self {
-        Self::new(0x1226172321152410, 0x12161410, 0x0a170b1309150811)
-    }
-
-    /// The inverse of S_URF3
-    pub fn S_URF3i() -> Self {
-        Self::new(0x0203000106070405, 0x0a0b0809, 0x0300010207040506)
-    }
-
-    /// S_U4   - 90-degree clockwise rotation on U-D axis (y)
-    pub fn S_U4() -> Self {
-        Self::new(0x0605040702010003, 0x1a19181b, 0x0605040702010003)
-    }
-
-    /// S_LR2  - Reflection left to right
-    pub fn S_LR2() -> Self {
-        Self::new(0x0607040502030001, 0x0a0b0809, 0x0704050603000102)
-    }
-
-    /// S_F2 - 180-degree rotation on F-B axis (z2)
-    pub fn S_F2() -> Self {
-        Self::new(0x0203000106070405, 0x0a0b0809, 0x0300010207040506)
-    }
-
-    /// M_U - 90-degree clockwise twist of the U face
-    pub fn M_U() -> Self {
-        Self::new(0x0706050402010003, 0x0b0a0908, 0x0706050402010003)
-    }
-
-    /// Move table:
-    /// U, U2, U', R, R2, R', F, F2, F', D, D2, D', L, L2, L', B, B2, B'
-    pub fn moves() -> [Self; 18] {
-        [
-            Cube::new(0x0706050402010003, 0x0b0a0908, 0x0706050402010003),
-            Cube::new(0x0706050401000302, 0x0b0a0908, 0x0706050401000302),
-            Cube::new(0x0706050400030201, 0x0b0a0908, 0x0706050400030201),
-            Cube::new(0x2306051710020124, 0x000a0904, 0x0706050b03020108),
-            Cube::new(0x0006050304020107, 0x080a090b, 0x0706050003020104),
-            Cube::new(0x2406051017020123, 0x040a0900, 0x070605080302010b),
-            Cube::new(0x0706142003022511, 0x0b0a1511, 0x0706180403021900),
-            Cube::new(0x0706000103020405, 0x0b0a0809, 0x0706010403020500),
-            Cube::new(0x0706112503022014, 0x0b0a1115, 0x0706190403021800),
-            Cube::new(0x0407060503020100, 0x0b0a0908, 0x0407060503020100),
-            Cube::new(0x0504070603020100, 0x0b0a0908, 0x0504070603020100),
-            Cube::new(0x0605040703020100, 0x0b0a0908, 0x0605040703020100),
-            Cube::new(0x0715210403261200, 0x0b060208, 0x07090504030a0100),
-            Cube::new(0x0701020403050600, 0x0b090a08, 0x0702050403060100),
-            Cube::new(0x0712260403211500, 0x0b020608, 0x070a050403090100),
-            Cube::new(0x1622050427130100, 0x17130908, 0x1a0605041b020100),
-            Cube::new(0x0203050406070100, 0x0a0b0908, 0x0306050407020100),
-            Cube::new(0x1327050422160100, 0x13170908, 0x1b0605041a020100),
-        ]
-    }
-
-    // TODO check this
-    pub fn move_sym_6() -> [[u8; 8]; 18] {
-        [
-            [0, 6, 3, 2, 8, 5, 0, 0],
-            [1, 7, 4, 1, 7, 4, 0, 0],
-            [2, 8, 5, 0, 6, 3, 0, 0],
-            [3, 0, 6, 5, 2, 8, 0, 0],
-            [4, 1, 7, 4, 1, 7, 0, 0],
-            [5, 2, 8, 3, 0, 6, 0, 0],
-            [6, 3, 0, 8, 5, 2, 0, 0],
-            [7, 4, 1, 7, 4, 1, 0, 0],
-            [8, 5, 2, 6, 3, 0, 0, 0],
-            [9, 15, 12, 11, 17, 14, 0, 0],
-            [10, 16, 13, 10, 16, 13, 0, 0],
-            [11, 17, 14, 9, 15, 12, 0, 0],
-            [12, 9, 15, 14, 11, 17, 0, 0],
-            [13, 10, 16, 13, 10, 16, 0, 0],
-            [14, 11, 17, 12, 9, 15, 0, 0],
-            [15, 12, 9, 17, 14, 11, 0, 0],
-            [16, 13, 10, 16, 13, 10, 0, 0],
-            [17, 14, 11, 15, 12, 9, 0, 0],
-        ]
-    }
-
-    /// Symmetries (0..47):
-    /// S_LR2  (0, 1)
-    /// S_F2   (0, 2)
-    /// S_U4   (0, 4, 8, 12)
-    /// S_URF3 (0, 16, 32)
-    pub fn sym() -> [Self; 48] {
-        [
-            Cube::new(0x0706050403020100, 0x0b0a0908, 0x0706050403020100),
-            Cube::new(0x0607040502030001, 0x0a0b0809, 0x0704050603000102),
-            Cube::new(0x0203000106070405, 0x0a0b0809, 0x0300010207040506),
-            Cube::new(0x0302010007060504, 0x0b0a0908, 0x0302010007060504),
-            Cube::new(0x0605040702010003, 0x1a19181b, 0x0605040702010003),
-            Cube::new(0x0506070401020300, 0x191a1b18, 0x0607040502030001),
-            Cube::new(0x0102030005060704, 0x191a1b18, 0x0203000106070405),
-            Cube::new(0x0201000306050407, 0x1a19181b, 0x0201000306050407),
-            Cube::new(0x0504070601000302, 0x09080b0a, 0x0504070601000302),
-            Cube::new(0x0405060700010203, 0x08090a0b, 0x0506070401020300),
-            Cube::new(0x0001020304050607, 0x08090a0b, 0x0102030005060704),
-            Cube::new(0x0100030205040706, 0x09080b0a, 0x0100030205040706),
-            Cube::new(0x0407060500030201, 0x181b1a19, 0x0407060500030201),
-            Cube::new(0x0704050603000102, 0x1b18191a, 0x0405060700010203),
-            Cube::new(0x0300010207040506, 0x1b18191a, 0x0001020304050607),
-            Cube::new(0x0003020104070605, 0x181b1a19, 0x0003020104070605),
-            Cube::new(0x1226172321152410, 0x12161410, 0x0a170b1309150811),
-            Cube::new(0x2612231715211024, 0x16121014, 0x0a130b1709110815),
-            Cube::new(0x1521102426122317, 0x16121014, 0x091108150a130b17),
-            Cube::new(0x2115241012261723, 0x12161410, 0x091508110a170b13),
-            Cube::new(0x2617231215241021, 0x06040002, 0x170b130a15081109),
-            Cube::new(0x1726122324152110, 0x04060200, 0x170a130b15091108),
-            Cube::new(0x2415211017261223, 0x04060200, 0x15091108170a130b),
-            Cube::new(0x1524102126172312, 0x06040002, 0x15081109170b130a),
-            Cube::new(0x1723122624102115, 0x14101216, 0x0b130a1708110915),
-            Cube::new(0x2317261210241521, 0x10141612, 0x0b170a1308150911),
-            Cube::new(0x1024152123172612, 0x10141612, 0x081509110b170a13),
-            Cube::new(0x2410211517231226, 0x14101216, 0x081109150b130a17),
-            Cube::new(0x2312261710211524, 0x00020604, 0x130a170b11091508),
-            Cube::new(0x1223172621102415, 0x02000406, 0x130b170a11081509),
-            Cube::new(0x2110241512231726, 0x02000406, 0x11081509130b170a),
-            Cube::new(0x1021152423122617, 0x00020604, 0x11091508130a170b),
-            Cube::new(0x2516221114271320, 0x05070301, 0x161a1219141b1018),
-            Cube::new(0x1625112227142013, 0x07050103, 0x1619121a1418101b),
-            Cube::new(0x2714201316251122, 0x07050103, 0x1418101b1619121a),
-            Cube::new(0x1427132025162211, 0x05070301, 0x141b1018161a1219),
-            Cube::new(0x1622112527132014, 0x17131115, 0x1a1219161b101814),
-            Cube::new(0x2216251113271420, 0x13171511, 0x1a1619121b141810),
-            Cube::new(0x1327142022162511, 0x13171511, 0x1b1418101a161912),
-            Cube::new(0x2713201416221125, 0x17131115, 0x1b1018141a121916),
-            Cube::new(0x2211251613201427, 0x03010507, 0x1219161a1018141b),
-            Cube::new(0x1122162520132714, 0x01030705, 0x121a1619101b1418),
-            Cube::new(0x2013271411221625, 0x01030705, 0x101b1418121a1619),
-            Cube::new(0x1320142722112516, 0x03010507, 0x1018141b1219161a),
-            Cube::new(0x1125162220142713, 0x11151713, 0x19161a1218141b10),
-            Cube::new(0x2511221614201327, 0x15111317, 0x19121a1618101b14),
-            Cube::new(0x1420132725112216, 0x15111317, 0x18101b1419121a16),
-            Cube::new(0x2014271311251622, 0x11151713, 0x18141b1019161a12),
-        ]
-    }
-
-    /// Inverse symmetry map
-    pub fn sym_inv() -> [u8; 48] {
-        [
-            0, 1, 2, 3, 12, 5, 6, 15, 8, 9, 10, 11, 4, 13, 14, 7, 32, 35, 42,
-            41, 20, 21, 28, 29, 34, 33, 40, 43, 22, 23, 30, 31, 16, 25, 24, 17,
-            38, 37, 36, 39, 26, 19, 18, 27, 44, 47, 46, 45,
-        ]
+    /// The 18 states one face turn away, with their turns, in `Move::ALL`
+    /// order.
+    pub fn successors(&self) -> impl Iterator<Item = (Move, Cube)> {
+        self.successors_with(&Move::ALL)
+    }
+
+    /// The states one turn of `moves` away, with their turns, in order.
+    pub fn successors_with<'a>(
+        &self,
+        moves: &'a [Move],
+    ) -> impl Iterator<Item = (Move, Cube)> + 'a {
+        let cube = *self;
+        moves.iter().map(move |&m| (m, cube.compose(m.cube())))
     }
 }
+
+// -----------------------------------------------------------------------------------------------
+// Generated code: move and symmetry maps (see build.rs)
+// -----------------------------------------------------------------------------------------------
+
+include!(concat!(env!("OUT_DIR"), "/tables.rs"));
@@ -0,0 +1,605 @@
+#![allow(non_snake_case)]
+use crate::avx2;
+use crate::cube::{Cube, Edge};
+use crate::layout::packed3::EDGES;
+use crate::notation::{Face, Move, ParseMoveError};
+use crate::types::*;
+use alloc::string::ToString;
+use core::fmt;
+
+/// A permutation of up to 32 unoriented pieces in one 256-bit register.
+///
+/// Byte i holds the index of the piece at position i; bytes past the last
+/// piece hold their own index, as in `Cube`.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy)]
+pub struct Perm32(m256i);
+
+impl Perm32 {
+    pub fn identity() -> Self {
+        Self(avx2::identity_bytes())
+    }
+
+    /// Build from the first `n` bytes of a table row; the rest is identity.
+    pub fn from_slice(perm: &[u8]) -> Self {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = perm.get(i).copied().unwrap_or(i as u8);
+        }
+        Self(unsafe { core::mem::transmute::<[u8; 32], m256i>(bytes) })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        unsafe { &*(&self.0 as *const m256i as *const [u8; 32]) }
+    }
+
+    pub fn compose(&self, other: &Self) -> Self {
+        Self(avx2::permute_bytes(self.0, other.0))
+    }
+
+    pub fn invert(&self) -> Self {
+        Self(avx2::invert_bytes(self.0))
+    }
+}
+
+impl PartialEq for Perm32 {
+    fn eq(&self, other: &Self) -> bool {
+        avx2::equals(self.0, other.0)
+    }
+}
+
+impl Eq for Perm32 {}
+
+/// A 4x4x4 cube.
+///
+/// Corners are kept in the corner lane of a `Cube`, whose edge lane stays
+/// at identity. The 24 wings and 24 centers each get a `Perm32`.
+///
+/// Wings are numbered 2e + k for the 3x3 edge slot e (`Cube` order); k is 0
+/// for the wing on the left when looking at the edge's reference sticker
+/// (the U/D sticker, or F/B for E-slice edges) with the other sticker below.
+/// A wing cannot flip in place, so wing 2E + K in slot 2e + k shows its
+/// reference color on the slot's reference face exactly when K == k.
+///
+/// Centers are numbered 4f + j for face f in U R F D L B order, j reading
+/// the face's 2x2 block row by row as in `facelet`.
+///
+/// There are no fixed centers; the corners fix the frame and only face and
+/// inner-slice turns are generated, so the cube never changes orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cube4 {
+    pub corners: Cube,
+    pub wings: Perm32,
+    pub centers: Perm32,
+}
+
+impl Cube4 {
+    pub fn identity() -> Self {
+        Self {
+            corners: Cube::identity(),
+            wings: Perm32::identity(),
+            centers: Perm32::identity(),
+        }
+    }
+
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            corners: self.corners.compose(&other.corners),
+            wings: self.wings.compose(&other.wings),
+            centers: self.centers.compose(&other.centers),
+        }
+    }
+
+    pub fn invert(&self) -> Self {
+        Self {
+            corners: self.corners.invert(),
+            wings: self.wings.invert(),
+            centers: self.centers.invert(),
+        }
+    }
+
+    /// Each center is on the face of its color.
+    pub fn centers_reduced(&self) -> bool {
+        let c = self.centers.as_bytes();
+        (0..24).all(|i| c[i] / 4 == i as u8 / 4)
+    }
+
+    /// Each edge slot holds both wings of one edge.
+    pub fn wings_paired(&self) -> bool {
+        let w = self.wings.as_bytes();
+        (0..12).all(|e| {
+            w[2 * e] / 2 == w[2 * e + 1] / 2 && w[2 * e] != w[2 * e + 1]
+        })
+    }
+
+    /// The 3x3 state of a reduced cube (centers solved, wings paired), or
+    /// None if the cube is not reduced.
+    ///
+    /// The result may be unreachable on a 3x3: a single flipped edge shows up
+    /// as odd edge orientation (OLL parity), a swap as odd permutation
+    /// parity (PLL parity).
+    pub fn to_reduced(&self) -> Option<Cube> {
+        if !self.centers_reduced() || !self.wings_paired() {
+            return None;
+        }
+        let w = self.wings.as_bytes();
+        let mut cube = self.corners;
+        for (e, edge) in cube.edges_mut().iter_mut().enumerate() {
+            *edge = Edge(EDGES.pack(w[2 * e] / 2, w[2 * e] % 2));
+        }
+        Some(cube)
+    }
+
+    /// Lift a 3x3 state to a reduced 4x4 with solved centers.
+    pub fn from_reduced(cube: &Cube) -> Self {
+        let mut wings = [0u8; 24];
+        for (e, edge) in cube.edges().iter().enumerate() {
+            let (piece, ori) = (EDGES.piece(edge.0), EDGES.ori(edge.0));
+            wings[2 * e] = 2 * piece + ori;
+            wings[2 * e + 1] = 2 * piece + (1 - ori);
+        }
+        let mut corners = *cube;
+        corners
+            .edges_mut()
+            .copy_from_slice(Cube::identity().edges());
+        Self {
+            corners,
+            wings: Perm32::from_slice(&wings),
+            centers: Perm32::identity(),
+        }
+    }
+
+    /// Move table, outer face turns then inner slices:
+    /// U, U2, U', R, ... B' (as in `Cube::moves`), then u, u2, u', r, ... b'
+    pub fn moves() -> [Self; 36] {
+        let outer = Cube::moves();
+        let mut moves = [Self::identity(); 36];
+        for (i, m) in moves.iter_mut().enumerate() {
+            if i < 18 {
+                m.corners.corners_mut().copy_from_slice(outer[i].corners());
+            }
+            m.wings = Perm32::from_slice(&WING_MOVES[i]);
+            m.centers = Perm32::from_slice(&CENTER_MOVES[i]);
+        }
+        moves
+    }
+}
+
+/// A face or inner-slice turn of a 4x4 or 5x5, numbered as in
+/// `Cube4::moves`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LayerMove(u8);
+
+impl LayerMove {
+    pub const ALL: [LayerMove; 36] = {
+        let mut all = [LayerMove(0); 36];
+        let mut i = 0;
+        while i < 36 {
+            all[i] = LayerMove(i as u8);
+            i += 1;
+        }
+        all
+    };
+
+    pub fn new(face: Face, inner: bool, power: u8) -> Self {
+        debug_assert!((1..=3).contains(&power));
+        Self(18 * inner as u8 + 3 * face as u8 + power - 1)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn face(self) -> Face {
+        Face::ALL[(self.0 as usize % 18) / 3]
+    }
+
+    /// Turns the second layer rather than the face.
+    pub fn is_inner(self) -> bool {
+        self.0 >= 18
+    }
+
+    pub fn power(self) -> u8 {
+        self.0 % 3 + 1
+    }
+
+    pub fn inverse(self) -> Self {
+        Self::new(self.face(), self.is_inner(), 4 - self.power())
+    }
+
+    /// Axis, and layer counted from the U, R or F face (so U, u, d, D are
+    /// layers 0, 1, 2, 3 of axis 0).
+    pub fn axis_layer(self) -> (u8, u8) {
+        let face = self.face() as u8;
+        let depth = self.is_inner() as u8;
+        if face < 3 {
+            (face, depth)
+        } else {
+            (face - 3, 3 - depth)
+        }
+    }
+}
+
+impl fmt::Display for LayerMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let face = self.face().to_char();
+        let face = if self.is_inner() {
+            face.to_ascii_lowercase()
+        } else {
+            face
+        };
+        let suffix = ["", "2", "'"][self.power() as usize - 1];
+        write!(f, "{}{}", face, suffix)
+    }
+}
+
+/// The same face turn on the outer layer.
+impl From<Move> for LayerMove {
+    fn from(m: Move) -> Self {
+        Self::new(m.face(), false, m.power())
+    }
+}
+
+impl core::str::FromStr for LayerMove {
+    type Err = ParseMoveError;
+
+    /// As a `Move`, with a lowercase face for the inner slice: `r2`, `u'`.
+    fn from_str(s: &str) -> Result<Self, ParseMoveError> {
+        let inner = s.starts_with(|c: char| c.is_ascii_lowercase());
+        let m: Move = s
+            .to_ascii_uppercase()
+            .parse()
+            .map_err(|_| ParseMoveError(s.to_string()))?;
+        Ok(Self::new(m.face(), inner, m.power()))
+    }
+}
+
+impl core::ops::Not for Cube4 {
+    type Output = Self;
+    fn not(self) -> Self {
+        self.invert()
+    }
+}
+
+impl core::ops::Mul for Cube4 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.compose(&other)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+// Generated code: wing and center move maps, in `Cube4::moves` order
+// -----------------------------------------------------------------------------------------------
+
+pub(crate) const WING_MOVES: [[u8; 24]; 36] = [
+    [
+        6, 7, 0, 1, 2, 3, 4, 5, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        4, 5, 6, 7, 0, 1, 2, 3, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        2, 3, 4, 5, 6, 7, 0, 1, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        16, 17, 2, 3, 4, 5, 6, 7, 22, 23, 10, 11, 12, 13, 14, 15, 8, 9, 18, 19,
+        20, 21, 0, 1,
+    ],
+    [
+        8, 9, 2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 22, 23, 18, 19,
+        20, 21, 16, 17,
+    ],
+    [
+        22, 23, 2, 3, 4, 5, 6, 7, 16, 17, 10, 11, 12, 13, 14, 15, 0, 1, 18, 19,
+        20, 21, 8, 9,
+    ],
+    [
+        0, 1, 19, 18, 4, 5, 6, 7, 8, 9, 17, 16, 12, 13, 14, 15, 3, 2, 11, 10,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 10, 11, 4, 5, 6, 7, 8, 9, 2, 3, 12, 13, 14, 15, 18, 19, 16, 17,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 17, 16, 4, 5, 6, 7, 8, 9, 19, 18, 12, 13, 14, 15, 11, 10, 3, 2,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 10, 11, 12, 13, 14, 15, 8, 9, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 12, 13, 14, 15, 8, 9, 10, 11, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 14, 15, 8, 9, 10, 11, 12, 13, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 20, 21, 6, 7, 8, 9, 10, 11, 18, 19, 14, 15, 16, 17, 4, 5,
+        12, 13, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 12, 13, 6, 7, 8, 9, 10, 11, 4, 5, 14, 15, 16, 17, 20, 21,
+        18, 19, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 18, 19, 6, 7, 8, 9, 10, 11, 20, 21, 14, 15, 16, 17, 12, 13,
+        4, 5, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 23, 22, 8, 9, 10, 11, 12, 13, 21, 20, 16, 17, 18, 19,
+        7, 6, 15, 14,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 14, 15, 8, 9, 10, 11, 12, 13, 6, 7, 16, 17, 18, 19,
+        22, 23, 20, 21,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 21, 20, 8, 9, 10, 11, 12, 13, 23, 22, 16, 17, 18, 19,
+        15, 14, 7, 6,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 22, 17, 19,
+        20, 18, 21, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 21, 22, 19,
+        20, 17, 18, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 21, 19,
+        20, 22, 17, 23,
+    ],
+    [
+        0, 1, 2, 10, 4, 5, 3, 7, 8, 9, 15, 11, 12, 13, 14, 6, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 15, 4, 5, 10, 7, 8, 9, 6, 11, 12, 13, 14, 3, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 6, 4, 5, 15, 7, 8, 9, 3, 11, 12, 13, 14, 10, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        5, 1, 2, 3, 4, 12, 6, 7, 8, 0, 10, 11, 9, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        12, 1, 2, 3, 4, 9, 6, 7, 8, 5, 10, 11, 0, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        9, 1, 2, 3, 4, 0, 6, 7, 8, 12, 10, 11, 5, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 17, 18, 20,
+        23, 21, 22, 16,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 20, 17, 18, 23,
+        16, 21, 22, 19,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 23, 17, 18, 16,
+        19, 21, 22, 20,
+    ],
+    [
+        0, 1, 7, 3, 4, 5, 6, 14, 8, 9, 10, 2, 12, 13, 11, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 14, 3, 4, 5, 6, 11, 8, 9, 10, 7, 12, 13, 2, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 11, 3, 4, 5, 6, 2, 8, 9, 10, 14, 12, 13, 7, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 8, 2, 3, 1, 5, 6, 7, 13, 9, 10, 11, 12, 4, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 13, 2, 3, 8, 5, 6, 7, 4, 9, 10, 11, 12, 1, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 4, 2, 3, 13, 5, 6, 7, 1, 9, 10, 11, 12, 8, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+];
+
+pub(crate) const CENTER_MOVES: [[u8; 24]; 36] = [
+    [
+        2, 0, 3, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        3, 2, 1, 0, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        1, 3, 0, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 6, 4, 7, 5, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 7, 6, 5, 4, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 5, 7, 4, 6, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 10, 8, 11, 9, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 11, 10, 9, 8, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 9, 11, 8, 10, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 14, 12, 15, 13, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 15, 14, 13, 12, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 12, 14, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 18, 16, 19, 17,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 18, 17, 16,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 17, 19, 16, 18,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        22, 20, 23, 21,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        23, 22, 21, 20,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        21, 23, 20, 22,
+    ],
+    [
+        0, 1, 2, 3, 20, 21, 6, 7, 4, 5, 10, 11, 12, 13, 14, 15, 8, 9, 18, 19,
+        16, 17, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 16, 17, 6, 7, 20, 21, 10, 11, 12, 13, 14, 15, 4, 5, 18, 19,
+        8, 9, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 8, 9, 6, 7, 16, 17, 10, 11, 12, 13, 14, 15, 20, 21, 18, 19,
+        4, 5, 22, 23,
+    ],
+    [
+        0, 9, 2, 11, 4, 5, 6, 7, 8, 13, 10, 15, 12, 22, 14, 20, 16, 17, 18, 19,
+        3, 21, 1, 23,
+    ],
+    [
+        0, 13, 2, 15, 4, 5, 6, 7, 8, 22, 10, 20, 12, 1, 14, 3, 16, 17, 18, 19,
+        11, 21, 9, 23,
+    ],
+    [
+        0, 22, 2, 20, 4, 5, 6, 7, 8, 1, 10, 3, 12, 9, 14, 11, 16, 17, 18, 19,
+        15, 21, 13, 23,
+    ],
+    [
+        0, 1, 19, 17, 2, 5, 3, 7, 8, 9, 10, 11, 6, 4, 14, 15, 16, 12, 18, 13,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 13, 12, 19, 5, 17, 7, 8, 9, 10, 11, 3, 2, 14, 15, 16, 6, 18, 4,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 4, 6, 13, 5, 12, 7, 8, 9, 10, 11, 17, 19, 14, 15, 16, 3, 18, 2,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 10, 11, 8, 9, 18, 19, 12, 13, 14, 15, 16, 17, 22, 23,
+        20, 21, 6, 7,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 18, 19, 8, 9, 22, 23, 12, 13, 14, 15, 16, 17, 6, 7,
+        20, 21, 10, 11,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 22, 23, 8, 9, 6, 7, 12, 13, 14, 15, 16, 17, 10, 11,
+        20, 21, 18, 19,
+    ],
+    [
+        23, 1, 21, 3, 4, 5, 6, 7, 0, 9, 2, 11, 8, 13, 10, 15, 16, 17, 18, 19,
+        20, 14, 22, 12,
+    ],
+    [
+        12, 1, 14, 3, 4, 5, 6, 7, 23, 9, 21, 11, 0, 13, 2, 15, 16, 17, 18, 19,
+        20, 10, 22, 8,
+    ],
+    [
+        8, 1, 10, 3, 4, 5, 6, 7, 12, 9, 14, 11, 23, 13, 21, 15, 16, 17, 18, 19,
+        20, 2, 22, 0,
+    ],
+    [
+        5, 7, 2, 3, 4, 15, 6, 14, 8, 9, 10, 11, 12, 13, 16, 18, 1, 17, 0, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        15, 14, 2, 3, 4, 18, 6, 16, 8, 9, 10, 11, 12, 13, 1, 0, 7, 17, 5, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        18, 16, 2, 3, 4, 0, 6, 1, 8, 9, 10, 11, 12, 13, 7, 5, 14, 17, 15, 19,
+        20, 21, 22, 23,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turns(s: &str) -> Cube4 {
+        let moves = Cube4::moves();
+        s.split_whitespace()
+            .map(|m| m.parse::<LayerMove>().unwrap())
+            .fold(Cube4::identity(), |c, m| c.compose(&moves[m.index()]))
+    }
+
+    #[test]
+    fn outer_turns_move_wings_and_corners_as_on_a_3x3() {
+        let (moves, outer) = (Cube4::moves(), Cube::moves());
+        for (i, m) in outer.iter().enumerate() {
+            let reduced = Cube4::from_reduced(m);
+            assert_eq!(moves[i].wings, reduced.wings, "{}", LayerMove::ALL[i]);
+            assert_eq!(
+                moves[i].corners,
+                reduced.corners,
+                "{}",
+                LayerMove::ALL[i]
+            );
+        }
+    }
+
+    #[test]
+    fn rotations_keep_centers_together_and_wings_paired() {
+        for &s in ["U u d' D'", "R r l' L'", "F f b' B'"].iter() {
+            let cube = turns(s);
+            let c = cube.centers.as_bytes();
+            assert!((0..24).all(|i| c[i] / 4 == c[i / 4 * 4] / 4), "{}", s);
+            assert!(cube.wings_paired(), "{}", s);
+            assert!(!cube.centers_reduced(), "{}", s);
+        }
+    }
+}
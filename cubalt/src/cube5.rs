@@ -0,0 +1,322 @@
+#![allow(non_snake_case)]
+use crate::cube::Cube;
+use crate::cube4::{Perm32, CENTER_MOVES, WING_MOVES};
+use crate::layout::packed3::EDGES;
+
+/// A 5x5x5 cube.
+///
+/// Corners and midges live in a `Cube`, exactly like the corners and edges
+/// of a 3x3. Wings and x-centers move like the wings and centers of a 4x4
+/// and use the same numbering (see `Cube4`). T-centers are numbered 4f + j
+/// for face f in U R F D L B order, j = top, left, right, bottom as the face
+/// is read in `facelet`.
+///
+/// Only face and second-layer turns are generated, so the fixed centers
+/// never move and are not stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cube5 {
+    pub outer: Cube,
+    pub wings: Perm32,
+    pub xcenters: Perm32,
+    pub tcenters: Perm32,
+}
+
+impl Cube5 {
+    pub fn identity() -> Self {
+        Self {
+            outer: Cube::identity(),
+            wings: Perm32::identity(),
+            xcenters: Perm32::identity(),
+            tcenters: Perm32::identity(),
+        }
+    }
+
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            outer: self.outer.compose(&other.outer),
+            wings: self.wings.compose(&other.wings),
+            xcenters: self.xcenters.compose(&other.xcenters),
+            tcenters: self.tcenters.compose(&other.tcenters),
+        }
+    }
+
+    pub fn invert(&self) -> Self {
+        Self {
+            outer: self.outer.invert(),
+            wings: self.wings.invert(),
+            xcenters: self.xcenters.invert(),
+            tcenters: self.tcenters.invert(),
+        }
+    }
+
+    /// Each x- and t-center is on the face of its color.
+    pub fn centers_reduced(&self) -> bool {
+        let x = self.xcenters.as_bytes();
+        let t = self.tcenters.as_bytes();
+        (0..24).all(|i| x[i] / 4 == i as u8 / 4 && t[i] / 4 == i as u8 / 4)
+    }
+
+    /// Each edge slot holds the two wings matching its midge, oriented the
+    /// same way.
+    pub fn edges_paired(&self) -> bool {
+        let w = self.wings.as_bytes();
+        self.outer.edges().iter().enumerate().all(|(e, midge)| {
+            let (piece, ori) = (EDGES.piece(midge.0), EDGES.ori(midge.0));
+            w[2 * e] == 2 * piece + ori && w[2 * e + 1] == 2 * piece + 1 - ori
+        })
+    }
+
+    /// The 3x3 state of a reduced cube, or None if the cube is not
+    /// reduced. The midges fix edge orientation, so unlike the 4x4 there is
+    /// no parity: the result is always reachable.
+    pub fn to_reduced(&self) -> Option<Cube> {
+        if self.centers_reduced() && self.edges_paired() {
+            Some(self.outer)
+        } else {
+            None
+        }
+    }
+
+    /// Lift a 3x3 state to a reduced 5x5 with solved centers.
+    pub fn from_reduced(cube: &Cube) -> Self {
+        let mut wings = [0u8; 24];
+        for (e, midge) in cube.edges().iter().enumerate() {
+            let (piece, ori) = (EDGES.piece(midge.0), EDGES.ori(midge.0));
+            wings[2 * e] = 2 * piece + ori;
+            wings[2 * e + 1] = 2 * piece + 1 - ori;
+        }
+        Self {
+            outer: *cube,
+            wings: Perm32::from_slice(&wings),
+            xcenters: Perm32::identity(),
+            tcenters: Perm32::identity(),
+        }
+    }
+
+    /// Move table, in the same order as `Cube4::moves`: outer face turns
+    /// U, U2, U', R, ... B', then second-layer slices u, u2, u', r, ... b'
+    pub fn moves() -> [Self; 36] {
+        let outer = Cube::moves();
+        let mut moves = [Self::identity(); 36];
+        for (i, m) in moves.iter_mut().enumerate() {
+            if i < 18 {
+                m.outer = outer[i];
+            }
+            m.wings = Perm32::from_slice(&WING_MOVES[i]);
+            m.xcenters = Perm32::from_slice(&CENTER_MOVES[i]);
+            m.tcenters = Perm32::from_slice(&TCENTER_MOVES[i]);
+        }
+        moves
+    }
+}
+
+impl core::ops::Not for Cube5 {
+    type Output = Self;
+    fn not(self) -> Self {
+        self.invert()
+    }
+}
+
+impl core::ops::Mul for Cube5 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.compose(&other)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------
+// Generated code: t-center move maps, in `Cube5::moves` order
+// -----------------------------------------------------------------------------------------------
+
+const TCENTER_MOVES: [[u8; 24]; 36] = [
+    [
+        1, 3, 0, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        3, 2, 1, 0, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        2, 0, 3, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 5, 7, 4, 6, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 7, 6, 5, 4, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 6, 4, 7, 5, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 9, 11, 8, 10, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 11, 10, 9, 8, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 10, 8, 11, 9, 12, 13, 14, 15, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 12, 14, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 15, 14, 13, 12, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 14, 12, 15, 13, 16, 17, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 17, 19, 16, 18,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 18, 17, 16,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 18, 16, 19, 17,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        21, 23, 20, 22,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        23, 22, 21, 20,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
+        22, 20, 23, 21,
+    ],
+    [
+        0, 1, 2, 3, 20, 5, 6, 7, 4, 9, 10, 11, 12, 13, 14, 15, 8, 17, 18, 19,
+        16, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 16, 5, 6, 7, 20, 9, 10, 11, 12, 13, 14, 15, 4, 17, 18, 19,
+        8, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 8, 5, 6, 7, 16, 9, 10, 11, 12, 13, 14, 15, 20, 17, 18, 19,
+        4, 21, 22, 23,
+    ],
+    [
+        0, 1, 10, 3, 4, 5, 6, 7, 8, 9, 14, 11, 12, 13, 21, 15, 16, 17, 18, 19,
+        20, 2, 22, 23,
+    ],
+    [
+        0, 1, 14, 3, 4, 5, 6, 7, 8, 9, 21, 11, 12, 13, 2, 15, 16, 17, 18, 19,
+        20, 10, 22, 23,
+    ],
+    [
+        0, 1, 21, 3, 4, 5, 6, 7, 8, 9, 2, 11, 12, 13, 10, 15, 16, 17, 18, 19,
+        20, 14, 22, 23,
+    ],
+    [
+        0, 1, 2, 18, 4, 3, 6, 7, 8, 9, 10, 11, 5, 13, 14, 15, 16, 17, 12, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 12, 4, 18, 6, 7, 8, 9, 10, 11, 3, 13, 14, 15, 16, 17, 5, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 5, 4, 12, 6, 7, 8, 9, 10, 11, 18, 13, 14, 15, 16, 17, 3, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 11, 8, 9, 10, 19, 12, 13, 14, 15, 16, 17, 18, 23,
+        20, 21, 22, 7,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 19, 8, 9, 10, 23, 12, 13, 14, 15, 16, 17, 18, 7,
+        20, 21, 22, 11,
+    ],
+    [
+        0, 1, 2, 3, 4, 5, 6, 23, 8, 9, 10, 7, 12, 13, 14, 15, 16, 17, 18, 11,
+        20, 21, 22, 19,
+    ],
+    [
+        0, 22, 2, 3, 4, 5, 6, 7, 8, 1, 10, 11, 12, 9, 14, 15, 16, 17, 18, 19,
+        20, 21, 13, 23,
+    ],
+    [
+        0, 13, 2, 3, 4, 5, 6, 7, 8, 22, 10, 11, 12, 1, 14, 15, 16, 17, 18, 19,
+        20, 21, 9, 23,
+    ],
+    [
+        0, 9, 2, 3, 4, 5, 6, 7, 8, 13, 10, 11, 12, 22, 14, 15, 16, 17, 18, 19,
+        20, 21, 1, 23,
+    ],
+    [
+        6, 1, 2, 3, 4, 5, 15, 7, 8, 9, 10, 11, 12, 13, 14, 17, 16, 0, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        15, 1, 2, 3, 4, 5, 17, 7, 8, 9, 10, 11, 12, 13, 14, 0, 16, 6, 18, 19,
+        20, 21, 22, 23,
+    ],
+    [
+        17, 1, 2, 3, 4, 5, 0, 7, 8, 9, 10, 11, 12, 13, 14, 6, 16, 15, 18, 19,
+        20, 21, 22, 23,
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube4::{Cube4, LayerMove};
+
+    #[test]
+    fn wings_and_xcenters_move_as_on_a_4x4() {
+        let (moves, moves4) = (Cube5::moves(), Cube4::moves());
+        for (i, m) in LayerMove::ALL.iter().enumerate() {
+            assert_eq!(moves[i].wings, moves4[i].wings, "{}", m);
+            assert_eq!(moves[i].xcenters, moves4[i].centers, "{}", m);
+        }
+    }
+
+    /// A face turn cycles the four t-centers of its face; a slice turn one
+    /// t-center on each face around its axis.
+    #[test]
+    fn quarter_turns_cycle_four_tcenters() {
+        for &m in LayerMove::ALL.iter().filter(|m| m.power() == 1) {
+            let t = TCENTER_MOVES[m.index()];
+            let start = (0..24).find(|&i| t[i] != i as u8).unwrap();
+            let (mut cycle, mut i) = ([start; 4], start);
+            for c in cycle.iter_mut().skip(1) {
+                i = t[i] as usize;
+                assert_ne!(i, start, "{}", m);
+                *c = i;
+            }
+            assert_eq!(t[i] as usize, start, "{}", m);
+            let moved = (0..24).filter(|&i| t[i] != i as u8).count();
+            assert_eq!(moved, 4, "{}", m);
+
+            let face = m.face() as usize;
+            let mut faces = cycle.map(|i| i / 4);
+            faces.sort_unstable();
+            let mut expected = [face; 4];
+            if m.is_inner() {
+                let around = (0..6).filter(|f| f % 3 != face % 3);
+                for (e, f) in expected.iter_mut().zip(around) {
+                    *e = f;
+                }
+            }
+            assert_eq!(faces, expected, "{}", m);
+        }
+    }
+}
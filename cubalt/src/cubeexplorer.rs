@@ -0,0 +1,106 @@
+//! Cube Explorer's text files: lists of maneuvers and of positions.
+//!
+//! A maneuver file has a maneuver per line, optionally followed by its
+//! length in parentheses, as Cube Explorer writes them, and a comment:
+//!
+//! ```text
+//! R U R' U' R' F R2 U' R' U' R U R' F'  (14f)  // T perm
+//! ```
+//!
+//! A position is written as a maneuver generating it or as a 54-letter
+//! facelet string, one per line. Lines without a maneuver or position are
+//! skipped.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::facelet::Facelets;
+use std::io::{self, BufRead, Error, ErrorKind, Write};
+
+/// One line of a maneuver file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Maneuver {
+    pub alg: Alg,
+    pub comment: Option<String>,
+}
+
+fn invalid(line: usize, msg: impl std::fmt::Display) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("line {}: {}", line, msg))
+}
+
+/// Splits off a `//` comment, and drops a `(14f)`, `(16q*)` or similar
+/// length.
+fn split_line(line: &str) -> (&str, Option<&str>) {
+    let (body, comment) = match line.find("//") {
+        Some(i) => (&line[..i], Some(line[i + 2..].trim())),
+        None => (line, None),
+    };
+    let body = match body.find('(') {
+        Some(i) if body.trim_end().ends_with(')') => &body[..i],
+        _ => body,
+    };
+    (body.trim(), comment)
+}
+
+/// The maneuvers of a file, in order.
+pub fn read_maneuvers<R: BufRead>(r: R) -> io::Result<Vec<Maneuver>> {
+    let mut maneuvers = Vec::new();
+    for (i, line) in r.lines().enumerate() {
+        let line = line?;
+        let (body, comment) = split_line(&line);
+        if body.is_empty() {
+            continue;
+        }
+        let alg = body.parse().map_err(|e| invalid(i + 1, e))?;
+        maneuvers.push(Maneuver {
+            alg,
+            comment: comment.map(str::to_string),
+        });
+    }
+    Ok(maneuvers)
+}
+
+/// Writes `maneuvers` as Cube Explorer does, with face-turn lengths.
+pub fn write_maneuvers<W: Write>(
+    mut w: W,
+    maneuvers: &[Maneuver],
+) -> io::Result<()> {
+    for m in maneuvers {
+        write!(w, "{}  ({}f)", m.alg, m.alg.len())?;
+        if let Some(c) = &m.comment {
+            write!(w, "  // {}", c)?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// The positions of a file, in order.
+pub fn read_positions<R: BufRead>(r: R) -> io::Result<Vec<Cube>> {
+    let mut positions = Vec::new();
+    for (i, line) in r.lines().enumerate() {
+        let line = line?;
+        let (body, _) = split_line(&line);
+        if body.is_empty() {
+            continue;
+        }
+        let cube = if body.len() == 54 && !body.contains(' ') {
+            body.parse::<Facelets>()
+                .and_then(|f| f.to_cube())
+                .map_err(|e| invalid(i + 1, e))?
+        } else {
+            body.parse::<Alg>().map_err(|e| invalid(i + 1, e))?.cube()
+        };
+        positions.push(cube);
+    }
+    Ok(positions)
+}
+
+/// Writes `positions` as facelet strings.
+pub fn write_positions<W: Write>(
+    mut w: W,
+    positions: &[Cube],
+) -> io::Result<()> {
+    for c in positions {
+        writeln!(w, "{}", Facelets::from_cube(c))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! Open-addressing hash map and set keyed by `Cube`.
+//!
+//! Keys are stored as raw registers and compared with a single SIMD
+//! compare; the all-zero register, which is never a valid cube, marks empty
+//! slots. Meant for transposition tables and BFS visited sets, where
+//! `std::collections::HashMap` spends most of its time hashing.
+use crate::avx2;
+use crate::cube::Cube;
+use crate::types::*;
+use std::arch::x86_64::*;
+use std::fmt;
+
+/// Returned when an insert would exceed the map's memory limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full;
+
+impl fmt::Display for Full {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cube map memory limit reached")
+    }
+}
+
+impl std::error::Error for Full {}
+
+const EMPTY: Cube =
+    Cube(unsafe { std::mem::transmute::<[u64; 4], m256i>([0; 4]) });
+
+/// A map from cubes to small values.
+pub struct CubeMap<V> {
+    keys: Vec<Cube>,
+    values: Vec<V>,
+    len: usize,
+    max_bytes: usize,
+}
+
+impl<V: Copy + Default> CubeMap<V> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Room for at least `n` entries before the first resize.
+    pub fn with_capacity(n: usize) -> Self {
+        let mut map = Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+            len: 0,
+            max_bytes: usize::MAX,
+        };
+        map.allocate((n * 4 / 3 + 1).next_power_of_two().max(16));
+        map
+    }
+
+    /// Grow as needed, but never past `bytes` of slot storage; inserts that
+    /// would need more fail with `Full`.
+    pub fn with_memory_limit(bytes: usize) -> Self {
+        let mut map = Self::with_capacity(0);
+        map.max_bytes = bytes;
+        map
+    }
+
+    pub fn slot_bytes() -> usize {
+        std::mem::size_of::<Cube>() + std::mem::size_of::<V>()
+    }
+
+    /// Bytes of slot storage currently allocated.
+    pub fn memory_bytes(&self) -> usize {
+        self.keys.len() * Self::slot_bytes()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.keys.len() / 4 * 3
+    }
+
+    pub fn clear(&mut self) {
+        for k in self.keys.iter_mut() {
+            *k = EMPTY;
+        }
+        self.len = 0;
+    }
+
+    fn allocate(&mut self, slots: usize) {
+        self.keys = vec![EMPTY; slots];
+        self.values = vec![V::default(); slots];
+    }
+
+    #[inline(always)]
+    fn home(&self, cube: &Cube) -> usize {
+        cube.hash_fast() as usize & (self.keys.len() - 1)
+    }
+
+    /// Slot holding `cube`, or the empty slot where it would go.
+    #[inline(always)]
+    fn probe(&self, cube: &Cube) -> usize {
+        let mask = self.keys.len() - 1;
+        let mut i = self.home(cube);
+        loop {
+            let k = &self.keys[i];
+            if *k == *cube || avx2::is_zero(k.0) {
+                return i;
+            }
+            i = (i + 1) & mask;
+        }
+    }
+
+    /// Hint that `cube` will be looked up soon.
+    #[inline(always)]
+    pub fn prefetch(&self, cube: &Cube) {
+        let i = self.home(cube);
+        unsafe {
+            _mm_prefetch(
+                &self.keys[i] as *const Cube as *const i8,
+                _MM_HINT_T0,
+            );
+        }
+    }
+
+    pub fn get(&self, cube: &Cube) -> Option<V> {
+        let i = self.probe(cube);
+        if avx2::is_zero(self.keys[i].0) {
+            None
+        } else {
+            Some(self.values[i])
+        }
+    }
+
+    pub fn contains(&self, cube: &Cube) -> bool {
+        self.get(cube).is_some()
+    }
+
+    /// Insert or overwrite, returning the previous value.
+    pub fn insert(&mut self, cube: Cube, value: V) -> Result<Option<V>, Full> {
+        let mut i = self.probe(&cube);
+        if !avx2::is_zero(self.keys[i].0) {
+            return Ok(Some(std::mem::replace(&mut self.values[i], value)));
+        }
+        if self.len >= self.capacity() {
+            self.grow()?;
+            i = self.probe(&cube);
+        }
+        self.keys[i] = cube;
+        self.values[i] = value;
+        self.len += 1;
+        Ok(None)
+    }
+
+    fn grow(&mut self) -> Result<(), Full> {
+        let slots = self.keys.len() * 2;
+        if slots * Self::slot_bytes() > self.max_bytes {
+            return Err(Full);
+        }
+        let keys = std::mem::take(&mut self.keys);
+        let values = std::mem::take(&mut self.values);
+        self.allocate(slots);
+        for (k, v) in keys.into_iter().zip(values) {
+            if !avx2::is_zero(k.0) {
+                let i = self.probe(&k);
+                self.keys[i] = k;
+                self.values[i] = v;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Cube, &V)> {
+        self.keys
+            .iter()
+            .zip(self.values.iter())
+            .filter(|(k, _)| !avx2::is_zero(k.0))
+    }
+}
+
+impl<V: Copy + Default> Default for CubeMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A set of cubes.
+#[derive(Default)]
+pub struct CubeSet(CubeMap<()>);
+
+impl CubeSet {
+    pub fn new() -> Self {
+        Self(CubeMap::new())
+    }
+
+    pub fn with_capacity(n: usize) -> Self {
+        Self(CubeMap::with_capacity(n))
+    }
+
+    pub fn with_memory_limit(bytes: usize) -> Self {
+        Self(CubeMap::with_memory_limit(bytes))
+    }
+
+    /// Returns whether `cube` was newly added.
+    pub fn insert(&mut self, cube: Cube) -> Result<bool, Full> {
+        Ok(self.0.insert(cube, ())?.is_none())
+    }
+
+    pub fn contains(&self, cube: &Cube) -> bool {
+        self.0.contains(cube)
+    }
+
+    pub fn prefetch(&self, cube: &Cube) {
+        self.0.prefetch(cube)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.0.memory_bytes()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cube> {
+        self.0.iter().map(|(k, _)| k)
+    }
+}
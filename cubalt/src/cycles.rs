@@ -0,0 +1,258 @@
+//! Cubes from cycle notation, evaluated at compile time.
+//!
+//! A spec lists the piece cycles of a state, edges and corners separately:
+//!
+//! ```text
+//! edges: (UR UF UL UB), corners: (URF UFL ULB UBR)
+//! ```
+//!
+//! Each name is a slot, with its letters in any order. The order carries the
+//! orientation: in `(a b ...)` the piece in `a` moves to `b`, and the sticker
+//! on the face of `a`'s first letter lands on the face of `b`'s first letter.
+//! So the R move is `corners: (FRD URF BRU DRB), edges: (FR UR BR DR)`.
+//! Names with the letters out of clockwise order describe reflections.
+//!
+//! A `+` after a cycle twists its piece clockwise as it returns to the first
+//! slot (flips it, for edges), and `-` twists it counter-clockwise; `(URF)+`
+//! is a twist in place. Slots left out keep their pieces.
+//!
+//! This module stands alone so that the build script can share it.
+
+/// Facelets of each edge slot, reference facelet first.
+pub(crate) const EDGE_NAMES: [[u8; 2]; 12] = [
+    *b"UR", *b"UF", *b"UL", *b"UB", *b"DR", *b"DF", *b"DL", *b"DB", *b"FR",
+    *b"FL", *b"BL", *b"BR",
+];
+
+/// Facelets of each corner slot, reference (U/D) facelet first, then
+/// clockwise.
+pub(crate) const CORNER_NAMES: [[u8; 3]; 8] = [
+    *b"URF", *b"UFL", *b"ULB", *b"UBR", *b"DFR", *b"DLF", *b"DBL", *b"DRB",
+];
+
+/// The packed words (as taken by `Cube::new`) of the state described by
+/// `spec`. Panics (at compile time, in a const) on malformed specs and on
+/// slots that receive two pieces.
+pub const fn parse(spec: &str) -> [u64; 3] {
+    let s = spec.as_bytes();
+    let mut edges = [0u8; 12];
+    let mut corners = [0u8; 8];
+    let mut i = 0;
+    while i < 12 {
+        edges[i] = i as u8;
+        i += 1;
+    }
+    i = 0;
+    while i < 8 {
+        corners[i] = i as u8;
+        i += 1;
+    }
+    // Slots already given a piece, as a bitmask.
+    let mut filled_edges = 0u16;
+    let mut filled_corners = 0u8;
+    // Whether a section has started, and whether it is of corners.
+    let mut in_section = false;
+    let mut corner_section = false;
+
+    i = 0;
+    while i < s.len() {
+        match s[i] {
+            b' ' | b'\n' | b'\t' | b',' => i += 1,
+            b'e' => {
+                i = expect(s, i, b"edges");
+                in_section = true;
+                corner_section = false;
+            }
+            b'c' => {
+                i = expect(s, i, b"corners");
+                in_section = true;
+                corner_section = true;
+            }
+            b'(' if !in_section => panic!("cycle outside a section"),
+            b'(' => {
+                // Slots and their orientation offsets, in cycle order.
+                let mut slots = [0u8; 12];
+                let mut offsets = [0u8; 12];
+                let mut n = 0;
+                i += 1;
+                loop {
+                    while i < s.len() && s[i] == b' ' {
+                        i += 1;
+                    }
+                    if i >= s.len() {
+                        panic!("unclosed cycle");
+                    }
+                    if s[i] == b')' {
+                        i += 1;
+                        break;
+                    }
+                    let start = i;
+                    while i < s.len() && is_face(s[i]) {
+                        i += 1;
+                    }
+                    if n == slots.len() {
+                        panic!("cycle too long");
+                    }
+                    let (slot, offset) = if corner_section {
+                        corner_slot(s, start, i)
+                    } else {
+                        edge_slot(s, start, i)
+                    };
+                    slots[n] = slot;
+                    offsets[n] = offset;
+                    n += 1;
+                }
+                if n == 0 {
+                    panic!("empty cycle");
+                }
+                while i < s.len() && s[i] == b' ' {
+                    i += 1;
+                }
+                let modulus = if corner_section { 3 } else { 2 };
+                let mut twist = 0;
+                if i < s.len() && s[i] == b'+' {
+                    twist = 1;
+                    i += 1;
+                } else if i < s.len() && s[i] == b'-' {
+                    twist = modulus - 1;
+                    i += 1;
+                }
+
+                let mut k = 0;
+                while k < n {
+                    let from = k;
+                    let to = (k + 1) % n;
+                    let mut ori =
+                        (offsets[to] + modulus - offsets[from]) % modulus;
+                    if to == 0 {
+                        ori = (ori + twist) % modulus;
+                    }
+                    let piece = slots[from];
+                    let slot = slots[to] as usize;
+                    if corner_section {
+                        if filled_corners & 1 << slot != 0 {
+                            panic!("corner slot used twice");
+                        }
+                        filled_corners |= 1 << slot;
+                        corners[slot] = ori << 4 | piece;
+                    } else {
+                        if filled_edges & 1 << slot != 0 {
+                            panic!("edge slot used twice");
+                        }
+                        filled_edges |= 1 << slot;
+                        edges[slot] = ori << 4 | piece;
+                    }
+                    k += 1;
+                }
+            }
+            _ => panic!("unexpected character in cycle spec"),
+        }
+    }
+
+    let mut corner_word = 0u64;
+    let mut edges_low = 0u64;
+    let mut edges_high = 0u64;
+    i = 0;
+    while i < 8 {
+        corner_word |= (corners[i] as u64) << (8 * i);
+        edges_low |= (edges[i] as u64) << (8 * i);
+        i += 1;
+    }
+    while i < 12 {
+        edges_high |= (edges[i] as u64) << (8 * (i - 8));
+        i += 1;
+    }
+    [corner_word, edges_high, edges_low]
+}
+
+/// `Cube` constant from cycle notation; see the `cycles` module. For
+/// example `const R: Cube = cycles!(corners: (FRD URF BRU DRB), edges: (FR
+/// UR BR DR));`.
+#[macro_export]
+macro_rules! cycles {
+    ($($spec:tt)*) => {
+        $crate::cube::Cube::from_cycles(stringify!($($spec)*))
+    };
+}
+
+const fn is_face(c: u8) -> bool {
+    matches!(c, b'U' | b'R' | b'F' | b'D' | b'L' | b'B')
+}
+
+/// Index after the keyword `word` at `i`, and its colon.
+const fn expect(s: &[u8], mut i: usize, word: &[u8]) -> usize {
+    let mut j = 0;
+    while j < word.len() {
+        if i >= s.len() || s[i] != word[j] {
+            panic!("expected `edges:` or `corners:`");
+        }
+        i += 1;
+        j += 1;
+    }
+    while i < s.len() && s[i] == b' ' {
+        i += 1;
+    }
+    if i >= s.len() || s[i] != b':' {
+        panic!("expected `:` after section name");
+    }
+    i + 1
+}
+
+/// Whether `s[start..end]` holds the same letters as `name`, and if so
+/// the index in it of `name`'s first letter.
+pub(crate) const fn find_letters(
+    s: &[u8],
+    start: usize,
+    end: usize,
+    name: &[u8],
+) -> Option<usize> {
+    if end - start != name.len() {
+        return None;
+    }
+    let mut j = 0;
+    let mut reference = None;
+    while j < name.len() {
+        let mut found = false;
+        let mut k = start;
+        while k < end {
+            if s[k] == name[j] {
+                found = true;
+                if j == 0 {
+                    reference = Some(k - start);
+                }
+            }
+            k += 1;
+        }
+        if !found {
+            return None;
+        }
+        j += 1;
+    }
+    reference
+}
+
+/// Edge slot of a name, and 1 if the name starts with the non-reference
+/// facelet.
+const fn edge_slot(s: &[u8], start: usize, end: usize) -> (u8, u8) {
+    let mut slot = 0;
+    while slot < 12 {
+        if let Some(r) = find_letters(s, start, end, &EDGE_NAMES[slot]) {
+            return (slot as u8, r as u8);
+        }
+        slot += 1;
+    }
+    panic!("not an edge name");
+}
+
+/// Corner slot of a name, and the clockwise turn taking the slot's
+/// reference facelet to the name's first letter.
+const fn corner_slot(s: &[u8], start: usize, end: usize) -> (u8, u8) {
+    let mut slot = 0;
+    while slot < 8 {
+        if let Some(r) = find_letters(s, start, end, &CORNER_NAMES[slot]) {
+            return (slot as u8, ((3 - r) % 3) as u8);
+        }
+        slot += 1;
+    }
+    panic!("not a corner name");
+}
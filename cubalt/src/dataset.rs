@@ -0,0 +1,177 @@
+//! Labelled states for machine learning: state, distance and scramble.
+//!
+//! Record `i` of seed `s` is derived from `Rng::new(s)` the way scramble
+//! `i` is (see `scramble`), so a dataset can be generated in pieces, on
+//! any number of threads, and come out the same. Labels are either the
+//! two-phase solver's solution length, an upper bound on the distance, or
+//! the exact distance from the optimal solver, which only reaches about
+//! 12 moves.
+//!
+//! Both formats have one record per line, with these fields in this order:
+//!
+//! - `index`: the record number
+//! - `state`: the 54-letter facelet string
+//! - `distance`: the solution length, in face turns
+//! - `exact`: whether `distance` is the distance from solved
+//! - `scramble`: a sequence of `distance` moves reaching the state
+use crate::alg::Alg;
+use crate::context::Parallelism;
+use crate::cube::Cube;
+use crate::facelet::Facelets;
+use crate::optimal::Optimal;
+use crate::random::{random_cube, Rng};
+use crate::solver::Solver;
+use std::io::{self, Write};
+use std::ops::{Range, RangeInclusive};
+
+/// First line of the CSV format.
+pub const CSV_HEADER: &str = "index,state,distance,exact,scramble";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub index: u64,
+    pub state: Cube,
+    pub distance: u32,
+    pub exact: bool,
+    pub scramble: Alg,
+}
+
+impl Record {
+    /// A CSV line, without the newline.
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            self.index,
+            Facelets::from_cube(&self.state),
+            self.distance,
+            self.exact,
+            self.scramble
+        )
+    }
+
+    /// A JSON object, on one line.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"index\":{},\"state\":\"{}\",\"distance\":{},\"exact\":{},\
+             \"scramble\":\"{}\"}}",
+            self.index,
+            Facelets::from_cube(&self.state),
+            self.distance,
+            self.exact,
+            self.scramble
+        )
+    }
+}
+
+/// How states are drawn and labelled.
+enum Labels<'a> {
+    /// Uniformly random states, labelled with a two-phase solution.
+    TwoPhase(&'a Solver),
+    /// Uniformly random states with their distance in a range, labelled
+    /// with their distance.
+    Optimal(&'a Optimal, RangeInclusive<u32>),
+}
+
+/// The records of one seed.
+pub struct Dataset<'a> {
+    labels: Labels<'a>,
+    seed: u64,
+}
+
+impl<'a> Dataset<'a> {
+    /// Random states with two-phase solution lengths, of at most 21 moves.
+    pub fn two_phase(solver: &'a Solver, seed: u64) -> Self {
+        Self {
+            labels: Labels::TwoPhase(solver),
+            seed,
+        }
+    }
+
+    /// Random states at a distance in `range`, which must end by 15, with
+    /// exact distances. Past about 12 moves this is very slow.
+    pub fn optimal(
+        optimal: &'a Optimal,
+        range: RangeInclusive<u32>,
+        seed: u64,
+    ) -> Self {
+        Self {
+            labels: Labels::Optimal(optimal, range),
+            seed,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Record `i`, independently of any other.
+    pub fn record(&self, i: u64) -> Record {
+        let mut master = Rng::new(self.seed);
+        master.jump(i);
+        let mut rng = Rng::new(master.next_u64());
+        let (state, solution, exact) = match &self.labels {
+            Labels::TwoPhase(solver) => {
+                let state = random_cube(&mut rng);
+                let solution = solver
+                    .solve(&state, 21)
+                    .expect("every state is within 20 moves");
+                (state, solution, false)
+            }
+            Labels::Optimal(optimal, range) => {
+                let state = optimal.random_in_range(&mut rng, range.clone());
+                let solution = optimal
+                    .solve(&state, *range.end())
+                    .expect("a state in range");
+                (state, solution, true)
+            }
+        };
+        Record {
+            index: i,
+            state,
+            distance: solution.len() as u32,
+            exact,
+            scramble: solution.inverse(),
+        }
+    }
+
+    /// Records `range`, in order, computed on `threads` threads.
+    pub fn records(&self, range: Range<u64>, threads: usize) -> Vec<Record> {
+        self.records_with(range, &Parallelism::Threads(threads))
+    }
+
+    /// Records `range`, in order, computed as `parallelism` allows.
+    pub fn records_with(
+        &self,
+        range: Range<u64>,
+        parallelism: &Parallelism,
+    ) -> Vec<Record> {
+        parallelism.map(range, |i| self.record(i))
+    }
+
+    /// `CSV_HEADER`, then records `range`.
+    pub fn write_csv<W: Write>(
+        &self,
+        mut w: W,
+        range: Range<u64>,
+        threads: usize,
+    ) -> io::Result<()> {
+        writeln!(w, "{}", CSV_HEADER)?;
+        for r in self.records(range, threads) {
+            writeln!(w, "{}", r.csv_row())?;
+        }
+        Ok(())
+    }
+
+    /// Records `range` as JSON lines.
+    pub fn write_jsonl<W: Write>(
+        &self,
+        mut w: W,
+        range: Range<u64>,
+        threads: usize,
+    ) -> io::Result<()> {
+        for r in self.records(range, threads) {
+            writeln!(w, "{}", r.to_json())?;
+        }
+        Ok(())
+    }
+}
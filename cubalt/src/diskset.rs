@@ -0,0 +1,238 @@
+//! External-memory set of cube keys.
+//!
+//! Inserts collect in an in-memory buffer; when it fills, it is sorted and
+//! written to a run file. Too many runs are merged into one, LSM-style.
+//! Queries come in batches, are sorted, and stream through each run once,
+//! so a set far larger than RAM costs only sequential reads.
+//!
+//! The set stores whatever cubes it is given; callers doing symmetry
+//! reduction should insert canonical representatives.
+use crate::cube::Cube;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Runs allowed before they are merged into one.
+const MAX_RUNS: usize = 8;
+
+struct Run {
+    path: PathBuf,
+    len: u64,
+}
+
+/// Sequential reader over a run file.
+struct RunReader {
+    reader: BufReader<File>,
+    left: u64,
+}
+
+impl RunReader {
+    fn open(run: &Run) -> io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::with_capacity(1 << 16, File::open(&run.path)?),
+            left: run.len,
+        })
+    }
+
+    fn next(&mut self) -> io::Result<Option<u128>> {
+        if self.left == 0 {
+            return Ok(None);
+        }
+        let mut buf = [0u8; 16];
+        self.reader.read_exact(&mut buf)?;
+        self.left -= 1;
+        Ok(Some(u128::from_le_bytes(buf)))
+    }
+}
+
+pub struct DiskCubeSet {
+    dir: PathBuf,
+    buffer: Vec<u128>,
+    buffer_limit: usize,
+    runs: Vec<Run>,
+    next_id: usize,
+}
+
+impl DiskCubeSet {
+    /// A new, empty set keeping its runs in `dir` (created if needed) and
+    /// holding at most `buffer_limit` keys in memory.
+    pub fn create<P: AsRef<Path>>(
+        dir: P,
+        buffer_limit: usize,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            buffer: Vec::new(),
+            buffer_limit: buffer_limit.max(1),
+            runs: Vec::new(),
+            next_id: 0,
+        })
+    }
+
+    /// Number of keys stored. Until `compact` is called, a key inserted
+    /// more than once may be counted more than once.
+    pub fn len(&self) -> u64 {
+        self.buffer.len() as u64 + self.runs.iter().map(|r| r.len).sum::<u64>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn insert_batch(&mut self, cubes: &[Cube]) -> io::Result<()> {
+        for c in cubes {
+            self.buffer.push(c.to_key());
+            if self.buffer.len() >= self.buffer_limit {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Membership of each cube in `cubes`, in order.
+    pub fn contains_batch(&mut self, cubes: &[Cube]) -> io::Result<Vec<bool>> {
+        let mut queries: Vec<(u128, usize)> =
+            cubes.iter().map(|c| c.to_key()).zip(0..).collect();
+        queries.sort_unstable();
+
+        self.buffer.sort_unstable();
+        self.buffer.dedup();
+        let mut found = vec![false; cubes.len()];
+        for &(k, i) in queries.iter() {
+            found[i] = self.buffer.binary_search(&k).is_ok();
+        }
+
+        for run in self.runs.iter() {
+            let mut reader = RunReader::open(run)?;
+            let mut cur = reader.next()?;
+            for &(k, i) in queries.iter() {
+                while let Some(r) = cur {
+                    if r >= k {
+                        break;
+                    }
+                    cur = reader.next()?;
+                }
+                match cur {
+                    Some(r) if r == k => found[i] = true,
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Insert `cubes`, returning those that were not already present (each
+    /// once). This is the step that produces the next BFS frontier.
+    pub fn insert_new_batch(
+        &mut self,
+        cubes: &[Cube],
+    ) -> io::Result<Vec<Cube>> {
+        let mut keys: Vec<u128> = cubes.iter().map(|c| c.to_key()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let unique: Vec<Cube> = keys.into_iter().map(Cube::from_key).collect();
+        let found = self.contains_batch(&unique)?;
+        let new: Vec<Cube> = unique
+            .into_iter()
+            .zip(found)
+            .filter(|&(_, f)| !f)
+            .map(|(c, _)| c)
+            .collect();
+        self.insert_batch(&new)?;
+        Ok(new)
+    }
+
+    /// Write the in-memory buffer out as a run.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.buffer.sort_unstable();
+        self.buffer.dedup();
+        let keys = std::mem::take(&mut self.buffer);
+        let run = self.write_run(keys.into_iter().map(Ok))?;
+        self.runs.push(run);
+        if self.runs.len() > MAX_RUNS {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Merge the buffer and all runs into a single deduplicated run.
+    pub fn compact(&mut self) -> io::Result<()> {
+        self.buffer.sort_unstable();
+        self.buffer.dedup();
+        let buffer = std::mem::take(&mut self.buffer);
+        let runs = std::mem::take(&mut self.runs);
+        let mut readers = runs
+            .iter()
+            .map(RunReader::open)
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heads = readers
+            .iter_mut()
+            .map(|r| r.next())
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut buffer = buffer.into_iter().peekable();
+
+        let mut last = None;
+        let merged = std::iter::from_fn(|| loop {
+            let (i, k) = match heads
+                .iter()
+                .enumerate()
+                .filter_map(|(i, h)| h.map(|k| (i, k)))
+                .min_by_key(|&(_, k)| k)
+            {
+                Some((i, k)) if buffer.peek().is_none_or(|&b| k <= b) => {
+                    (Some(i), k)
+                }
+                _ => match buffer.next() {
+                    Some(b) => (None, b),
+                    None => return None,
+                },
+            };
+            if let Some(i) = i {
+                match readers[i].next() {
+                    Ok(h) => heads[i] = h,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            if last != Some(k) {
+                last = Some(k);
+                return Some(Ok(k));
+            }
+        });
+        let run = self.write_run(merged)?;
+        for r in runs.iter() {
+            fs::remove_file(&r.path)?;
+        }
+        self.runs.push(run);
+        Ok(())
+    }
+
+    fn write_run<I: Iterator<Item = io::Result<u128>>>(
+        &mut self,
+        keys: I,
+    ) -> io::Result<Run> {
+        let path = self.dir.join(format!("run-{:06}.keys", self.next_id));
+        self.next_id += 1;
+        let mut writer =
+            BufWriter::with_capacity(1 << 16, File::create(&path)?);
+        let mut len = 0;
+        for k in keys {
+            writer.write_all(&k?.to_le_bytes())?;
+            len += 1;
+        }
+        writer.flush()?;
+        Ok(Run { path, len })
+    }
+}
+
+impl Drop for DiskCubeSet {
+    fn drop(&mut self) {
+        for r in self.runs.iter() {
+            let _ = fs::remove_file(&r.path);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Sets of solutions that differ in structure, not only in how they are
+//! written.
+//!
+//! Two algs are the same here when one is the other rotated, or inverted,
+//! or with turns of opposite faces swapped, merged or split (`U D` and
+//! `D U`, `R2` and `R R`). Each alg has a key, the least of its variants
+//! in a normal form, and algs with the same key are the same.
+use crate::alg::Alg;
+use crate::notation::Move;
+use crate::rotation::CubeRotation;
+use alloc::vec::Vec;
+
+/// `alg` merged, with each pair of turns on one axis lower face first.
+fn normal_form(alg: &Alg) -> Vec<Move> {
+    let mut moves = alg.simplify_commuting().0;
+    for i in 1..moves.len() {
+        let (a, b) = (moves[i - 1], moves[i]);
+        if a.face().axis() == b.face().axis() && b.face() < a.face() {
+            moves.swap(i - 1, i);
+        }
+    }
+    moves
+}
+
+/// The same for algs related by rotation, inversion, or turns of opposite
+/// faces commuting or merging, and different otherwise.
+pub fn key(alg: &Alg) -> Vec<Move> {
+    let inverse = alg.inverse();
+    [alg, &inverse]
+        .iter()
+        .flat_map(|a| {
+            CubeRotation::all().map(move |r| normal_form(&r.map_alg(a)))
+        })
+        .min()
+        .expect("24 rotations")
+}
+
+/// Algs kept as they come while they are unlike those kept before, up to
+/// a limit.
+#[derive(Debug, Clone)]
+pub struct Distinct {
+    algs: Vec<Alg>,
+    keys: Vec<Vec<Move>>,
+    limit: usize,
+}
+
+impl Distinct {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            algs: Vec::new(),
+            keys: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Keep `alg` if there is room and it is unlike every alg kept;
+    /// returns whether it was kept.
+    pub fn insert(&mut self, alg: Alg) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        let key = key(&alg);
+        if self.keys.contains(&key) {
+            return false;
+        }
+        self.keys.push(key);
+        self.algs.push(alg);
+        true
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.algs.len() >= self.limit
+    }
+
+    pub fn algs(&self) -> &[Alg] {
+        &self.algs
+    }
+
+    pub fn into_algs(self) -> Vec<Alg> {
+        self.algs
+    }
+}
+
+/// The first `n` of `algs` each unlike those before it.
+pub fn diverse(algs: impl IntoIterator<Item = Alg>, n: usize) -> Vec<Alg> {
+    let mut distinct = Distinct::new(n);
+    for alg in algs {
+        distinct.insert(alg);
+        if distinct.is_full() {
+            break;
+        }
+    }
+    distinct.into_algs()
+}
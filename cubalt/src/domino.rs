@@ -0,0 +1,173 @@
+//! The Domino (3x3x2): the U and D layers of the 3x3, without the middle.
+//!
+//! Its turns are the phase 2 moves, U and D turns and half turns of the
+//! sides, so its states are those of G1 with the E-slice edges left out.
+//! Without them the two parities are free, and there are 8! * 8! =
+//! 1,625,702,400 states. A state is kept as a `Cube` with the slice edges
+//! always home, as the pocket cube keeps its edges solved.
+//!
+//! Solutions are optimal, pruned by the corner and edge permutations. Their
+//! tables come from the two-phase solver's phase 2 tables, each folded
+//! over the slice permutation that the Domino lacks.
+use crate::alg::Alg;
+use crate::coord::{Coordinate, CornerPerm, SlicePerm, UDEdgePerm};
+use crate::cube::Cube;
+use crate::notation::Move;
+use crate::pattern::Solved;
+use crate::prune::PruneTable;
+use crate::puzzle::Puzzle;
+use crate::search::{Heuristic, Ida};
+use crate::solver::{in_g1, Solver, PHASE2_MOVES};
+use std::sync::Arc;
+
+/// The E-slice edges each side half turn swaps, swapped back.
+const SLICE_SWAPS: [Cube; 6] = [
+    crate::cycles!(),
+    crate::cycles!(edges: (FR BR)),
+    crate::cycles!(edges: (FR FL)),
+    crate::cycles!(),
+    crate::cycles!(edges: (FL BL)),
+    crate::cycles!(edges: (BL BR)),
+];
+
+/// The 3x3 move states with the slice edges put back, in `Move` order.
+/// Only those of the Domino's moves are Domino states.
+const fn move_states() -> [Domino; 18] {
+    let cubes = Cube::moves();
+    let mut states = [Domino(cubes[0]); 18];
+    let mut i = 0;
+    while i < 18 {
+        states[i] = Domino(cubes[i].const_compose(&SLICE_SWAPS[i / 3]));
+        i += 1;
+    }
+    states
+}
+
+static MOVE_STATES: [Domino; 18] = move_states();
+
+/// Longest solution `DominoSolver::solve` looks for, beyond what any state
+/// needs.
+const MAX_LENGTH: u32 = 24;
+
+/// A Domino state: a `Cube` in G1 whose slice edges are always home.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Domino(Cube);
+
+impl Domino {
+    /// The U and D layers of a 3x3 state. `None` if it is not in G1.
+    pub fn from_cube(cube: &Cube) -> Option<Self> {
+        if !in_g1(cube) {
+            return None;
+        }
+        let mut c = *cube;
+        c.edges_mut()[8..].copy_from_slice(&Cube::identity().edges()[8..]);
+        Some(Self(c))
+    }
+
+    /// As a 3x3 state with the slice edges solved, which with the wrong
+    /// parities is not a state of the 3x3.
+    pub fn cube(&self) -> &Cube {
+        &self.0
+    }
+}
+
+impl Puzzle for Domino {
+    type Move = Move;
+    type Coord = DominoCoord;
+
+    fn identity() -> Self {
+        Self(Cube::identity())
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Self(self.0.compose(&other.0))
+    }
+
+    fn invert(&self) -> Self {
+        Self(self.0.invert())
+    }
+
+    fn moves() -> &'static [Move] {
+        &PHASE2_MOVES
+    }
+
+    /// Meaningless for quarter turns of the sides.
+    fn move_state(m: Move) -> Self {
+        debug_assert!(PHASE2_MOVES.contains(&m), "{} is not a Domino move", m);
+        MOVE_STATES[m.index()]
+    }
+
+    fn move_axis_layer(m: Move) -> (u8, u8) {
+        Cube::move_axis_layer(m)
+    }
+}
+
+/// All of a Domino state: the corner permutation, major, and the U and D
+/// edge permutation, minor.
+pub struct DominoCoord;
+
+impl Coordinate<Domino> for DominoCoord {
+    const SIZE: usize = CornerPerm::SIZE * UDEdgePerm::SIZE;
+
+    fn rank(p: &Domino) -> usize {
+        CornerPerm::rank(&p.0) * UDEdgePerm::SIZE + UDEdgePerm::rank(&p.0)
+    }
+
+    fn unrank(c: usize) -> Domino {
+        let corners = CornerPerm::unrank(c / UDEdgePerm::SIZE);
+        Domino(corners.compose(&UDEdgePerm::unrank(c % UDEdgePerm::SIZE)))
+    }
+}
+
+/// The distance of each value of `table`'s coordinate, major, times the
+/// slice permutation, minor, to solved, with the slice in any order.
+fn fold_slice(table: &PruneTable) -> Arc<[u8]> {
+    (0..table.len() / SlicePerm::SIZE)
+        .map(|c| {
+            (0..SlicePerm::SIZE)
+                .map(|s| table.distance(c * SlicePerm::SIZE + s))
+                .min()
+                .expect("the slice has permutations")
+        })
+        .collect()
+}
+
+/// Optimal Domino solutions. The tables are 80 KB, folded from a two-phase
+/// solver's in a moment; clones share them.
+#[derive(Clone)]
+pub struct DominoSolver {
+    /// Distance of each `CornerPerm`, and of each `UDEdgePerm`.
+    corners: Arc<[u8]>,
+    edges: Arc<[u8]>,
+}
+
+impl Default for DominoSolver {
+    fn default() -> Self {
+        Self::new(Solver::shared())
+    }
+}
+
+impl DominoSolver {
+    pub fn new(solver: &Solver) -> Self {
+        let (corners, edges) = solver.phase2_tables();
+        Self {
+            corners: fold_slice(corners),
+            edges: fold_slice(edges),
+        }
+    }
+
+    /// A shortest solution of `domino`, in Domino moves.
+    pub fn solve(&self, domino: &Domino) -> Alg {
+        let mut ida = Ida::new(self, &Solved, MAX_LENGTH);
+        Alg(ida.solve(domino).expect("every state is within 24 moves"))
+    }
+}
+
+/// Exact for the corners alone and for the edges alone.
+impl Heuristic<Domino> for DominoSolver {
+    fn estimate(&self, d: &Domino) -> u32 {
+        let corners = self.corners[CornerPerm::rank(&d.0)];
+        let edges = self.edges[UDEdgePerm::rank(&d.0)];
+        corners.max(edges) as u32
+    }
+}
@@ -0,0 +1,80 @@
+//! One-hot encodings of states, as neural networks take them.
+//!
+//! `Pieces` has a row of 24 for each of the 20 piece slots, edges then
+//! corners: the piece and orientation there, as `piece * 2 + ori` for
+//! edges and `piece * 3 + ori` for corners. `Facelets` has a row of 6 for
+//! each of the 54 stickers in `facelet` order: the sticker's color. Rows
+//! are laid out one after another, and a batch is one state after
+//! another, so a batch of `n` is an `n` by `size()` row-major matrix.
+use crate::cube::Cube;
+use crate::facelet::Facelets;
+use crate::layout::packed3::{CORNERS, EDGES};
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// 20 x 24.
+    Pieces,
+    /// 54 x 6.
+    Facelets,
+}
+
+impl Encoding {
+    /// Number of values per state.
+    pub const fn size(self) -> usize {
+        match self {
+            Encoding::Pieces => 20 * 24,
+            Encoding::Facelets => 54 * 6,
+        }
+    }
+
+    /// Writes the encoding of `cube` to `out`, which must be `size()` long.
+    pub fn encode_into(self, cube: &Cube, out: &mut [f32]) {
+        assert_eq!(out.len(), self.size(), "output has the wrong length");
+        out.fill(0.0);
+        match self {
+            Encoding::Pieces => {
+                for (i, e) in cube.edges().iter().enumerate() {
+                    let v = EDGES.piece(e.0) * 2 + EDGES.ori(e.0);
+                    out[24 * i + v as usize] = 1.0;
+                }
+                for (i, c) in cube.corners().iter().enumerate() {
+                    let v = CORNERS.piece(c.0) * 3 + CORNERS.ori(c.0);
+                    out[24 * (12 + i) + v as usize] = 1.0;
+                }
+            }
+            Encoding::Facelets => {
+                let f = Facelets::from_cube(cube);
+                for (i, &color) in f.0.iter().enumerate() {
+                    out[6 * i + color as usize] = 1.0;
+                }
+            }
+        }
+    }
+
+    pub fn encode(self, cube: &Cube) -> Vec<f32> {
+        let mut out = vec![0.0; self.size()];
+        self.encode_into(cube, &mut out);
+        out
+    }
+
+    /// The encodings of `cubes`, one after another.
+    pub fn encode_batch(self, cubes: &[Cube]) -> Vec<f32> {
+        let mut out = vec![0.0; cubes.len() * self.size()];
+        for (cube, row) in cubes.iter().zip(out.chunks_exact_mut(self.size())) {
+            self.encode_into(cube, row);
+        }
+        out
+    }
+
+    /// `encode_batch` as an `n` by `size()` array.
+    #[cfg(feature = "ndarray")]
+    pub fn encode_array(self, cubes: &[Cube]) -> ndarray::Array2<f32> {
+        ndarray::Array2::from_shape_vec(
+            (cubes.len(), self.size()),
+            self.encode_batch(cubes),
+        )
+        .expect("one row per cube")
+    }
+}
@@ -0,0 +1,225 @@
+//! How easy an alg is to execute by hand, and the easiest way to execute
+//! one.
+//!
+//! A `Scorer` gives an alg a cost, lower being easier. The built-in
+//! `Fingertricks` follows the grip of each hand: R and L turns are wrist
+//! turns, and too many one way make the hand regrip; F, B and D need a
+//! hand at home. The optimizer tries each alg of a case from each angle,
+//! with the U turns that angle needs, and each way of holding the cube,
+//! and keeps the cheapest.
+use crate::alg::Alg;
+use crate::auf;
+use crate::cube::Cube;
+use crate::notation::{Face, Move};
+use crate::pattern::Goal;
+use crate::rotation::CubeRotation;
+use alloc::string::ToString;
+use core::fmt;
+
+/// A cost of executing algs by hand.
+pub trait Scorer {
+    fn score(&self, alg: &Alg) -> u32;
+
+    /// Cost of rotating the cube by `rotation` before an alg.
+    fn rotation_cost(&self, _rotation: CubeRotation) -> u32 {
+        0
+    }
+}
+
+impl<F: Fn(&Alg) -> u32> Scorer for F {
+    fn score(&self, alg: &Alg) -> u32 {
+        self(alg)
+    }
+}
+
+/// A model of two-handed execution, each cost a weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingertricks {
+    /// Cost of a turn of each face, in `Face` order.
+    pub face: [u32; 6],
+    /// Added for a half turn.
+    pub half_turn: u32,
+    /// Added each time a hand regrips.
+    pub regrip: u32,
+    /// Taken off for each trigger, as `R U R'` or `R' F R F'`.
+    pub trigger: u32,
+    /// Cost of each x, y or z turn of a rotation.
+    pub rotation: u32,
+}
+
+impl Default for Fingertricks {
+    /// U and R cheapest, B dearest, a regrip worth three turns.
+    fn default() -> Self {
+        Self {
+            face: [1, 1, 2, 2, 2, 4],
+            half_turn: 1,
+            regrip: 3,
+            trigger: 1,
+            rotation: 2,
+        }
+    }
+}
+
+/// Quarter turns a wrist can be from home: one back, as after R', or two
+/// forward, as after R2 or R R.
+const WRIST_RANGE: core::ops::RangeInclusive<i8> = -1..=2;
+
+/// Where each hand's wrist is, in quarter turns of its face from home.
+#[derive(Debug, Clone, Copy, Default)]
+struct Grip {
+    right: i8,
+    left: i8,
+}
+
+impl Grip {
+    /// The grip after `m`, and whether a hand regripped first.
+    fn turn(self, m: Move) -> (Grip, bool) {
+        // Half turns go whichever way is comfortable, taken as forward.
+        let quarters = match m.power() {
+            3 => -1,
+            p => p as i8,
+        };
+        let mirrored = match m.power() {
+            2 => 2,
+            p => p as i8 - 2,
+        };
+        match m.face() {
+            Face::R => {
+                let regrip = !WRIST_RANGE.contains(&(self.right + quarters));
+                let from = if regrip { 0 } else { self.right };
+                let grip = Grip {
+                    right: from + quarters,
+                    ..self
+                };
+                (grip, regrip)
+            }
+            // The left hand's L' is the right hand's R.
+            Face::L => {
+                let regrip = !WRIST_RANGE.contains(&(self.left + mirrored));
+                let from = if regrip { 0 } else { self.left };
+                let grip = Grip {
+                    left: from + mirrored,
+                    ..self
+                };
+                (grip, regrip)
+            }
+            Face::U => (self, false),
+            // F, D and B are turned by a finger of a hand at home.
+            _ if self.right == 0 || self.left == 0 => (self, false),
+            _ => (Grip { right: 0, ..self }, true),
+        }
+    }
+}
+
+/// Whether `moves` starts with a trigger: an R or L quarter turn, a U
+/// quarter turn and the first undone, or the sledgehammer `R' F R F'`.
+fn trigger_len(moves: &[Move]) -> Option<usize> {
+    match moves {
+        [a, Move::U | Move::Ui, c, ..]
+            if matches!(a.face(), Face::R | Face::L)
+                && a.power() != 2
+                && *c == a.inverse() =>
+        {
+            Some(3)
+        }
+        [Move::Ri, Move::F, Move::R, Move::Fi, ..] => Some(4),
+        _ => None,
+    }
+}
+
+impl Scorer for Fingertricks {
+    fn score(&self, alg: &Alg) -> u32 {
+        let moves = alg.moves();
+        let mut cost = 0;
+        let mut grip = Grip::default();
+        for &m in moves {
+            cost += self.face[m.face() as usize];
+            if m.power() == 2 {
+                cost += self.half_turn;
+            }
+            let (next, regrip) = grip.turn(m);
+            grip = next;
+            if regrip {
+                cost += self.regrip;
+            }
+        }
+        let mut triggers = 0;
+        let mut i = 0;
+        while i < moves.len() {
+            match trigger_len(&moves[i..]) {
+                Some(n) => {
+                    triggers += 1;
+                    i += n;
+                }
+                None => i += 1,
+            }
+        }
+        cost.saturating_sub(triggers * self.trigger)
+    }
+
+    fn rotation_cost(&self, rotation: CubeRotation) -> u32 {
+        let turns = rotation.to_string().split_whitespace().count() as u32;
+        turns * self.rotation
+    }
+}
+
+/// An alg as executed: the cube rotated by `rotation`, then `alg`, written
+/// for the rotated cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution {
+    pub rotation: CubeRotation,
+    pub alg: Alg,
+    pub score: u32,
+}
+
+impl Execution {
+    /// The turns made, in the frame before the rotation.
+    pub fn moves(&self) -> Alg {
+        self.rotation.map_alg(&self.alg)
+    }
+}
+
+impl fmt::Display for Execution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.rotation != CubeRotation::IDENTITY {
+            write!(f, "{} ", self.rotation)?;
+        }
+        write!(f, "{}", self.alg)
+    }
+}
+
+/// The cheapest way to hold the cube for `alg`: the rotation, and `alg`
+/// written after it, that score least, no rotation on ties.
+pub fn best_grip<S: Scorer>(scorer: &S, alg: &Alg) -> Execution {
+    CubeRotation::all()
+        .map(|rotation| {
+            let written = rotation.inverse().map_alg(alg);
+            let score = scorer.rotation_cost(rotation) + scorer.score(&written);
+            Execution {
+                rotation,
+                alg: written,
+                score,
+            }
+        })
+        .min_by_key(|e| e.score)
+        .expect("24 rotations")
+}
+
+/// The cheapest execution taking `state` to `goal` by one of `algs`: each
+/// alg is tried from the four angles about U, with the U turns before and
+/// after it that the angle needs, and in its best grip. `None` if no alg
+/// reaches `goal` from any angle.
+pub fn best_alg<S: Scorer, G: Goal<Cube>>(
+    scorer: &S,
+    state: &Cube,
+    algs: &[Alg],
+    goal: &G,
+) -> Option<Execution> {
+    algs.iter()
+        .flat_map(|alg| {
+            (0..4).map(move |k| CubeRotation::Y.pow(k).map_alg(alg))
+        })
+        .filter_map(|alg| auf::stitch(state, &alg, goal))
+        .map(|alg| best_grip(scorer, &alg))
+        .min_by_key(|e| e.score)
+}
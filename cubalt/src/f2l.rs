@@ -0,0 +1,204 @@
+//! The 41 F2L cases, and their recognition.
+//!
+//! A case is where the front-right pair lies, its DFR corner and FR edge,
+//! with the cross and the other three slots solved. Each piece is in the U
+//! layer or in the slot, and cases differ by more than a U turn: a corner
+//! in the U layer is taken at URF, and otherwise an edge in the U layer at
+//! UF. The cases are numbered from 1 in `F2lCase::ALL` order, both pieces
+//! in the U layer first; this is not the numbering of any case sheet.
+use crate::auf::{self, AUFS};
+use crate::cube::{Corner, Cube, Edge};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::notation::Move;
+use crate::pattern::Pattern;
+use crate::rotation::CubeRotation;
+use core::fmt;
+
+/// Slots of the front-right pair.
+const DFR: u8 = 4;
+const FR: u8 = 8;
+/// U-layer slots the cases are taken at.
+const URF: u8 = 0;
+const UF: u8 = 1;
+
+/// One F2L case: the slot and orientation of each piece of the pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct F2lCase {
+    /// Slot and twist of the DFR corner: URF or DFR.
+    pub corner: (u8, u8),
+    /// Slot and flip of the FR edge: a U-layer slot or FR.
+    pub edge: (u8, u8),
+}
+
+impl F2lCase {
+    pub const ALL: [F2lCase; 41] = all_cases();
+
+    /// The case's number, from 1.
+    pub fn id(self) -> usize {
+        F2lCase::ALL
+            .iter()
+            .position(|&c| c == self)
+            .expect("a case of ALL")
+            + 1
+    }
+
+    /// The case with number `id`.
+    pub fn from_id(id: usize) -> Option<Self> {
+        id.checked_sub(1).and_then(|i| F2lCase::ALL.get(i)).copied()
+    }
+
+    /// A state with the case, the rest of F2L solved and the last layer
+    /// scrambled as little as legality allows.
+    pub fn state(self) -> Cube {
+        let mut c = Cube::identity();
+        let (cs, co) = self.corner;
+        let (es, eo) = self.edge;
+        // A piece in the last layer swaps with the one in its slot, which
+        // takes the opposite orientation; a piece in its slot gives the
+        // opposite orientation to URF or UF.
+        let corners = c.corners_mut();
+        if cs == DFR {
+            corners[DFR as usize] = Corner(CORNERS.pack(DFR, co));
+            corners[URF as usize] = Corner(CORNERS.pack(URF, (3 - co) % 3));
+        } else {
+            corners[DFR as usize] = Corner(CORNERS.pack(cs, (3 - co) % 3));
+            corners[cs as usize] = Corner(CORNERS.pack(DFR, co));
+        }
+        let edges = c.edges_mut();
+        if es == FR {
+            edges[FR as usize] = Edge(EDGES.pack(FR, eo));
+            edges[UF as usize] = Edge(EDGES.pack(UF, eo));
+        } else {
+            edges[FR as usize] = Edge(EDGES.pack(es, eo));
+            edges[es as usize] = Edge(EDGES.pack(FR, eo));
+        }
+        if (cs == DFR) != (es == FR) {
+            // One swap is odd: swap two more last-layer edges.
+            let mut others = (0..4u8).filter(|&i| i != es);
+            let (a, b) = (others.next().unwrap(), others.next().unwrap());
+            edges.swap(a as usize, b as usize);
+        }
+        c
+    }
+}
+
+const fn all_cases() -> [F2lCase; 41] {
+    let mut cases = [F2lCase {
+        corner: (DFR, 0),
+        edge: (FR, 0),
+    }; 41];
+    let mut n = 0;
+    // The corner in the U layer, the edge anywhere.
+    let mut co = 0;
+    while co < 3 {
+        let mut es = 0;
+        while es < 4 {
+            let mut eo = 0;
+            while eo < 2 {
+                cases[n] = F2lCase {
+                    corner: (URF, co),
+                    edge: (es, eo),
+                };
+                n += 1;
+                eo += 1;
+            }
+            es += 1;
+        }
+        co += 1;
+    }
+    co = 0;
+    while co < 3 {
+        let mut eo = 0;
+        while eo < 2 {
+            cases[n] = F2lCase {
+                corner: (URF, co),
+                edge: (FR, eo),
+            };
+            n += 1;
+            eo += 1;
+        }
+        co += 1;
+    }
+    // The corner in the slot.
+    co = 0;
+    while co < 3 {
+        let mut eo = 0;
+        while eo < 2 {
+            cases[n] = F2lCase {
+                corner: (DFR, co),
+                edge: (UF, eo),
+            };
+            n += 1;
+            eo += 1;
+        }
+        co += 1;
+    }
+    co = 0;
+    while co < 3 {
+        let mut eo = 0;
+        while eo < 2 {
+            if co != 0 || eo != 0 {
+                cases[n] = F2lCase {
+                    corner: (DFR, co),
+                    edge: (FR, eo),
+                };
+                n += 1;
+            }
+            eo += 1;
+        }
+        co += 1;
+    }
+    cases
+}
+
+impl fmt::Display for F2lCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "F2L {}", self.id())
+    }
+}
+
+/// A case found by `recognize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct F2lMatch {
+    pub case: F2lCase,
+    /// The unsolved slot, an index into `F2L_SLOTS`.
+    pub slot: usize,
+    /// The U turn to make first, after which the slot, turned to the front
+    /// right by a y rotation, shows `case`.
+    pub auf: Option<Move>,
+}
+
+/// The case of `cube`, if its cross and all but one F2L slot are solved.
+pub fn recognize(cube: &Cube) -> Option<F2lMatch> {
+    if Pattern::f2l().matches(cube) {
+        return None;
+    }
+    let slot = (0..4).find(|&s| Pattern::f2l_minus_one(s).matches(cube))?;
+    let front = (0..4)
+        .map(|k| CubeRotation::Y.pow(k).conjugate(cube))
+        .find(|c| Pattern::f2l_minus_one(0).matches(c))?;
+    AUFS.iter().find_map(|&auf| {
+        let c = front.compose(&auf::cube(auf));
+        let corner = c
+            .corners()
+            .iter()
+            .position(|x| CORNERS.piece(x.0) == DFR)
+            .map(|i| (i as u8, CORNERS.ori(c.corners()[i].0)))?;
+        let edge = c
+            .edges()
+            .iter()
+            .position(|x| EDGES.piece(x.0) == FR)
+            .map(|i| (i as u8, EDGES.ori(c.edges()[i].0)))?;
+        let case = F2lCase { corner, edge };
+        let taken = match (corner.0, edge.0) {
+            (DFR, FR) => auf.is_none(),
+            (DFR, _) => edge.0 == UF,
+            _ => corner.0 == URF,
+        };
+        (taken && F2lCase::ALL.contains(&case)).then_some(F2lMatch {
+            case,
+            slot,
+            auf,
+        })
+    })
+}
@@ -0,0 +1,596 @@
+//! Facelet (sticker) level model.
+//!
+//! This is the bridge between physical cubes (scanners, manual entry) and
+//! the cubie representation in `Cube`. Stickers are numbered the usual way:
+//! faces in U R F D L B order, each read row by row from outside, the U face
+//! with B on top, the D face with F on top and the side faces with U on top.
+use crate::cube::{Corner, Cube, Edge};
+use crate::cycles::{CORNER_NAMES, EDGE_NAMES};
+use crate::layout::packed3::{CORNERS, EDGES};
+use alloc::{format, string::String};
+use core::fmt;
+
+/// A sticker color, named after the face whose center has that color.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+
+impl Color {
+    pub const ALL: [Color; 6] =
+        [Color::U, Color::R, Color::F, Color::D, Color::L, Color::B];
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' => Some(Color::U),
+            'R' => Some(Color::R),
+            'F' => Some(Color::F),
+            'D' => Some(Color::D),
+            'L' => Some(Color::L),
+            'B' => Some(Color::B),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        b"URFDLB"[self as usize] as char
+    }
+}
+
+/// Sticker positions of each corner slot, reference (U/D) sticker first,
+/// then clockwise.
+pub const CORNER_FACELETS: [[usize; 3]; 8] = [
+    [8, 9, 20],   // URF
+    [6, 18, 38],  // UFL
+    [0, 36, 47],  // ULB
+    [2, 45, 11],  // UBR
+    [29, 26, 15], // DFR
+    [27, 44, 24], // DLF
+    [33, 53, 42], // DBL
+    [35, 17, 51], // DRB
+];
+
+/// Sticker positions of each edge slot, reference sticker first.
+pub const EDGE_FACELETS: [[usize; 2]; 12] = [
+    [5, 10],  // UR
+    [7, 19],  // UF
+    [3, 37],  // UL
+    [1, 46],  // UB
+    [32, 16], // DR
+    [28, 25], // DF
+    [30, 43], // DL
+    [34, 52], // DB
+    [23, 12], // FR
+    [21, 41], // FL
+    [50, 39], // BL
+    [48, 14], // BR
+];
+
+const CORNER_COLORS: [[Color; 3]; 8] = {
+    use Color::*;
+    [
+        [U, R, F],
+        [U, F, L],
+        [U, L, B],
+        [U, B, R],
+        [D, F, R],
+        [D, L, F],
+        [D, B, L],
+        [D, R, B],
+    ]
+};
+
+const EDGE_COLORS: [[Color; 2]; 12] = {
+    use Color::*;
+    [
+        [U, R],
+        [U, F],
+        [U, L],
+        [U, B],
+        [D, R],
+        [D, F],
+        [D, L],
+        [D, B],
+        [F, R],
+        [F, L],
+        [B, L],
+        [B, R],
+    ]
+};
+
+/// Why a sticker configuration does not describe a legal cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaceletError {
+    /// A character other than U, R, F, D, L, B, or the wrong length.
+    Syntax,
+    /// The center stickers are not U R F D L B in face order.
+    Centers,
+    /// A color is on this many stickers rather than nine.
+    ColorCount(Color, usize),
+    /// The stickers at this corner slot match no corner.
+    Corner(usize),
+    /// The stickers at this edge slot match no edge.
+    Edge(usize),
+    /// Some corner appears twice (and so another is missing).
+    DuplicateCorner(usize),
+    /// Some edge appears twice (and so another is missing).
+    DuplicateEdge(usize),
+    /// Corner orientations do not sum to zero.
+    Twist,
+    /// Edge orientations do not sum to zero.
+    Flip,
+    /// Edge and corner permutations have different parity.
+    Parity,
+}
+
+impl fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FaceletError::Syntax => write!(f, "expected 54 of URFDLB"),
+            FaceletError::Centers => write!(f, "centers are not URFDLB"),
+            FaceletError::ColorCount(c, n) => {
+                write!(f, "color {}: {} stickers, expected 9", c.to_char(), n)
+            }
+            FaceletError::Corner(i) => {
+                write!(f, "corner slot {} is invalid", i)
+            }
+            FaceletError::Edge(i) => write!(f, "edge slot {} is invalid", i),
+            FaceletError::DuplicateCorner(i) => {
+                write!(f, "corner {} appears twice", i)
+            }
+            FaceletError::DuplicateEdge(i) => {
+                write!(f, "edge {} appears twice", i)
+            }
+            FaceletError::Twist => write!(f, "a corner is twisted"),
+            FaceletError::Flip => write!(f, "an edge is flipped"),
+            FaceletError::Parity => write!(f, "two pieces are swapped"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FaceletError {}
+
+fn name(bytes: &[u8]) -> &str {
+    core::str::from_utf8(bytes).expect("slot names are ASCII")
+}
+
+impl FaceletError {
+    /// Where on the physical cube to look for the mistake.
+    pub fn hint(&self) -> String {
+        match *self {
+            FaceletError::Syntax => {
+                "each face is nine stickers, one letter each".into()
+            }
+            FaceletError::Centers => {
+                "each face's middle sticker must be its own color".into()
+            }
+            FaceletError::ColorCount(c, n) => format!(
+                "{} stickers are the color of the {} center, expected 9; \
+                 {} misread",
+                n,
+                c.to_char(),
+                if n.abs_diff(9) == 1 {
+                    "one was"
+                } else {
+                    "some were"
+                }
+            ),
+            FaceletError::Corner(i) => format!(
+                "the stickers of corner {} are not the colors of any \
+                 corner; recheck them",
+                name(&CORNER_NAMES[i])
+            ),
+            FaceletError::Edge(i) => format!(
+                "the stickers of edge {} are not the colors of any edge; \
+                 recheck them",
+                name(&EDGE_NAMES[i])
+            ),
+            FaceletError::DuplicateCorner(j) => format!(
+                "two corners have the colors of {}; one was misread",
+                name(&CORNER_NAMES[j])
+            ),
+            FaceletError::DuplicateEdge(j) => format!(
+                "two edges have the colors of {}; one was misread",
+                name(&EDGE_NAMES[j])
+            ),
+            FaceletError::Twist => "one corner is twisted in place: either \
+                 its stickers were misread, or the cube was put back \
+                 together wrong"
+                .into(),
+            FaceletError::Flip => "one edge is flipped in place: either its \
+                 stickers were misread, or the cube was put back together \
+                 wrong"
+                .into(),
+            FaceletError::Parity => "two pieces are swapped: either two \
+                 stickers were read the wrong way round, or the cube was \
+                 put back together wrong"
+                .into(),
+        }
+    }
+}
+
+/// A cube described sticker by sticker.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Facelets(pub [Color; 54]);
+
+impl Facelets {
+    pub fn solved() -> Self {
+        let mut f = [Color::U; 54];
+        for (i, c) in f.iter_mut().enumerate() {
+            *c = Color::ALL[i / 9];
+        }
+        Self(f)
+    }
+
+    /// The stickers of a cubie-level state.
+    pub fn from_cube(cube: &Cube) -> Self {
+        let mut f = Self::solved();
+        for (i, c) in cube.corners().iter().enumerate() {
+            let (j, ori) = (CORNERS.piece(c.0), CORNERS.ori(c.0));
+            for n in 0..3 {
+                f.0[CORNER_FACELETS[i][(n + ori as usize) % 3]] =
+                    CORNER_COLORS[j as usize][n];
+            }
+        }
+        for (i, e) in cube.edges().iter().enumerate() {
+            let (j, ori) = (EDGES.piece(e.0), EDGES.ori(e.0));
+            for n in 0..2 {
+                f.0[EDGE_FACELETS[i][(n + ori as usize) % 2]] =
+                    EDGE_COLORS[j as usize][n];
+            }
+        }
+        f
+    }
+
+    /// Recover the cubie-level state, checking that the stickers describe a
+    /// cube reachable from solved.
+    pub fn to_cube(&self) -> Result<Cube, FaceletError> {
+        let cube = self.to_cube_unchecked()?;
+        let twist: u32 =
+            cube.corners().iter().map(|c| CORNERS.ori(c.0) as u32).sum();
+        if !twist.is_multiple_of(3) {
+            return Err(FaceletError::Twist);
+        }
+        let flip: u32 =
+            cube.edges().iter().map(|e| EDGES.ori(e.0) as u32).sum();
+        if !flip.is_multiple_of(2) {
+            return Err(FaceletError::Flip);
+        }
+        if cube.parity() {
+            return Err(FaceletError::Parity);
+        }
+        Ok(cube)
+    }
+
+    /// Recover the cubie-level state, checking only that every slot holds a
+    /// real piece and every piece appears once. The result may be
+    /// unreachable (twisted corner, flipped edge, swapped pair).
+    pub fn to_cube_unchecked(&self) -> Result<Cube, FaceletError> {
+        let f = &self.0;
+        for (i, &c) in Color::ALL.iter().enumerate() {
+            if f[9 * i + 4] != c {
+                return Err(FaceletError::Centers);
+            }
+        }
+        for &c in Color::ALL.iter() {
+            let n = f.iter().filter(|&&x| x == c).count();
+            if n != 9 {
+                return Err(FaceletError::ColorCount(c, n));
+            }
+        }
+
+        let mut cube = Cube::identity();
+        let mut seen = 0u32;
+        for i in 0..8 {
+            let (j, ori) = (0..8)
+                .flat_map(|j| (0..3).map(move |ori| (j, ori)))
+                .find(|&(j, ori)| {
+                    (0..3).all(|n| {
+                        f[CORNER_FACELETS[i][(n + ori) % 3]]
+                            == CORNER_COLORS[j][n]
+                    })
+                })
+                .ok_or(FaceletError::Corner(i))?;
+            if seen & (1 << j) != 0 {
+                return Err(FaceletError::DuplicateCorner(j));
+            }
+            seen |= 1 << j;
+            cube.corners_mut()[i] = Corner(CORNERS.pack(j as u8, ori as u8));
+        }
+
+        seen = 0;
+        for i in 0..12 {
+            let (j, ori) = (0..12)
+                .flat_map(|j| (0..2).map(move |ori| (j, ori)))
+                .find(|&(j, ori)| {
+                    (0..2).all(|n| {
+                        f[EDGE_FACELETS[i][(n + ori) % 2]] == EDGE_COLORS[j][n]
+                    })
+                })
+                .ok_or(FaceletError::Edge(i))?;
+            if seen & (1 << j) != 0 {
+                return Err(FaceletError::DuplicateEdge(j));
+            }
+            seen |= 1 << j;
+            cube.edges_mut()[i] = Edge(EDGES.pack(j as u8, ori as u8));
+        }
+        Ok(cube)
+    }
+
+    /// The stickers as an unfolded net: U on top, then L F R B, then D.
+    pub fn net(&self) -> String {
+        let row = |face: usize, r: usize| -> String {
+            (0..3)
+                .map(|c| self.0[9 * face + 3 * r + c].to_char())
+                .collect()
+        };
+        let mut s = String::new();
+        for r in 0..3 {
+            s += &format!("    {}\n", row(0, r));
+        }
+        for r in 0..3 {
+            s += &format!(
+                "{} {} {} {}\n",
+                row(4, r),
+                row(2, r),
+                row(1, r),
+                row(5, r)
+            );
+        }
+        for r in 0..3 {
+            s += &format!("    {}\n", row(3, r));
+        }
+        s
+    }
+
+    /// Apply a move (or any other orientation-preserving cube transformation)
+    /// directly to the stickers. This works on any sticker configuration,
+    /// including ones `to_cube` would reject.
+    pub fn apply(&self, m: &Cube) -> Self {
+        let perm = sticker_permutation(m);
+        let mut f = *self;
+        for (dst, &src) in perm.iter().enumerate() {
+            f.0[dst] = self.0[src];
+        }
+        f
+    }
+}
+
+/// Stickers entered so far, `None` where not yet known, for checking a cube
+/// while it is typed in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PartialFacelets(pub [Option<Color>; 54]);
+
+impl Default for PartialFacelets {
+    fn default() -> Self {
+        Self([None; 54])
+    }
+}
+
+impl PartialFacelets {
+    /// The first mistake the known stickers already show: a wrong center,
+    /// a color on more than nine stickers, a slot whose known stickers fit
+    /// no piece, or a piece seen twice. Twists, flips and swaps only show
+    /// once every sticker is known; see `complete`.
+    pub fn check(&self) -> Result<(), FaceletError> {
+        let f = &self.0;
+        for (i, &c) in Color::ALL.iter().enumerate() {
+            if f[9 * i + 4].is_some_and(|x| x != c) {
+                return Err(FaceletError::Centers);
+            }
+        }
+        for &c in Color::ALL.iter() {
+            let n = f.iter().filter(|&&x| x == Some(c)).count();
+            if n > 9 {
+                return Err(FaceletError::ColorCount(c, n));
+            }
+        }
+        // Pieces each slot may hold, and those known in full.
+        let fits = |slot: &[usize], colors: &[Color], ori: usize| {
+            (0..slot.len()).all(|n| {
+                f[slot[(n + ori) % slot.len()]].is_none_or(|x| x == colors[n])
+            })
+        };
+        let mut seen = 0u32;
+        for (i, slot) in CORNER_FACELETS.iter().enumerate() {
+            let mut pieces = (0..8)
+                .filter(|&j| (0..3).any(|o| fits(slot, &CORNER_COLORS[j], o)));
+            let j = pieces.next().ok_or(FaceletError::Corner(i))?;
+            if slot.iter().all(|&k| f[k].is_some()) {
+                if seen & (1 << j) != 0 {
+                    return Err(FaceletError::DuplicateCorner(j));
+                }
+                seen |= 1 << j;
+            }
+        }
+        seen = 0;
+        for (i, slot) in EDGE_FACELETS.iter().enumerate() {
+            let mut pieces = (0..12)
+                .filter(|&j| (0..2).any(|o| fits(slot, &EDGE_COLORS[j], o)));
+            let j = pieces.next().ok_or(FaceletError::Edge(i))?;
+            if slot.iter().all(|&k| f[k].is_some()) {
+                if seen & (1 << j) != 0 {
+                    return Err(FaceletError::DuplicateEdge(j));
+                }
+                seen |= 1 << j;
+            }
+        }
+        Ok(())
+    }
+
+    /// The whole sticker set, once every sticker is known.
+    pub fn complete(&self) -> Option<Facelets> {
+        let mut f = [Color::U; 54];
+        for (x, c) in f.iter_mut().zip(self.0.iter()) {
+            *x = (*c)?;
+        }
+        Some(Facelets(f))
+    }
+}
+
+/// For a transformation `m`, the sticker position each position takes its
+/// sticker from. Centers stay put.
+pub(crate) fn sticker_permutation(m: &Cube) -> [usize; 54] {
+    let mut perm = [0; 54];
+    for (i, p) in perm.iter_mut().enumerate() {
+        *p = i;
+    }
+    for (i, c) in m.corners().iter().enumerate() {
+        let (j, ori) = (CORNERS.piece(c.0) as usize, CORNERS.ori(c.0) as usize);
+        for k in 0..3 {
+            perm[CORNER_FACELETS[i][(k + ori) % 3]] = CORNER_FACELETS[j][k];
+        }
+    }
+    for (i, e) in m.edges().iter().enumerate() {
+        let (j, ori) = (EDGES.piece(e.0) as usize, EDGES.ori(e.0) as usize);
+        for k in 0..2 {
+            perm[EDGE_FACELETS[i][(k + ori) % 2]] = EDGE_FACELETS[j][k];
+        }
+    }
+    perm
+}
+
+impl fmt::Display for Facelets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.iter() {
+            write!(f, "{}", c.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Facelets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Facelets({})", self)
+    }
+}
+
+impl core::str::FromStr for Facelets {
+    type Err = FaceletError;
+
+    /// Parse the usual 54-character `UUUUUUUUURRR...` string.
+    fn from_str(s: &str) -> Result<Self, FaceletError> {
+        let mut f = [Color::U; 54];
+        let mut n = 0;
+        for ch in s.chars() {
+            let c = Color::from_char(ch).ok_or(FaceletError::Syntax)?;
+            *f.get_mut(n).ok_or(FaceletError::Syntax)? = c;
+            n += 1;
+        }
+        if n != 54 {
+            return Err(FaceletError::Syntax);
+        }
+        Ok(Self(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// The solved stickers with `edit` made to them.
+    fn solved_with(edit: impl FnOnce(&mut [Color; 54])) -> Facelets {
+        let mut f = Facelets::solved();
+        edit(&mut f.0);
+        f
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn random_cubes_round_trip() {
+        use crate::notation::Move;
+        use crate::random::{random_cube, Rng};
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let cube = random_cube(&mut rng);
+            let f = Facelets::from_cube(&cube);
+            assert_eq!(f.to_cube().unwrap(), cube);
+            assert_eq!(f.to_string().parse::<Facelets>(), Ok(f));
+            let m = rng.choose(&Move::ALL).cube();
+            assert_eq!(f.apply(m), Facelets::from_cube(&cube.compose(m)));
+        }
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let solved = Facelets::solved().to_string();
+        assert_eq!("UUU".parse::<Facelets>(), Err(FaceletError::Syntax));
+        let long = format!("{}U", solved);
+        assert_eq!(long.parse::<Facelets>(), Err(FaceletError::Syntax));
+        let bad = solved.replacen('R', "X", 1);
+        assert_eq!(bad.parse::<Facelets>(), Err(FaceletError::Syntax));
+    }
+
+    #[test]
+    fn wrong_counts() {
+        let centers = solved_with(|f| f.swap(4, 13));
+        assert_eq!(centers.to_cube(), Err(FaceletError::Centers));
+        let count = solved_with(|f| f[0] = Color::R);
+        let err = count.to_cube().unwrap_err();
+        assert_eq!(err, FaceletError::ColorCount(Color::U, 8));
+        assert_eq!(err.to_string(), "color U: 8 stickers, expected 9");
+        let count = solved_with(|f| f[..3].copy_from_slice(&[Color::F; 3]));
+        let err = count.to_cube().unwrap_err();
+        assert_eq!(err, FaceletError::ColorCount(Color::U, 6));
+        assert!(err.hint().starts_with("6 stickers"));
+    }
+
+    #[test]
+    fn impossible_pieces() {
+        // The U sticker of URF and the F sticker of UF trade places, so URF
+        // shows F twice.
+        let corner =
+            solved_with(|f| f.swap(CORNER_FACELETS[0][0], EDGE_FACELETS[1][1]));
+        assert_eq!(corner.to_cube(), Err(FaceletError::Corner(0)));
+        let edge =
+            solved_with(|f| f.swap(EDGE_FACELETS[0][0], EDGE_FACELETS[8][1]));
+        assert_eq!(edge.to_cube(), Err(FaceletError::Edge(0)));
+        // URF painted as UFL, UL as UR: the counts still add up.
+        let duplicate = solved_with(|f| {
+            for n in 0..3 {
+                f[CORNER_FACELETS[0][n]] = CORNER_COLORS[1][n];
+            }
+            f[EDGE_FACELETS[2][1]] = Color::R;
+        });
+        assert_eq!(duplicate.to_cube(), Err(FaceletError::DuplicateCorner(1)));
+        let duplicate = solved_with(|f| {
+            f[EDGE_FACELETS[0][1]] = Color::F;
+            f[EDGE_FACELETS[8][0]] = Color::R;
+        });
+        assert_eq!(duplicate.to_cube(), Err(FaceletError::DuplicateEdge(1)));
+    }
+
+    #[test]
+    fn unreachable_states() {
+        let [a, b, c] = CORNER_FACELETS[0];
+        let twist = solved_with(|f| {
+            let (x, y, z) = (f[a], f[b], f[c]);
+            f[a] = z;
+            f[b] = x;
+            f[c] = y;
+        });
+        assert_eq!(twist.to_cube(), Err(FaceletError::Twist));
+        assert!(twist.to_cube_unchecked().is_ok());
+
+        let flip =
+            solved_with(|f| f.swap(EDGE_FACELETS[0][0], EDGE_FACELETS[0][1]));
+        assert_eq!(flip.to_cube(), Err(FaceletError::Flip));
+        assert!(flip.to_cube_unchecked().is_ok());
+
+        let swap = solved_with(|f| {
+            for (&a, &b) in EDGE_FACELETS[0].iter().zip(&EDGE_FACELETS[1]) {
+                f.swap(a, b);
+            }
+        });
+        assert_eq!(swap.to_cube(), Err(FaceletError::Parity));
+        assert!(swap.to_cube_unchecked().is_ok());
+    }
+}
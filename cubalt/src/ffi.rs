@@ -0,0 +1,236 @@
+//! C ABI. See `include/cubalt.h` for the C declarations. Build a library
+//! to link against with
+//! `cargo rustc -p cubalt --release --features ffi --crate-type staticlib`
+//! (or `cdylib`).
+//!
+//! Cubes and solvers are opaque heap handles: every pointer returned must
+//! be released with the matching `_free` function. Strings are
+//! NUL-terminated UTF-8. Functions writing into a caller's buffer behave
+//! like `snprintf`: they write at most `len` bytes including the NUL and
+//! return the full length, so a too-small buffer can be retried.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::encode::Encoding;
+use crate::facelet::Facelets;
+use crate::solver::Solver;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_uint};
+use std::ptr;
+
+fn boxed(cube: Cube) -> *mut Cube {
+    Box::into_raw(Box::new(cube))
+}
+
+unsafe fn to_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Copy `src` and a NUL into `buf`, truncating to `len`.
+unsafe fn write_buf(src: &[u8], buf: *mut u8, len: usize) -> usize {
+    if !buf.is_null() && len > 0 {
+        let n = src.len().min(len - 1);
+        ptr::copy_nonoverlapping(src.as_ptr(), buf, n);
+        *buf.add(n) = 0;
+    }
+    src.len()
+}
+
+/// The solved cube.
+#[no_mangle]
+pub extern "C" fn cubalt_cube_new() -> *mut Cube {
+    boxed(Cube::identity())
+}
+
+/// # Safety
+/// `cube` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_free(cube: *mut Cube) {
+    if !cube.is_null() {
+        drop(Box::from_raw(cube));
+    }
+}
+
+/// # Safety
+/// `cube` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_clone(cube: *const Cube) -> *mut Cube {
+    boxed(*cube)
+}
+
+/// `a`, then `b`.
+///
+/// # Safety
+/// Both must be valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_compose(
+    a: *const Cube,
+    b: *const Cube,
+) -> *mut Cube {
+    boxed((*a).compose(&*b))
+}
+
+/// # Safety
+/// `cube` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_invert(cube: *const Cube) -> *mut Cube {
+    boxed((*cube).invert())
+}
+
+/// # Safety
+/// Both must be valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_equal(
+    a: *const Cube,
+    b: *const Cube,
+) -> c_int {
+    (*a == *b) as c_int
+}
+
+/// # Safety
+/// `cube` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_is_solved(cube: *const Cube) -> c_int {
+    (*cube == Cube::identity()) as c_int
+}
+
+/// The solved cube with `alg` applied, or NULL if it does not parse.
+///
+/// # Safety
+/// `alg` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_from_alg(alg: *const c_char) -> *mut Cube {
+    match to_str(alg).and_then(|s| s.parse::<Alg>().ok()) {
+        Some(alg) => boxed(alg.cube()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The cube described by a 54-letter facelet string, or NULL if it is not
+/// a reachable state.
+///
+/// # Safety
+/// `facelets` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_from_facelets(
+    facelets: *const c_char,
+) -> *mut Cube {
+    match to_str(facelets)
+        .and_then(|s| s.parse::<Facelets>().ok())
+        .and_then(|f| f.to_cube().ok())
+    {
+        Some(cube) => boxed(cube),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Apply `alg` in place. Returns 0, or -1 if it does not parse (leaving
+/// the cube unchanged).
+///
+/// # Safety
+/// `cube` must be a valid handle, `alg` NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_apply_alg(
+    cube: *mut Cube,
+    alg: *const c_char,
+) -> c_int {
+    match to_str(alg).and_then(|s| s.parse::<Alg>().ok()) {
+        Some(alg) => {
+            *cube = alg.apply(&*cube);
+            0
+        }
+        None => -1,
+    }
+}
+
+/// The 54-letter facelet string. Needs a 55-byte buffer.
+///
+/// # Safety
+/// `cube` must be a valid handle, `buf` NULL or valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_facelets(
+    cube: *const Cube,
+    buf: *mut u8,
+    len: usize,
+) -> usize {
+    let s = Facelets::from_cube(&*cube).to_string();
+    write_buf(s.as_bytes(), buf, len)
+}
+
+/// The raw 32-byte internal state (no NUL appended). Returns 32.
+///
+/// # Safety
+/// `cube` must be a valid handle, `buf` NULL or valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_bytes(
+    cube: *const Cube,
+    buf: *mut u8,
+    len: usize,
+) -> usize {
+    let bytes = (*cube).as_bytes();
+    if !buf.is_null() {
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buf, len.min(32));
+    }
+    32
+}
+
+/// The one-hot encoding of `cube`: pieces (480 values) if `facelets` is
+/// 0, facelets (324 values) otherwise; see `encode`. Writes only if `len`
+/// is enough, and returns the number of values.
+///
+/// # Safety
+/// `cube` must be a valid handle, `out` NULL or valid for `len` floats.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_cube_one_hot(
+    cube: *const Cube,
+    facelets: c_int,
+    out: *mut f32,
+    len: usize,
+) -> usize {
+    let encoding = if facelets == 0 {
+        Encoding::Pieces
+    } else {
+        Encoding::Facelets
+    };
+    let n = encoding.size();
+    if !out.is_null() && len >= n {
+        encoding.encode_into(&*cube, std::slice::from_raw_parts_mut(out, n));
+    }
+    n
+}
+
+/// Builds the pruning tables, which takes a few seconds.
+#[no_mangle]
+pub extern "C" fn cubalt_solver_new() -> *mut Solver {
+    Box::into_raw(Box::new(Solver::new()))
+}
+
+/// # Safety
+/// `solver` must come from this library and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_solver_free(solver: *mut Solver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Write a solution of at most `max_length` moves into `buf`. Returns its
+/// length in bytes, or -1 (as size_t) if none was found.
+///
+/// # Safety
+/// Handles must be valid, `buf` NULL or valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cubalt_solve(
+    solver: *const Solver,
+    cube: *const Cube,
+    max_length: c_uint,
+    buf: *mut u8,
+    len: usize,
+) -> usize {
+    match (*solver).solve(&*cube, max_length) {
+        Some(alg) => write_buf(alg.to_string().as_bytes(), buf, len),
+        None => usize::MAX,
+    }
+}
@@ -0,0 +1,197 @@
+//! Fewest-moves attempt bookkeeping, with NISS.
+//!
+//! Moves go either on the normal scramble or on its inverse. With scramble
+//! S, normal moves N and inverse moves I, the skeleton is N followed by
+//! I^-1, and the cube seen on the normal side is I^-1 * S * N. So the
+//! inverse moves are premoves: I^-1 is played before the scramble, and
+//! moves after the scramble end with it.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::notation::Move;
+use std::fmt;
+
+/// Which scramble moves currently go on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Normal,
+    Inverse,
+}
+
+impl Side {
+    pub fn other(self) -> Self {
+        match self {
+            Side::Normal => Side::Inverse,
+            Side::Inverse => Side::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Move(Side),
+    Switch,
+}
+
+/// An attempt in progress.
+#[derive(Debug, Clone)]
+pub struct FmcAttempt {
+    scramble: Alg,
+    normal: Alg,
+    inverse: Alg,
+    side: Side,
+    history: Vec<Op>,
+}
+
+impl FmcAttempt {
+    pub fn new(scramble: Alg) -> Self {
+        Self {
+            scramble,
+            normal: Alg::new(),
+            inverse: Alg::new(),
+            side: Side::Normal,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn scramble(&self) -> &Alg {
+        &self.scramble
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Moves done on the normal scramble.
+    pub fn normal(&self) -> &Alg {
+        &self.normal
+    }
+
+    /// Moves done on the inverse scramble.
+    pub fn inverse(&self) -> &Alg {
+        &self.inverse
+    }
+
+    /// The inverse moves as premoves, in the order they are played before
+    /// the scramble.
+    pub fn premoves(&self) -> Alg {
+        self.inverse.inverse()
+    }
+
+    /// Play a move on the current side.
+    pub fn push(&mut self, m: Move) {
+        match self.side {
+            Side::Normal => self.normal.push(m),
+            Side::Inverse => self.inverse.push(m),
+        }
+        self.history.push(Op::Move(self.side));
+    }
+
+    /// Play `m` before the premoves so far, whichever the current side:
+    /// the same as `m`'s inverse on the inverse side.
+    pub fn push_premove(&mut self, m: Move) {
+        self.inverse.push(m.inverse());
+        self.history.push(Op::Move(Side::Inverse));
+    }
+
+    pub fn push_alg(&mut self, alg: &Alg) {
+        for &m in alg.moves() {
+            self.push(m);
+        }
+    }
+
+    /// Continue on the other side.
+    pub fn switch(&mut self) {
+        self.side = self.side.other();
+        self.history.push(Op::Switch);
+    }
+
+    /// Undo the last move or switch. Returns false if there was none.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(Op::Move(Side::Normal)) => {
+                self.normal.0.pop();
+            }
+            Some(Op::Move(Side::Inverse)) => {
+                self.inverse.0.pop();
+            }
+            Some(Op::Switch) => self.side = self.side.other(),
+            None => return false,
+        }
+        true
+    }
+
+    /// The solution so far: normal moves, then the premoves.
+    pub fn skeleton(&self) -> Alg {
+        let mut alg = self.normal.clone();
+        alg.0.extend(self.premoves().0);
+        alg
+    }
+
+    /// The skeleton as moves after the scramble, cancelled across the
+    /// join.
+    pub fn solution(&self) -> Alg {
+        self.normal.with_premoves(&self.premoves())
+    }
+
+    /// Skeleton length after cancelling across the join.
+    pub fn move_count(&self) -> usize {
+        self.solution().len()
+    }
+
+    /// The cube as seen on the normal side: I^-1 * S * N.
+    pub fn normal_state(&self) -> Cube {
+        self.normal
+            .apply_with_premoves(&self.premoves(), &self.scramble)
+    }
+
+    /// The cube as seen on the inverse side: N^-1 * S^-1 * I.
+    pub fn inverse_state(&self) -> Cube {
+        self.normal_state().invert()
+    }
+
+    /// The cube as seen on the current side.
+    pub fn state(&self) -> Cube {
+        match self.side {
+            Side::Normal => self.normal_state(),
+            Side::Inverse => self.inverse_state(),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.normal_state() == Cube::identity()
+    }
+
+    /// Corners and edges not solved (misplaced or misoriented) after the
+    /// skeleton; what insertions still have to fix.
+    pub fn unsolved(&self) -> (usize, usize) {
+        let c = self.normal_state();
+        let corners = c
+            .corners()
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| x.0 != CORNERS.pack(*i as u8, 0))
+            .count();
+        let edges = c
+            .edges()
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| x.0 != EDGES.pack(*i as u8, 0))
+            .count();
+        (corners, edges)
+    }
+}
+
+/// The usual write-up: normal moves, then inverse moves in parentheses.
+impl fmt::Display for FmcAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.normal)?;
+        if !self.inverse.is_empty() {
+            if !self.normal.is_empty() {
+                write!(f, " ")?;
+            }
+            write!(f, "({})", self.inverse)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Positions known to be at distance 20, the most any position needs in
+//! half-turn moves.
+//!
+//! The superflip, every edge flipped in place, was the first shown to need
+//! 20 moves (Reid, 1995); composed with the four spot pattern it needs 20
+//! too, and 26 quarter turns. Each comes with a 20-move alg reaching it.
+//! Proving one of them at 20 searches every depth to 19, far beyond what
+//! these tables do in a day; `verify` with a lower bound of 13 or so checks
+//! the tables and solver against them in seconds, as a smoke test of a
+//! table build, and each move more takes about twenty times as long.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::optimal::Optimal;
+use core::fmt;
+
+/// A position of known distance, with an alg of that length reaching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownPosition {
+    pub name: &'static str,
+    pub distance: u32,
+    alg: &'static str,
+}
+
+impl KnownPosition {
+    /// An optimal alg reaching the position from solved.
+    pub fn alg(&self) -> Alg {
+        self.alg.parse().expect("known algs parse")
+    }
+
+    pub fn cube(&self) -> Cube {
+        self.alg().cube()
+    }
+}
+
+/// Every edge flipped in place.
+pub const SUPERFLIP: KnownPosition = KnownPosition {
+    name: "superflip",
+    distance: 20,
+    alg: "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+};
+
+/// The superflip composed with the four spot, F2 B2 U D' R2 L2 U D', which
+/// gives each side face its opposite's color but for the center.
+pub const SUPERFLIP_FOUR_SPOT: KnownPosition = KnownPosition {
+    name: "superflip + four spot",
+    distance: 20,
+    alg: "D' B2 U' R2 D' B2 D' B F U2 L' B2 R2 F2 D' U F' U2 L' R'",
+};
+
+/// The known positions at distance 20, one of each class under the
+/// cube's symmetries. The four spot on the other two axes gives positions
+/// symmetric to the second.
+pub const DISTANCE_20: [KnownPosition; 2] = [SUPERFLIP, SUPERFLIP_FOUR_SPOT];
+
+/// How a position failed to check out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The position's alg is not of its distance.
+    AlgLength(&'static str),
+    /// The solver found a solution shorter than the distance.
+    Shorter(&'static str, Alg),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::AlgLength(name) => {
+                write!(f, "{}: alg is not of its distance", name)
+            }
+            VerifyError::Shorter(name, alg) => {
+                write!(f, "{}: solved in {} moves by {}", name, alg.len(), alg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Checks that `optimal` finds no solution of `p` shorter than `below`
+/// moves, at most its distance; with `below` equal to the distance, that
+/// the solver reproduces it. A half-turn solver is assumed.
+pub fn verify(
+    optimal: &Optimal,
+    p: &KnownPosition,
+    below: u32,
+) -> Result<(), VerifyError> {
+    assert!(below <= p.distance, "{} is past the distance", below);
+    if p.alg().len() as u32 != p.distance {
+        return Err(VerifyError::AlgLength(p.name));
+    }
+    match below
+        .checked_sub(1)
+        .and_then(|d| optimal.solve(&p.cube(), d))
+    {
+        Some(alg) => Err(VerifyError::Shorter(p.name, alg)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::layout::packed3::EDGES;
+
+    #[test]
+    fn algs_reach_the_positions() {
+        let c = SUPERFLIP.cube();
+        let corners = |c: &Cube| c.corners().iter().map(|x| x.0).collect();
+        let solved: Vec<u8> = corners(&Cube::identity());
+        assert_eq!(corners(&c), solved);
+        for (slot, e) in c.edges().iter().enumerate() {
+            assert_eq!(EDGES.piece(e.0) as usize, slot);
+            assert_eq!(EDGES.ori(e.0), 1);
+        }
+        let four_spot: Alg = "F2 B2 U D' R2 L2 U D'".parse().unwrap();
+        assert_eq!(c.compose(&four_spot.cube()), SUPERFLIP_FOUR_SPOT.cube());
+    }
+
+    #[test]
+    fn positions_are_not_symmetric() {
+        for (i, p) in DISTANCE_20.iter().enumerate() {
+            for q in &DISTANCE_20[i + 1..] {
+                let (p, q) = (p.cube(), q.cube());
+                for s in 0..48 {
+                    let c = q.sym_conjugate(s);
+                    assert!(c != p && c != p.invert());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn positions_verify() {
+        let optimal = Optimal::new();
+        for p in DISTANCE_20.iter() {
+            assert_eq!(verify(&optimal, p, 9), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Keyframes for animating a scramble and its solution in a web viewer.
+//!
+//! Each move becomes a keyframe holding its start time, its duration and
+//! the stickers after it, so a viewer can tween between states without
+//! simulating the cube itself. The JSON looks like
+//!
+//! ```text
+//! {"start":"UUUUUUUUU...","frames":[
+//!   {"move":"R","phase":"scramble","time":0,"duration":200,
+//!    "facelets":"UUFUUFUUF..."}, ...]}
+//! ```
+use crate::alg::Alg;
+use crate::facelet::Facelets;
+use crate::notation::Move;
+use std::fmt::{self, Write};
+
+/// Which of the two algs a keyframe's move belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Scramble,
+    Solution,
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Scramble => "scramble",
+            Phase::Solution => "solution",
+        }
+    }
+}
+
+/// Move durations in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub quarter_turn: u32,
+    pub half_turn: u32,
+    /// Pause between the scramble and the solution.
+    pub pause: u32,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            quarter_turn: 200,
+            half_turn: 300,
+            pause: 1000,
+        }
+    }
+}
+
+impl Timing {
+    pub fn duration(&self, m: Move) -> u32 {
+        if m.power() == 2 {
+            self.half_turn
+        } else {
+            self.quarter_turn
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyframe {
+    pub mv: Move,
+    pub phase: Phase,
+    /// Start time in milliseconds.
+    pub time: u32,
+    pub duration: u32,
+    /// The stickers once the move is done.
+    pub facelets: Facelets,
+}
+
+/// The keyframes of a scramble followed by a solution, from solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    pub start: Facelets,
+    pub frames: Vec<Keyframe>,
+}
+
+impl Animation {
+    pub fn new(scramble: &Alg, solution: &Alg, timing: &Timing) -> Self {
+        let start = Facelets::solved();
+        let mut frames = Vec::with_capacity(scramble.len() + solution.len());
+        let (mut f, mut time) = (start, 0);
+        for (phase, alg) in
+            [(Phase::Scramble, scramble), (Phase::Solution, solution)]
+        {
+            if phase == Phase::Solution && !frames.is_empty() {
+                time += timing.pause;
+            }
+            for &mv in alg.moves() {
+                f = f.apply(mv.cube());
+                let duration = timing.duration(mv);
+                frames.push(Keyframe {
+                    mv,
+                    phase,
+                    time,
+                    duration,
+                    facelets: f,
+                });
+                time += duration;
+            }
+        }
+        Self { start, frames }
+    }
+
+    /// Milliseconds until the last move is done.
+    pub fn duration(&self) -> u32 {
+        self.frames.last().map_or(0, |k| k.time + k.duration)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        self.write_json(&mut s).expect("writing to a String");
+        s
+    }
+
+    fn write_json(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{{\"start\":\"{}\",\"frames\":[", self.start)?;
+        for (i, k) in self.frames.iter().enumerate() {
+            if i != 0 {
+                write!(s, ",")?;
+            }
+            write!(
+                s,
+                "{{\"move\":\"{}\",\"phase\":\"{}\",\"time\":{},\
+                 \"duration\":{},\"facelets\":\"{}\"}}",
+                k.mv,
+                k.phase.name(),
+                k.time,
+                k.duration,
+                k.facelets
+            )?;
+        }
+        write!(s, "]}}")
+    }
+}
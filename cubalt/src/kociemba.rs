@@ -0,0 +1,217 @@
+//! Move tables from Kociemba's twophase (Python) and ckociemba (C).
+//!
+//! Both write a move table as the raw array of entries: one 16-bit
+//! little-endian value per coordinate value and move, moves in the same
+//! U R F D L B order as `Move::ALL`. The coordinates are numbered
+//! differently from this crate's, so loading one renumbers it into the
+//! matching `MoveTable`; the piece orders and the orientation conventions
+//! are the same.
+//!
+//! Only the raw coordinates can be loaded. Kociemba's pruning tables and
+//! sorted-slice move tables are over coordinates this crate does not have.
+use crate::coord::{Coordinate, CornerOrient, CornerPerm, EdgeOrient};
+use crate::cube::Cube;
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::movetable::MoveTable;
+use crate::notation::Move;
+use crate::puzzle::Puzzle;
+use std::io::{self, Error, ErrorKind, Read};
+
+/// The move tables that can be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KociembaTable {
+    /// `move_twist`, or ckociemba's `twistMove`; loads as `CornerOrient`.
+    Twist,
+    /// `move_flip`, or ckociemba's `flipMove`; loads as `EdgeOrient`.
+    Flip,
+    /// `move_corners` (twophase only); loads as `CornerPerm`.
+    Corners,
+}
+
+impl KociembaTable {
+    /// Number of coordinate values.
+    pub fn size(self) -> usize {
+        match self {
+            KociembaTable::Twist => CornerOrient::SIZE,
+            KociembaTable::Flip => EdgeOrient::SIZE,
+            KociembaTable::Corners => CornerPerm::SIZE,
+        }
+    }
+
+    /// The file name twophase gives the table.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            KociembaTable::Twist => "move_twist",
+            KociembaTable::Flip => "move_flip",
+            KociembaTable::Corners => "move_corners",
+        }
+    }
+
+    /// Kociemba's coordinate of `c`.
+    pub fn rank(self, c: &Cube) -> usize {
+        match self {
+            KociembaTable::Twist => c.corners()[..7]
+                .iter()
+                .fold(0, |r, x| 3 * r + CORNERS.ori(x.0) as usize),
+            KociembaTable::Flip => c.edges()[..11]
+                .iter()
+                .fold(0, |r, x| 2 * r + EDGES.ori(x.0) as usize),
+            KociembaTable::Corners => {
+                let mut perm = [0; 8];
+                for (p, x) in perm.iter_mut().zip(c.corners()) {
+                    *p = CORNERS.piece(x.0);
+                }
+                // Rotate each piece home from the right, counting turns.
+                let mut r = 0;
+                for j in (1..8).rev() {
+                    let mut k = 0;
+                    while perm[j] as usize != j {
+                        perm[..=j].rotate_left(1);
+                        k += 1;
+                    }
+                    r = (j + 1) * r + k;
+                }
+                r
+            }
+        }
+    }
+
+    /// This crate's coordinate of `c`.
+    fn own_rank(self, c: &Cube) -> usize {
+        match self {
+            KociembaTable::Twist => CornerOrient::rank(c),
+            KociembaTable::Flip => EdgeOrient::rank(c),
+            KociembaTable::Corners => CornerPerm::rank(c),
+        }
+    }
+
+    fn own_unrank(self, i: usize) -> Cube {
+        match self {
+            KociembaTable::Twist => CornerOrient::unrank(i),
+            KociembaTable::Flip => EdgeOrient::unrank(i),
+            KociembaTable::Corners => CornerPerm::unrank(i),
+        }
+    }
+}
+
+/// Reads `table` and renumbers it into this crate's coordinate, with the
+/// moves of `Move::ALL`. The first rows are checked against the moves
+/// themselves, to catch files of another table or another layout.
+pub fn read_move_table<R: Read>(
+    mut r: R,
+    table: KociembaTable,
+) -> io::Result<MoveTable> {
+    let (size, moves) = (table.size(), Move::ALL.len());
+    let mut raw = vec![0u8; size * moves * 2];
+    r.read_exact(&mut raw)?;
+    let theirs: Vec<usize> = raw
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        .collect();
+    if theirs.iter().any(|&x| x >= size) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "move table entry out of range",
+        ));
+    }
+
+    // Kociemba's number of each of our values, and the reverse.
+    let to_theirs: Vec<usize> = (0..size)
+        .map(|i| table.rank(&table.own_unrank(i)))
+        .collect();
+    let mut to_ours = vec![0; size];
+    for (i, &t) in to_theirs.iter().enumerate() {
+        to_ours[t] = i;
+    }
+
+    let mut entries = Vec::with_capacity(size * moves);
+    for &t in to_theirs.iter() {
+        for m in 0..moves {
+            entries.push(to_ours[theirs[t * moves + m]] as u32);
+        }
+    }
+    let loaded = MoveTable::from_parts(moves, entries);
+    for i in 0..size.min(64) {
+        let c = table.own_unrank(i);
+        for (j, &m) in Move::ALL.iter().enumerate() {
+            if loaded.get(i, j) != table.own_rank(&c.apply(m)) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("not a twophase {} table", table.file_name()),
+                ));
+            }
+        }
+    }
+    Ok(loaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alg::Alg;
+    use crate::facelet::Facelets;
+
+    /// `table` as twophase writes it, from Kociemba's numbering.
+    fn their_file(table: KociembaTable) -> Vec<u8> {
+        let moves = Move::ALL.len();
+        let mut entries = vec![0u16; table.size() * moves];
+        for i in 0..table.size() {
+            let c = table.own_unrank(i);
+            let t = table.rank(&c);
+            for (j, &m) in Move::ALL.iter().enumerate() {
+                entries[t * moves + j] = table.rank(&c.apply(m)) as u16;
+            }
+        }
+        entries.iter().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn tables_load_as_ours() {
+        let cases = [
+            (
+                KociembaTable::Twist,
+                MoveTable::build::<Cube, CornerOrient>(),
+            ),
+            (KociembaTable::Flip, MoveTable::build::<Cube, EdgeOrient>()),
+            (
+                KociembaTable::Corners,
+                MoveTable::build::<Cube, CornerPerm>(),
+            ),
+        ];
+        for (table, ours) in cases.iter() {
+            let file = their_file(*table);
+            assert_eq!(&read_move_table(&file[..], *table).unwrap(), ours);
+        }
+        let zeros = vec![0; KociembaTable::Twist.size() * 36];
+        assert!(read_move_table(&zeros[..], KociembaTable::Twist).is_err());
+        let file = their_file(KociembaTable::Flip);
+        assert!(read_move_table(&file[1..], KociembaTable::Flip).is_err());
+    }
+
+    #[test]
+    fn face_turns_match_twophase() {
+        // twophase's `coR`, `coF`, `coL`, `coB` and `eoF`, `eoB`.
+        let twist = |m: Move| KociembaTable::Twist.rank(m.cube());
+        let flip = |m: Move| KociembaTable::Flip.rank(m.cube());
+        assert_eq!(twist(Move::R), 1494);
+        assert_eq!(twist(Move::F), 1236);
+        assert_eq!(twist(Move::L), 412);
+        assert_eq!(twist(Move::B), 137);
+        assert_eq!(flip(Move::F), 550);
+        assert_eq!(flip(Move::B), 137);
+        assert_eq!(KociembaTable::Corners.rank(&Cube::identity()), 0);
+    }
+
+    #[test]
+    fn cube_strings_round_trip() {
+        // The example in the README of the `kociemba` Python package.
+        let s = "DRLUUBFBRBLURRLRUBLRDDFDLFUFUFFDBRDUBRUFLLFDDBFLUBLRBD";
+        let solution: Alg =
+            "D2 R' D' F2 B D R2 D2 R' F2 D' F2 U' B2 L2 U2 D R2 U"
+                .parse()
+                .unwrap();
+        let cube = s.parse::<Facelets>().unwrap().to_cube().unwrap();
+        assert_eq!(Facelets::from_cube(&cube).to_string(), s);
+        assert_eq!(cube.compose(&solution.cube()), Cube::identity());
+    }
+}
@@ -0,0 +1,148 @@
+//! Piece layouts.
+//!
+//! A layout describes where one kind of piece lives inside a SIMD register
+//! and how its permutation and orientation are packed into a byte. The group
+//! operations in `avx2`/`sse` only ever see raw registers; everything that
+//! knows "edges are bytes 0..12 of the low lane" belongs here, so that other
+//! puzzle sizes can describe their own packing without touching them.
+
+/// Orientation arithmetic modulo `M`.
+///
+/// Edges use `Ori<2>`, corners `Ori<3>`. Pieces that cannot be twisted in
+/// place (centers, wings) use `Ori<1>`, for which everything is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Ori<const M: u8>(u8);
+
+impl<const M: u8> Ori<M> {
+    pub const MODULUS: u8 = M;
+
+    pub const fn new(v: u8) -> Self {
+        Self(v % M)
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    pub const fn add(self, other: Self) -> Self {
+        Self((self.0 + other.0) % M)
+    }
+
+    pub const fn sub(self, other: Self) -> Self {
+        Self((self.0 + M - other.0) % M)
+    }
+
+    pub const fn neg(self) -> Self {
+        Self((M - self.0) % M)
+    }
+}
+
+impl<const M: u8> core::ops::Add for Ori<M> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Ori::add(self, other)
+    }
+}
+
+impl<const M: u8> core::ops::Sub for Ori<M> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Ori::sub(self, other)
+    }
+}
+
+impl<const M: u8> core::ops::Neg for Ori<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Ori::neg(self)
+    }
+}
+
+/// Where a kind of piece lives in a 256-bit register.
+///
+/// Each piece takes one byte: the low bits are the piece index, the bits at
+/// `ori_shift` the orientation. Unused bytes of the lane hold their own
+/// index ("identity padding"), so that byte shuffles leave them alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PieceLayout {
+    /// Which 128-bit lane (0 = low, 1 = high).
+    pub lane: usize,
+    /// Number of pieces, starting at byte 0 of the lane.
+    pub count: usize,
+    /// Mask of the piece index bits.
+    pub perm_mask: u8,
+    /// Position of the lowest orientation bit.
+    pub ori_shift: u8,
+    /// Number of distinct orientations.
+    pub ori_modulus: u8,
+}
+
+impl PieceLayout {
+    /// Mask of the orientation bits.
+    pub const fn ori_mask(&self) -> u8 {
+        let bits = 8 - (self.ori_modulus - 1).leading_zeros() as u8;
+        (((1u16 << bits) - 1) as u8) << self.ori_shift
+    }
+
+    /// Byte offset of the first piece within the full register.
+    pub const fn offset(&self) -> usize {
+        self.lane * 16
+    }
+
+    pub const fn pack(&self, piece: u8, ori: u8) -> u8 {
+        (piece & self.perm_mask) | (ori << self.ori_shift)
+    }
+
+    pub const fn piece(&self, v: u8) -> u8 {
+        v & self.perm_mask
+    }
+
+    pub const fn ori(&self, v: u8) -> u8 {
+        (v & self.ori_mask()) >> self.ori_shift
+    }
+}
+
+/// The 3x3 packed layout used by `Cube`; see there for the bit diagram.
+pub mod packed3 {
+    use super::PieceLayout;
+
+    pub const EDGES: PieceLayout = PieceLayout {
+        lane: 0,
+        count: 12,
+        perm_mask: 0x0f,
+        ori_shift: 4,
+        ori_modulus: 2,
+    };
+
+    pub const CORNERS: PieceLayout = PieceLayout {
+        lane: 1,
+        count: 8,
+        perm_mask: 0x07,
+        ori_shift: 4,
+        ori_modulus: 3,
+    };
+
+    pub type EdgeOri = super::Ori<2>;
+    pub type CornerOri = super::Ori<3>;
+
+    /// Identity padding of the unused edge bytes 12..16.
+    pub const EDGE_PADDING: u64 = 0x0f0e0d0c00000000;
+
+    /// Identity padding of the unused corner bytes 8..16.
+    pub const CORNER_PADDING: u64 = 0x0f0e0d0c0b0a0908;
+
+    /// Pack the three literal words used by the generated tables into the
+    /// four little-endian words of the register.
+    pub const fn pack(
+        corners: u64,
+        edges_high: u64,
+        edges_low: u64,
+    ) -> [u64; 4] {
+        [
+            edges_low,
+            EDGE_PADDING | edges_high,
+            corners,
+            CORNER_PADDING,
+        ]
+    }
+}
@@ -10,45 +10,118 @@
     target_feature = "avx2",
     target_arch = "x86_64",
 ))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(dead_code)]
+extern crate alloc;
+
 pub mod types;
 #[macro_use]
 pub mod macros;
+pub mod alg;
+pub mod auf;
 pub mod avx2;
+pub mod axial;
+#[cfg(feature = "solver")]
+pub mod bandage;
+#[cfg(feature = "std")]
+pub mod bld;
+pub mod canonical;
+#[cfg(feature = "solver")]
+pub mod checkpoint;
+pub mod commutator;
+#[cfg(feature = "solver")]
+pub mod context;
+pub mod coord;
+#[cfg(feature = "solver")]
+pub mod cross;
 pub mod cube;
+pub mod cube4;
+pub mod cube5;
+#[cfg(feature = "std")]
+pub mod cubeexplorer;
+#[cfg(feature = "std")]
+pub mod cubemap;
+pub mod cycles;
+#[cfg(feature = "solver")]
+pub mod dataset;
+#[cfg(feature = "std")]
+pub mod diskset;
+pub mod diverse;
+#[cfg(feature = "solver")]
+pub mod domino;
+pub mod encode;
+pub mod ergonomics;
+pub mod f2l;
+pub mod facelet;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod fmc;
+#[cfg(feature = "solver")]
+pub mod hardest;
+#[cfg(feature = "render")]
+pub mod keyframes;
+#[cfg(feature = "solver")]
+pub mod kociemba;
+pub mod layout;
+pub mod memo;
+pub mod min2phase;
+#[cfg(feature = "solver")]
+pub mod mixing;
+pub mod named;
+#[cfg(feature = "tables")]
+pub mod movetable;
+pub mod notation;
+#[cfg(feature = "solver")]
+pub mod optimal;
+#[cfg(feature = "rayon")]
+pub mod par;
+pub mod pattern;
+#[cfg(feature = "solver")]
+pub mod petrus;
+pub mod pieces;
+pub mod pocket;
+pub mod prelude;
+#[cfg(feature = "tables")]
+pub mod prune;
+pub mod puzzle;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "std")]
+pub mod recon;
+#[cfg(feature = "solver")]
+pub mod reduce4;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod robot;
+pub mod rotation;
+#[cfg(feature = "std")]
+pub mod schreier;
+#[cfg(feature = "solver")]
+pub mod scramble;
+#[cfg(feature = "solver")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod smartcube;
+#[cfg(feature = "solver")]
+pub mod solver;
 pub mod sse;
-
-use cube::Cube;
-
-pub fn ppr(cube: &Cube) {
-    println!("{:?}", cube.0);
-    for edge in cube.edges() {
-        print!("{:?} | ", edge.0);
-    }
-    println!();
-    for corner in cube.corners() {
-        print!("{:?} | ", corner.0);
-    }
-    println!();
-}
-
-pub fn toplevel() {
-    println!("toplevel: hi");
-
-    let cube = Cube::identity();
-    ppr(&cube);
-
-    let cube_inv = !cube;
-    ppr(&cube_inv);
-
-    let mut c = cube;
-    ppr(&c);
-    c = c * Cube::M_U();
-    ppr(&c);
-    c = c * Cube::M_U();
-    ppr(&c);
-    c = c * Cube::M_U();
-    ppr(&c);
-    c = c * Cube::M_U();
-    ppr(&c);
-}
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod steps;
+#[cfg(feature = "std")]
+pub mod subgroup;
+#[cfg(feature = "solver")]
+pub mod symcoord;
+#[cfg(feature = "solver")]
+pub mod tablecache;
+#[cfg(feature = "solver")]
+pub mod trainer;
+#[cfg(feature = "std")]
+pub mod twogen;
+#[cfg(feature = "solver")]
+pub mod void;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -312,6 +312,24 @@ macro_rules! constify_imm2 {
     };
 }
 
+/// Enter a debug-level span for the rest of the enclosing block, when the
+/// `tracing` feature is on; otherwise nothing.
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+/// A debug-level event, when the `tracing` feature is on; otherwise
+/// nothing.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)*);
+    };
+}
+
 #[cfg(test)]
 macro_rules! assert_approx_eq {
     ($a:expr, $b:expr, $eps:expr) => {{
@@ -0,0 +1,457 @@
+//! Blindfold memo, in Speffz or another lettering.
+//!
+//! Speffz letters the stickers of each face A to X, face by face in the
+//! order U L F R B D, clockwise from the top left of each (U with B at the
+//! top, D with F at the top, the others with U at the top). Edges and
+//! corners are lettered separately, so every letter names one edge sticker
+//! and one corner sticker. A `Scheme` gives any other letters, in the same
+//! reading order, and the buffers to trace from.
+//!
+//! The memo traces each kind of piece from a buffer: the sticker in the
+//! buffer's reference position is the first target, the sticker that was
+//! in that target's place is the next, and so on back to the buffer. When
+//! the buffer comes home with pieces left, a cycle break targets the first
+//! of them and the cycle is traced from there. Pieces home but flipped or
+//! twisted are listed apart.
+use crate::cube::Cube;
+use crate::cycles::{find_letters, CORNER_NAMES, EDGE_NAMES};
+use crate::layout::packed3::{CORNERS, EDGES};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Speffz letters of each edge slot's stickers, in `Cube` order, reference
+/// sticker first.
+pub const EDGE_LETTERS: [[char; 2]; 12] = [
+    ['B', 'M'],
+    ['C', 'I'],
+    ['D', 'E'],
+    ['A', 'Q'],
+    ['V', 'O'],
+    ['U', 'K'],
+    ['X', 'G'],
+    ['W', 'S'],
+    ['J', 'P'],
+    ['L', 'F'],
+    ['R', 'H'],
+    ['T', 'N'],
+];
+
+/// Speffz letters of each corner slot's stickers, in `Cube` order,
+/// reference (U or D) sticker first, then clockwise.
+pub const CORNER_LETTERS: [[char; 3]; 8] = [
+    ['C', 'M', 'J'],
+    ['D', 'I', 'F'],
+    ['A', 'E', 'R'],
+    ['B', 'Q', 'N'],
+    ['V', 'K', 'P'],
+    ['U', 'G', 'L'],
+    ['X', 'S', 'H'],
+    ['W', 'O', 'T'],
+];
+
+/// Slot of the UF edge, the usual edge buffer.
+pub const UF: usize = 1;
+/// Slot of the URF corner, the usual corner buffer.
+pub const URF: usize = 0;
+
+/// A lettering and the buffers to trace from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheme {
+    pub edges: [[char; 2]; 12],
+    pub corners: [[char; 3]; 8],
+    /// Slot of the edge buffer.
+    pub edge_buffer: usize,
+    /// Slot of the corner buffer.
+    pub corner_buffer: usize,
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Self::speffz()
+    }
+}
+
+/// A lettering that does not name every sticker once, or a buffer that is
+/// not a piece.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSchemeError(pub String);
+
+impl fmt::Display for ParseSchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bad lettering scheme: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSchemeError {}
+
+impl Scheme {
+    /// Speffz, from the UF and URF buffers.
+    pub fn speffz() -> Self {
+        Self {
+            edges: EDGE_LETTERS,
+            corners: CORNER_LETTERS,
+            edge_buffer: UF,
+            corner_buffer: URF,
+        }
+    }
+
+    /// The letters of `edges` and `corners`, 24 each, for the stickers in
+    /// Speffz's reading order; whitespace is ignored. `ABCD EFGH ...` is
+    /// Speffz itself. The buffers stay UF and URF.
+    pub fn from_stickers(
+        edges: &str,
+        corners: &str,
+    ) -> Result<Self, ParseSchemeError> {
+        fn letters(
+            s: &str,
+            kind: &str,
+        ) -> Result<[char; 24], ParseSchemeError> {
+            let chars: Vec<char> =
+                s.chars().filter(|c| !c.is_whitespace()).collect();
+            let err = |msg: &str| {
+                ParseSchemeError(alloc::format!("{} {}", kind, msg))
+            };
+            if chars.len() != 24 {
+                return Err(err("need 24 letters"));
+            }
+            for (i, c) in chars.iter().enumerate() {
+                if chars[..i].contains(c) {
+                    return Err(err(&alloc::format!("repeat {:?}", c)));
+                }
+            }
+            Ok(core::array::from_fn(|i| chars[i]))
+        }
+        let (e, c) = (letters(edges, "edges")?, letters(corners, "corners")?);
+        let speffz = |x: char| (x as u8 - b'A') as usize;
+        Ok(Self {
+            edges: EDGE_LETTERS.map(|s| s.map(|x| e[speffz(x)])),
+            corners: CORNER_LETTERS.map(|s| s.map(|x| c[speffz(x)])),
+            ..Self::speffz()
+        })
+    }
+
+    /// This scheme traced from other buffers, named by their faces in any
+    /// order: `"DF"`, `"UBL"`.
+    pub fn with_buffers(
+        self,
+        edge: &str,
+        corner: &str,
+    ) -> Result<Self, ParseSchemeError> {
+        let err = |name: &str| {
+            ParseSchemeError(alloc::format!("no piece {:?}", name))
+        };
+        Ok(Self {
+            edge_buffer: slot(edge, &EDGE_NAMES).ok_or_else(|| err(edge))?,
+            corner_buffer: slot(corner, &CORNER_NAMES)
+                .ok_or_else(|| err(corner))?,
+            ..self
+        })
+    }
+}
+
+/// The slot named `name`, with its letters in any order.
+fn slot<const N: usize>(name: &str, names: &[[u8; N]]) -> Option<usize> {
+    let name = name.as_bytes();
+    names
+        .iter()
+        .position(|n| find_letters(name, 0, name.len(), n).is_some())
+}
+
+/// One letter of the memo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub letter: char,
+    /// Starts a new cycle, on a piece that is not the buffer's.
+    pub cycle_break: bool,
+}
+
+/// The memo of a state, for a scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memo {
+    pub edges: Vec<Target>,
+    pub corners: Vec<Target>,
+    /// Edges home but flipped, by the letter of the sticker on their
+    /// reference face.
+    pub flipped: Vec<char>,
+    /// Corners home but twisted, by the letter of the sticker on their U
+    /// or D face.
+    pub twisted: Vec<char>,
+    /// An odd number of targets of each kind: the last edge and corner
+    /// are left unpaired.
+    pub parity: bool,
+}
+
+impl Memo {
+    /// The memo of `cube` in Speffz from the UF and URF buffers.
+    pub fn new(cube: &Cube) -> Self {
+        Self::with_scheme(cube, &Scheme::speffz())
+    }
+
+    /// The memo of `cube` from the edge and corner slots given.
+    pub fn with_buffers(cube: &Cube, edge: usize, corner: usize) -> Self {
+        let scheme = Scheme {
+            edge_buffer: edge,
+            corner_buffer: corner,
+            ..Scheme::speffz()
+        };
+        Self::with_scheme(cube, &scheme)
+    }
+
+    pub fn with_scheme(cube: &Cube, scheme: &Scheme) -> Self {
+        let (edges, flipped) = trace(
+            cube.edges().iter().map(|e| e.0).collect(),
+            scheme.edge_buffer,
+            |piece, ori| EDGES.pack(piece, ori),
+            |v| (EDGES.piece(v), EDGES.ori(v)),
+            2,
+            &scheme.edges,
+        );
+        let (corners, twisted) = trace(
+            cube.corners().iter().map(|c| c.0).collect(),
+            scheme.corner_buffer,
+            |piece, ori| CORNERS.pack(piece, ori),
+            |v| (CORNERS.piece(v), CORNERS.ori(v)),
+            3,
+            &scheme.corners,
+        );
+        let parity = edges.len() % 2 == 1;
+        Self {
+            edges,
+            corners,
+            flipped,
+            twisted,
+            parity,
+        }
+    }
+
+    /// The targets in pairs, edges then corners, and the pieces to orient:
+    /// `CF UN JB / BW LE P / flip: T / twist: X`.
+    pub fn letter_pairs(&self) -> String {
+        let pairs = |targets: &[Target]| {
+            targets
+                .chunks(2)
+                .map(|p| p.iter().map(|t| t.letter).collect::<String>())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut s =
+            alloc::format!("{} / {}", pairs(&self.edges), pairs(&self.corners));
+        for (name, letters) in
+            [("flip", &self.flipped), ("twist", &self.twisted)]
+        {
+            if !letters.is_empty() {
+                let letters: String = letters.iter().collect();
+                s.push_str(&alloc::format!(" / {}: {}", name, letters));
+            }
+        }
+        s
+    }
+}
+
+impl fmt::Display for Memo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.letter_pairs())?;
+        if self.parity {
+            write!(f, " (parity)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The targets of one kind of piece, and the pieces left misoriented at
+/// home. `pieces` holds the packed values of the slots; the trace swaps
+/// each target with the buffer in it as it goes.
+fn trace<const N: usize>(
+    mut pieces: Vec<u8>,
+    buffer: usize,
+    pack: impl Fn(u8, u8) -> u8,
+    unpack: impl Fn(u8) -> (u8, u8),
+    oris: u8,
+    letters: &[[char; N]],
+) -> (Vec<Target>, Vec<char>) {
+    let mut targets = Vec::new();
+    loop {
+        let (piece, ori) = unpack(pieces[buffer]);
+        let (slot, ori, cycle_break) = if piece as usize == buffer {
+            let next = (0..pieces.len())
+                .find(|&i| i != buffer && unpack(pieces[i]).0 as usize != i);
+            match next {
+                Some(i) => (i, 0, true),
+                None => break,
+            }
+        } else {
+            (piece as usize, ori, false)
+        };
+        targets.push(Target {
+            letter: letters[slot][((oris - ori) % oris) as usize],
+            cycle_break,
+        });
+        // Swap the buffer's piece into `slot`, sticker to sticker.
+        let (held, held_ori) = unpack(pieces[buffer]);
+        let (there, there_ori) = unpack(pieces[slot]);
+        let back = (oris - ori) % oris;
+        pieces[slot] = pack(held, (held_ori + back) % oris);
+        pieces[buffer] = pack(there, (there_ori + ori) % oris);
+    }
+    let misoriented = (0..pieces.len())
+        .filter(|&i| i != buffer)
+        .filter_map(|i| match unpack(pieces[i]).1 {
+            0 => None,
+            ori => Some(letters[i][(oris - ori) as usize]),
+        })
+        .collect();
+    (targets, misoriented)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alg::Alg;
+    use crate::cube::{Corner, Edge};
+    use alloc::string::ToString;
+
+    /// `cube` after swapping each target of `memo` with the buffer, the
+    /// way a solve shoots them, checking the cycle breaks on the way.
+    fn replay(cube: &Cube, memo: &Memo, scheme: &Scheme) -> Cube {
+        let mut cube = *cube;
+        let find = |letters: &[[char; 3]], c: char| {
+            letters.iter().enumerate().find_map(|(slot, l)| {
+                l.iter().position(|&x| x == c).map(|k| (slot, k as u8))
+            })
+        };
+        let edges: Vec<[char; 3]> =
+            scheme.edges.iter().map(|e| [e[0], e[1], e[1]]).collect();
+        let buffer = scheme.edge_buffer;
+        for t in memo.edges.iter() {
+            let home = EDGES.piece(cube.edges()[buffer].0) as usize == buffer;
+            assert_eq!(t.cycle_break, home);
+            let (slot, ori) = find(&edges, t.letter).unwrap();
+            let mut swap = Cube::identity();
+            swap.edges_mut()[slot] = Edge(EDGES.pack(buffer as u8, ori));
+            swap.edges_mut()[buffer] = Edge(EDGES.pack(slot as u8, ori));
+            cube = cube.compose(&swap);
+        }
+        let buffer = scheme.corner_buffer;
+        for t in memo.corners.iter() {
+            let v = cube.corners()[buffer].0;
+            assert_eq!(t.cycle_break, CORNERS.piece(v) as usize == buffer);
+            let (slot, k) = find(&scheme.corners, t.letter).unwrap();
+            let mut swap = Cube::identity();
+            swap.corners_mut()[slot] = Corner(CORNERS.pack(buffer as u8, k));
+            swap.corners_mut()[buffer] =
+                Corner(CORNERS.pack(slot as u8, (3 - k) % 3));
+            cube = cube.compose(&swap);
+        }
+        cube
+    }
+
+    /// Checks that replaying `memo` brings every piece home, oriented
+    /// unless listed as flipped or twisted.
+    fn check(cube: &Cube, scheme: &Scheme) {
+        let memo = Memo::with_scheme(cube, scheme);
+        let cube = replay(cube, &memo, scheme);
+        let mut flipped = Vec::new();
+        for (i, e) in cube.edges().iter().enumerate() {
+            assert_eq!(EDGES.piece(e.0) as usize, i, "{}", memo);
+            if i != scheme.edge_buffer && EDGES.ori(e.0) != 0 {
+                flipped.push(scheme.edges[i][1]);
+            }
+        }
+        let mut twisted = Vec::new();
+        for (i, c) in cube.corners().iter().enumerate() {
+            assert_eq!(CORNERS.piece(c.0) as usize, i, "{}", memo);
+            let ori = CORNERS.ori(c.0) as usize;
+            if i != scheme.corner_buffer && ori != 0 {
+                twisted.push(scheme.corners[i][3 - ori]);
+            }
+        }
+        assert_eq!(memo.flipped, flipped);
+        assert_eq!(memo.twisted, twisted);
+        assert_eq!(memo.parity, memo.edges.len() % 2 == 1);
+        assert_eq!(memo.edges.len() % 2, memo.corners.len() % 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn memos_replay_to_solved() {
+        use crate::random::{random_cube, Rng};
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let cube = random_cube(&mut rng);
+            check(&cube, &Scheme::speffz());
+            let scheme = Scheme {
+                edge_buffer: rng.below(12) as usize,
+                corner_buffer: rng.below(8) as usize,
+                ..Scheme::speffz()
+            };
+            check(&cube, &scheme);
+        }
+    }
+
+    #[test]
+    fn solved_has_no_targets() {
+        let memo = Memo::new(&Cube::identity());
+        assert!(memo.edges.is_empty() && memo.corners.is_empty());
+        assert!(memo.flipped.is_empty() && memo.twisted.is_empty());
+        assert!(!memo.parity);
+    }
+
+    #[test]
+    fn t_perm() {
+        let alg: Alg = "R U R' U' R' F R2 U' R' U' R U R' F'".parse().unwrap();
+        let memo = Memo::new(&alg.cube());
+        check(&alg.cube(), &Scheme::speffz());
+        assert_eq!(memo.to_string(), "BD B / B (parity)");
+        assert!(memo.edges[0].cycle_break);
+    }
+
+    #[test]
+    fn flips_and_twists_in_place() {
+        let mut cube = Cube::identity();
+        for slot in [0, 2] {
+            cube.edges_mut()[slot] = Edge(EDGES.pack(slot as u8, 1));
+        }
+        cube.corners_mut()[3] = Corner(CORNERS.pack(3, 1));
+        cube.corners_mut()[5] = Corner(CORNERS.pack(5, 2));
+        check(&cube, &Scheme::speffz());
+        let memo = Memo::new(&cube);
+        assert!(memo.edges.is_empty() && memo.corners.is_empty());
+        assert_eq!(memo.to_string(), " /  / flip: ME / twist: NG");
+    }
+
+    #[test]
+    fn schemes_from_stickers() {
+        let speffz = "ABCD EFGH IJKL MNOP QRST UVWX";
+        assert_eq!(Scheme::from_stickers(speffz, speffz), Ok(Scheme::speffz()));
+        let lower = speffz.to_lowercase();
+        let scheme = Scheme::from_stickers(&lower, &lower).unwrap();
+        let alg: Alg = "R U R' U' R' F R2 U' R' U' R U R' F'".parse().unwrap();
+        check(&alg.cube(), &scheme);
+        let memo = Memo::with_scheme(&alg.cube(), &scheme);
+        assert_eq!(memo.to_string(), "bd b / b (parity)");
+        assert!(Scheme::from_stickers(&speffz[1..], speffz).is_err());
+        let repeat = speffz.replace('B', "A");
+        assert!(Scheme::from_stickers(speffz, &repeat).is_err());
+    }
+
+    #[test]
+    fn schemes_with_buffers() {
+        let scheme = Scheme::speffz().with_buffers("DF", "UBL").unwrap();
+        assert_eq!((scheme.edge_buffer, scheme.corner_buffer), (5, 2));
+        let scheme = Scheme::speffz().with_buffers("FD", "LUB").unwrap();
+        assert_eq!((scheme.edge_buffer, scheme.corner_buffer), (5, 2));
+        assert!(Scheme::speffz().with_buffers("UX", "UBL").is_err());
+        assert!(Scheme::speffz().with_buffers("DF", "UB").is_err());
+        let alg: Alg = "R U R' U R U2 R'".parse().unwrap();
+        for buffer in 0..12 {
+            let scheme = Scheme {
+                edge_buffer: buffer,
+                corner_buffer: buffer % 8,
+                ..Scheme::speffz()
+            };
+            check(&alg.cube(), &scheme);
+        }
+    }
+}
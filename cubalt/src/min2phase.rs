@@ -0,0 +1,315 @@
+//! Conversions to and from the conventions of min2phase.
+//!
+//! min2phase keeps a state as two arrays, `ca` with `ori << 3 | piece` per
+//! corner and `ea` with `piece << 1 | ori` per edge, in the same piece
+//! orders as `Cube`. Its permutation coordinates are numbered as this
+//! crate's are, but twist, flip and slice are not. Its 16 symmetries, those
+//! keeping the U-D axis, are indexed as `F2^(i >> 3) LR2^(i >> 2 & 1)
+//! U4^(i & 3)` and conjugate as `S^-1 * c * S`.
+//!
+//! A sym-coordinate of min2phase, `class << 4 | sym`, numbers its classes
+//! its own way; the symmetry converts with `sym_to_ours`, and the raw value
+//! of a class's representative with `raw_to_ours`.
+use crate::coord::{
+    Coordinate, CornerOrient, CornerPerm, EdgeOrient, SlicePerm, UDEdgePerm,
+    UDSlice,
+};
+use crate::cube::{Corner, Cube, Edge};
+use crate::layout::packed3::{CORNERS, EDGES};
+
+/// min2phase's raw coordinates, each the counterpart of one of this
+/// crate's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Min2phaseCoord {
+    /// `getTwist`; `CornerOrient`.
+    Twist,
+    /// `getFlip`; `EdgeOrient`.
+    Flip,
+    /// `getUDSlice`; `UDSlice`.
+    UDSlice,
+    /// `getCPerm`; `CornerPerm`.
+    CPerm,
+    /// `getEPerm`, phase 2 only; `UDEdgePerm`.
+    EPerm,
+    /// `getMPerm`, phase 2 only; `SlicePerm`.
+    MPerm,
+}
+
+const fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    let mut r = 1;
+    let mut i = 0;
+    while i < k {
+        r = r * (n - i) / (i + 1);
+        i += 1;
+    }
+    r
+}
+
+/// Lexicographic rank of the pieces of `p`, as min2phase's `getNPerm`.
+fn rank_perm(p: &[u8]) -> usize {
+    let mut r = 0;
+    for i in 0..p.len() {
+        let smaller = p[i + 1..].iter().filter(|&&x| x < p[i]).count();
+        r = r * (p.len() - i) + smaller;
+    }
+    r
+}
+
+/// Inverse of `rank_perm`, on `p.len()` pieces numbered from 0.
+fn unrank_perm(mut r: usize, p: &mut [u8]) {
+    let n = p.len();
+    let mut digits = [0; 12];
+    for i in (0..n).rev() {
+        digits[i] = r % (n - i);
+        r /= n - i;
+    }
+    let mut left = 0u16;
+    for i in 0..n {
+        let k = (0..n as u8)
+            .filter(|&x| left & 1 << x == 0)
+            .nth(digits[i])
+            .expect("digit in range");
+        left |= 1 << k;
+        p[i] = k;
+    }
+}
+
+impl Min2phaseCoord {
+    pub const ALL: [Min2phaseCoord; 6] = [
+        Min2phaseCoord::Twist,
+        Min2phaseCoord::Flip,
+        Min2phaseCoord::UDSlice,
+        Min2phaseCoord::CPerm,
+        Min2phaseCoord::EPerm,
+        Min2phaseCoord::MPerm,
+    ];
+
+    /// Number of values, the same in both numberings.
+    pub fn size(self) -> usize {
+        match self {
+            Min2phaseCoord::Twist => CornerOrient::SIZE,
+            Min2phaseCoord::Flip => EdgeOrient::SIZE,
+            Min2phaseCoord::UDSlice => UDSlice::SIZE,
+            Min2phaseCoord::CPerm => CornerPerm::SIZE,
+            Min2phaseCoord::EPerm => UDEdgePerm::SIZE,
+            Min2phaseCoord::MPerm => SlicePerm::SIZE,
+        }
+    }
+
+    /// min2phase's value of the coordinate for `c`.
+    pub fn rank(self, c: &Cube) -> usize {
+        let (ca, ea) = to_arrays(c);
+        match self {
+            Min2phaseCoord::Twist => {
+                ca[..7].iter().fold(0, |r, &x| 3 * r + (x >> 3) as usize)
+            }
+            Min2phaseCoord::Flip => {
+                ea[..11].iter().fold(0, |r, &x| 2 * r + (x & 1) as usize)
+            }
+            Min2phaseCoord::UDSlice => {
+                let (mut r, mut left) = (0, 4);
+                for i in (0..12).rev() {
+                    if ea[i] >> 1 >= 8 {
+                        r += choose(i, left);
+                        left -= 1;
+                    }
+                }
+                choose(12, 4) - 1 - r
+            }
+            Min2phaseCoord::CPerm => rank_perm(&ca.map(|x| x & 7)),
+            Min2phaseCoord::EPerm => rank_perm(&ea.map(|x| x >> 1)[..8]),
+            Min2phaseCoord::MPerm => rank_perm(&ea.map(|x| x >> 1)) % 24,
+        }
+    }
+
+    /// A state with min2phase's value `i`, as its `set` methods make.
+    pub fn unrank(self, mut i: usize) -> Cube {
+        let mut ca = [0, 1, 2, 3, 4, 5, 6, 7];
+        let mut ea = [0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22];
+        match self {
+            Min2phaseCoord::Twist => {
+                let mut twist = 0;
+                for x in ca[..7].iter_mut().rev() {
+                    *x |= ((i % 3) as u8) << 3;
+                    twist += i % 3;
+                    i /= 3;
+                }
+                ca[7] |= (((3 - twist % 3) % 3) as u8) << 3;
+            }
+            Min2phaseCoord::Flip => {
+                let mut flip = 0;
+                for x in ea[..11].iter_mut().rev() {
+                    *x |= (i & 1) as u8;
+                    flip ^= i & 1;
+                    i >>= 1;
+                }
+                ea[11] |= flip as u8;
+            }
+            Min2phaseCoord::UDSlice => {
+                let mut r = choose(12, 4) - 1 - i;
+                let (mut left, mut other) = (4, 8);
+                for x in ea.iter_mut().rev() {
+                    let pos = (*x >> 1) as usize;
+                    if left > 0 && r >= choose(pos, left) {
+                        r -= choose(pos, left);
+                        left -= 1;
+                        *x = (8 + left as u8) << 1;
+                    } else {
+                        other -= 1;
+                        *x = (other as u8) << 1;
+                    }
+                }
+            }
+            Min2phaseCoord::CPerm => unrank_perm(i, &mut ca),
+            Min2phaseCoord::EPerm => {
+                unrank_perm(i, &mut ea[..8]);
+                ea[..8].iter_mut().for_each(|x| *x <<= 1);
+            }
+            Min2phaseCoord::MPerm => {
+                unrank_perm(i, &mut ea[8..]);
+                ea[8..].iter_mut().for_each(|x| *x = (*x + 8) << 1);
+            }
+        }
+        from_arrays(&ca, &ea).expect("a legal state")
+    }
+
+    /// This crate's value for min2phase's value `i`.
+    pub fn raw_to_ours(self, i: usize) -> usize {
+        self.rank_ours(&self.unrank(i))
+    }
+
+    /// min2phase's value for this crate's value `i`.
+    pub fn raw_from_ours(self, i: usize) -> usize {
+        self.rank(&self.unrank_ours(i))
+    }
+
+    fn rank_ours(self, c: &Cube) -> usize {
+        match self {
+            Min2phaseCoord::Twist => CornerOrient::rank(c),
+            Min2phaseCoord::Flip => EdgeOrient::rank(c),
+            Min2phaseCoord::UDSlice => UDSlice::rank(c),
+            Min2phaseCoord::CPerm => CornerPerm::rank(c),
+            Min2phaseCoord::EPerm => UDEdgePerm::rank(c),
+            Min2phaseCoord::MPerm => SlicePerm::rank(c),
+        }
+    }
+
+    fn unrank_ours(self, i: usize) -> Cube {
+        match self {
+            Min2phaseCoord::Twist => CornerOrient::unrank(i),
+            Min2phaseCoord::Flip => EdgeOrient::unrank(i),
+            Min2phaseCoord::UDSlice => UDSlice::unrank(i),
+            Min2phaseCoord::CPerm => CornerPerm::unrank(i),
+            Min2phaseCoord::EPerm => UDEdgePerm::unrank(i),
+            Min2phaseCoord::MPerm => SlicePerm::unrank(i),
+        }
+    }
+}
+
+/// min2phase's `ca` and `ea` arrays for `c`.
+pub fn to_arrays(c: &Cube) -> ([u8; 8], [u8; 12]) {
+    let (mut ca, mut ea) = ([0; 8], [0; 12]);
+    for (x, y) in ca.iter_mut().zip(c.corners()) {
+        *x = CORNERS.ori(y.0) << 3 | CORNERS.piece(y.0);
+    }
+    for (x, y) in ea.iter_mut().zip(c.edges()) {
+        *x = EDGES.piece(y.0) << 1 | EDGES.ori(y.0);
+    }
+    (ca, ea)
+}
+
+/// The state with arrays `ca` and `ea`, or `None` if they do not hold a
+/// permutation of the pieces with unreflected orientations.
+pub fn from_arrays(ca: &[u8; 8], ea: &[u8; 12]) -> Option<Cube> {
+    let (mut corners, mut edges) = (0u8, 0u16);
+    let mut c = Cube::identity();
+    for (x, &y) in c.corners_mut().iter_mut().zip(ca) {
+        let (piece, ori) = (y & 7, y >> 3);
+        if ori > 2 || corners & 1 << piece != 0 {
+            return None;
+        }
+        corners |= 1 << piece;
+        *x = Corner(CORNERS.pack(piece, ori));
+    }
+    for (x, &y) in c.edges_mut().iter_mut().zip(ea) {
+        let piece = y >> 1;
+        if piece >= 12 || edges & 1 << piece != 0 {
+            return None;
+        }
+        edges |= 1 << piece;
+        *x = Edge(EDGES.pack(piece, y & 1));
+    }
+    Some(c)
+}
+
+/// This crate's index of min2phase's symmetry `i`, for `i < 16`:
+/// conjugating by `i` in min2phase is `Cube::sym_conjugate` by this.
+pub fn sym_to_ours(i: usize) -> usize {
+    SYM_TO_OURS[i] as usize
+}
+
+/// min2phase's index of this crate's symmetry `s`, if it keeps the U-D
+/// axis.
+pub fn sym_from_ours(s: usize) -> Option<usize> {
+    SYM_TO_OURS.iter().position(|&x| x as usize == s)
+}
+
+/// Found by conjugating a state with no symmetry both ways.
+const SYM_TO_OURS: [u8; 16] =
+    [0, 12, 8, 4, 1, 13, 9, 5, 2, 14, 10, 6, 3, 15, 11, 7];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Move;
+
+    /// min2phase's `CubieCube.moveCube` for U R F D L B: its corner
+    /// permutation, twist, permutation of all twelve edges, and flip.
+    const MOVE_CUBES: [(usize, usize, usize, usize); 6] = [
+        (15120, 0, 119750400, 0),
+        (21021, 1494, 323403417, 0),
+        (8064, 1236, 29441808, 550),
+        (9, 0, 5880, 0),
+        (1230, 412, 2949660, 0),
+        (224, 137, 328552, 137),
+    ];
+
+    #[test]
+    fn face_turns_match_min2phase() {
+        for (&m, &(cperm, twist, eperm, flip)) in
+            [Move::U, Move::R, Move::F, Move::D, Move::L, Move::B]
+                .iter()
+                .zip(MOVE_CUBES.iter())
+        {
+            let c = m.cube();
+            let (ca, ea) = to_arrays(c);
+            assert_eq!(from_arrays(&ca, &ea).as_ref(), Some(c), "{}", m);
+            assert_eq!(Min2phaseCoord::CPerm.rank(c), cperm, "{}", m);
+            assert_eq!(Min2phaseCoord::Twist.rank(c), twist, "{}", m);
+            assert_eq!(rank_perm(&ea.map(|x| x >> 1)), eperm, "{}", m);
+            assert_eq!(Min2phaseCoord::Flip.rank(c), flip, "{}", m);
+        }
+    }
+
+    #[test]
+    fn coordinates_round_trip() {
+        for &coord in Min2phaseCoord::ALL.iter() {
+            for i in 0..coord.size() {
+                assert_eq!(coord.rank(&coord.unrank(i)), i, "{:?}", coord);
+                let ours = coord.raw_to_ours(i);
+                assert_eq!(coord.raw_from_ours(ours), i, "{:?}", coord);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetries_round_trip() {
+        for i in 0..16 {
+            assert_eq!(sym_from_ours(sym_to_ours(i)), Some(i));
+        }
+        assert_eq!(sym_from_ours(16), None);
+    }
+}
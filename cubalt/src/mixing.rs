@@ -0,0 +1,103 @@
+//! Random-walk mixing: how far from solved, and how close to uniform, a
+//! state is after k random moves.
+use crate::canonical::is_canonical;
+use crate::coord::Coordinate;
+use crate::puzzle::Puzzle;
+use crate::random::Rng;
+use crate::stats::Histogram;
+
+/// A random walk of `len` moves. With `canonical`, each move is drawn
+/// uniformly from those forming a canonical sequence with the previous
+/// one (no repeated layer, commuting turns in order), as in scrambles.
+pub fn random_walk<P: Puzzle>(
+    rng: &mut Rng,
+    len: usize,
+    canonical: bool,
+) -> Vec<P::Move> {
+    let mut walk: Vec<P::Move> = Vec::with_capacity(len);
+    while walk.len() < len {
+        let m = *rng.choose(P::moves());
+        if !canonical || is_canonical::<P>(walk.last().copied(), m) {
+            walk.push(m);
+        }
+    }
+    walk
+}
+
+/// Distribution of `distance` over `samples` endpoints of random walks of
+/// length `len` from solved. `distance` may be an exact solver or any
+/// estimate (a heuristic gives a lower bound on the true distribution).
+pub fn walk_distances<P, F>(
+    rng: &mut Rng,
+    len: usize,
+    samples: usize,
+    canonical: bool,
+    mut distance: F,
+) -> Histogram
+where
+    P: Puzzle,
+    F: FnMut(&P) -> u32,
+{
+    let mut h = Histogram::new();
+    for _ in 0..samples {
+        let walk = random_walk::<P>(rng, len, canonical);
+        let p = walk.iter().fold(P::identity(), |p, &m| p.apply(m));
+        h.add(distance(&p) as usize);
+    }
+    h
+}
+
+/// Exact total variation distance between the distribution of coordinate
+/// `C` after k uniform (unrestricted) random moves and the uniform
+/// distribution on the values reachable from solved, for k in
+/// 0..=max_len.
+pub fn coordinate_mixing<P: Puzzle, C: Coordinate<P>>(
+    max_len: usize,
+) -> Vec<f64> {
+    let moves = P::moves();
+    let mut reachable = vec![false; C::SIZE];
+    let mut transitions = vec![0u32; C::SIZE * moves.len()];
+    let start = C::rank(&P::identity());
+    reachable[start] = true;
+    let mut frontier = vec![start];
+    while let Some(c) = frontier.pop() {
+        let p = C::unrank(c);
+        for (i, &m) in moves.iter().enumerate() {
+            let d = C::rank(&p.apply(m));
+            transitions[c * moves.len() + i] = d as u32;
+            if !reachable[d] {
+                reachable[d] = true;
+                frontier.push(d);
+            }
+        }
+    }
+    let uniform = 1.0 / reachable.iter().filter(|&&r| r).count() as f64;
+    let step = 1.0 / moves.len() as f64;
+
+    let mut dist = vec![0.0; C::SIZE];
+    dist[start] = 1.0;
+    let mut result = Vec::with_capacity(max_len + 1);
+    for k in 0..=max_len {
+        let tv: f64 = dist
+            .iter()
+            .zip(reachable.iter())
+            .filter(|(_, &r)| r)
+            .map(|(&p, _)| (p - uniform).abs())
+            .sum();
+        result.push(tv / 2.0);
+        if k == max_len {
+            break;
+        }
+        let mut next = vec![0.0; C::SIZE];
+        for (c, &p) in dist.iter().enumerate() {
+            if p == 0.0 {
+                continue;
+            }
+            for i in 0..moves.len() {
+                next[transitions[c * moves.len() + i] as usize] += p * step;
+            }
+        }
+        dist = next;
+    }
+    result
+}
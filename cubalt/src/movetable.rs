@@ -0,0 +1,184 @@
+//! Move tables: a coordinate's transitions under each move.
+use crate::coord::Coordinate;
+use crate::puzzle::Puzzle;
+use alloc::vec::Vec;
+
+/// The coordinate reached from each value of a coordinate by each move,
+/// so that searches can stay in coordinate space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct MoveTable {
+    moves: usize,
+    /// Value * moves + move -> value after the move.
+    table: Vec<u32>,
+}
+
+impl MoveTable {
+    /// Transitions of coordinate `C` under all of `P::moves()`.
+    pub fn build<P: Puzzle, C: Coordinate<P>>() -> Self {
+        Self::build_with_moves::<P, C>(P::moves())
+    }
+
+    /// Transitions of coordinate `C` under `moves`, indexed in that order.
+    pub fn build_with_moves<P: Puzzle, C: Coordinate<P>>(
+        moves: &[P::Move],
+    ) -> Self {
+        trace_span!(
+            "move_table",
+            coord = core::any::type_name::<C>(),
+            size = C::SIZE
+        );
+        let mut table = Vec::with_capacity(C::SIZE * moves.len());
+        for c in 0..C::SIZE {
+            let p = C::unrank(c);
+            for &m in moves.iter() {
+                table.push(C::rank(&p.apply(m)) as u32);
+            }
+        }
+        Self {
+            moves: moves.len(),
+            table,
+        }
+    }
+
+    /// Value after applying move `m` (an index into the build's moves) to
+    /// value `c`.
+    pub fn get(&self, c: usize, m: usize) -> usize {
+        self.table[c * self.moves + m] as usize
+    }
+
+    /// Number of coordinate values.
+    pub fn len(&self) -> usize {
+        self.table.len().checked_div(self.moves).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// A table from its entries, value * moves + move.
+    pub(crate) fn from_parts(moves: usize, table: Vec<u32>) -> Self {
+        Self { moves, table }
+    }
+}
+
+#[cfg(feature = "std")]
+impl MoveTable {
+    /// Little-endian: the number of values and of moves as u32s, then the
+    /// entries as u32s.
+    pub fn write_to<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        w.write_all(&(self.len() as u32).to_le_bytes())?;
+        w.write_all(&(self.moves as u32).to_le_bytes())?;
+        for &x in self.table.iter() {
+            w.write_all(&x.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read a table written by `write_to`, checking that it has `size`
+    /// values and `moves` moves.
+    pub fn read_from<R: std::io::Read>(
+        mut r: R,
+        size: usize,
+        moves: usize,
+    ) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind};
+        let mut word = [0; 4];
+        let mut next = |r: &mut R| {
+            r.read_exact(&mut word)?;
+            Ok::<_, Error>(u32::from_le_bytes(word))
+        };
+        let (len, m) = (next(&mut r)? as usize, next(&mut r)? as usize);
+        if len != size || m != moves {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "move table is {} x {}, expected {} x {}",
+                    len, m, size, moves
+                ),
+            ));
+        }
+        let mut table = Vec::with_capacity(size * moves);
+        for _ in 0..size * moves {
+            let x = next(&mut r)?;
+            if x as usize >= size {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "move table entry out of range",
+                ));
+            }
+            table.push(x);
+        }
+        Ok(Self { moves, table })
+    }
+}
+
+/// Zero-copy loading, as for `PruneTable`.
+#[cfg(feature = "rkyv")]
+impl MoveTable {
+    /// The table in rkyv's archived format.
+    pub fn to_archived_bytes(&self) -> rkyv::util::AlignedVec {
+        rkyv::to_bytes::<rkyv::rancor::Error>(self).expect("serializing a Vec")
+    }
+
+    /// The table archived in `bytes` by `to_archived_bytes`, checked to
+    /// have `size` values and `moves` moves. The bytes must be 16-byte
+    /// aligned.
+    pub fn access_archived(
+        bytes: &[u8],
+        size: usize,
+        moves: usize,
+    ) -> Result<&ArchivedMoveTable, rkyv::rancor::Error> {
+        use rkyv::rancor::{fail, Error};
+        let table = rkyv::access::<ArchivedMoveTable, Error>(bytes)?;
+        if table.moves() != moves || table.len() != size {
+            fail!(ShapeMismatch);
+        }
+        if table.table.iter().any(|x| x.to_native() as usize >= size) {
+            fail!(ShapeMismatch);
+        }
+        Ok(table)
+    }
+}
+
+/// An archived move table of the wrong size, or with entries out of range.
+#[cfg(feature = "rkyv")]
+#[derive(Debug)]
+struct ShapeMismatch;
+
+#[cfg(feature = "rkyv")]
+impl core::fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "archived move table has the wrong shape")
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl std::error::Error for ShapeMismatch {}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedMoveTable {
+    /// As `MoveTable::get`.
+    pub fn get(&self, c: usize, m: usize) -> usize {
+        self.table[c * self.moves() + m].to_native() as usize
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len().checked_div(self.moves()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    pub fn moves(&self) -> usize {
+        self.moves.to_native() as usize
+    }
+}
@@ -0,0 +1,147 @@
+//! Classic pretty patterns, with a face-turn alg reaching each.
+//!
+//! The cube keeps its centers fixed, so the patterns here are those of the
+//! face-turn group: the checkerboard is six half turns rather than the
+//! slice moves it is usually shown with, and the spot patterns, which move
+//! only the centers in the usual sense, are every other piece turned
+//! around them instead.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::notation::Move;
+use crate::rotation::CubeRotation;
+use core::fmt;
+
+/// A named pattern: its state, and an alg reaching it from solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamedPattern {
+    pub name: &'static str,
+    pub moves: &'static [Move],
+    pub cube: Cube,
+}
+
+impl NamedPattern {
+    pub fn alg(&self) -> Alg {
+        Alg(self.moves.to_vec())
+    }
+
+    /// Whether `cube` is this pattern, rotated, inverted or both.
+    pub fn matches(&self, cube: &Cube) -> bool {
+        self.relation(cube).is_some()
+    }
+
+    /// A rotation, and whether to invert first, taking `cube` to this
+    /// pattern.
+    fn relation(&self, cube: &Cube) -> Option<(CubeRotation, bool)> {
+        let inverse = cube.invert();
+        [(cube, false), (&inverse, true)]
+            .iter()
+            .flat_map(|&(c, inverted)| {
+                CubeRotation::all().map(move |r| (c, inverted, r))
+            })
+            .find(|(c, _, r)| r.conjugate(c) == self.cube)
+            .map(|(_, inverted, rotation)| (rotation, inverted))
+    }
+}
+
+/// A pattern from its name and alg, both checked at compile time.
+macro_rules! named {
+    ($name:expr, $alg:expr) => {
+        NamedPattern {
+            name: $name,
+            moves: crate::alg!($alg),
+            cube: crate::alg!(cube $alg),
+        }
+    };
+}
+
+/// Every edge flipped in place.
+pub const SUPERFLIP: NamedPattern = named!(
+    "superflip",
+    "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2"
+);
+
+/// Each face a checkerboard of its own color and its opposite's.
+pub const CHECKERBOARD: NamedPattern =
+    named!("checkerboard", "U2 D2 F2 B2 L2 R2");
+
+/// A 2x2x2 cube of other colors in one corner.
+pub const CUBE_IN_CUBE: NamedPattern =
+    named!("cube in cube", "F L F U' R U F2 L2 U' L' B D' B' L2 U");
+
+/// A 2x2x2 cube in a 2x2x2 cube in one corner.
+pub const CUBE_IN_CUBE_IN_CUBE: NamedPattern = named!(
+    "cube in cube in cube",
+    "U' L' U' F' R2 B' R F U B2 U B' L U' F U R F'"
+);
+
+/// Each face its neighbor's color but for the center.
+pub const SIX_SPOT: NamedPattern = named!("six spot", "U D' R L' F B' U D'");
+
+/// Each side face its opposite's color but for the center.
+pub const FOUR_SPOT: NamedPattern =
+    named!("four spot", "F2 B2 U D' R2 L2 U D'");
+
+/// A band of color snaking over the cube.
+pub const ANACONDA: NamedPattern =
+    named!("anaconda", "L U B' U' R L' B L' F' B D' R B' F' L'");
+
+/// Two-layer blocks stacked like Tetris pieces.
+pub const TETRIS: NamedPattern = named!("tetris", "L R F B U' D' L' R'");
+
+/// The patterns above.
+pub static ALL: [NamedPattern; 8] = [
+    SUPERFLIP,
+    CHECKERBOARD,
+    CUBE_IN_CUBE,
+    CUBE_IN_CUBE_IN_CUBE,
+    SIX_SPOT,
+    FOUR_SPOT,
+    ANACONDA,
+    TETRIS,
+];
+
+/// The pattern named `name`, ignoring case.
+pub fn by_name(name: &str) -> Option<&'static NamedPattern> {
+    ALL.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// A state found to be a named pattern: conjugating the state, or its
+/// inverse if `inverted`, by `rotation` gives the pattern's own state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recognized {
+    pub pattern: &'static NamedPattern,
+    pub rotation: CubeRotation,
+    pub inverted: bool,
+}
+
+impl Recognized {
+    pub fn name(&self) -> &'static str {
+        self.pattern.name
+    }
+}
+
+impl fmt::Display for Recognized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.inverted {
+            write!(f, "inverse ")?;
+        }
+        write!(f, "{}", self.pattern.name)?;
+        if self.rotation != CubeRotation::IDENTITY {
+            write!(f, " ({})", self.rotation)?;
+        }
+        Ok(())
+    }
+}
+
+/// The first pattern of `ALL` that `cube` is, up to rotation and
+/// inversion.
+pub fn recognize(cube: &Cube) -> Option<Recognized> {
+    ALL.iter().find_map(|pattern| {
+        let (rotation, inverted) = pattern.relation(cube)?;
+        Some(Recognized {
+            pattern,
+            rotation,
+            inverted,
+        })
+    })
+}
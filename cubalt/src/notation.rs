@@ -0,0 +1,193 @@
+//! Face-turn notation for the 3x3.
+use crate::cube::Cube;
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// A face, in the order used by the move and facelet tables.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Face {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+
+impl Face {
+    pub const ALL: [Face; 6] =
+        [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+    /// 0 for U/D, 1 for R/L, 2 for F/B.
+    pub fn axis(self) -> u8 {
+        self as u8 % 3
+    }
+
+    pub fn opposite(self) -> Face {
+        Face::ALL[(self as usize + 3) % 6]
+    }
+
+    pub fn to_char(self) -> char {
+        b"URFDLB"[self as usize] as char
+    }
+}
+
+/// A face turn, numbered as in `Cube::moves`: face * 3 + (power - 1).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Move {
+    U,
+    U2,
+    Ui,
+    R,
+    R2,
+    Ri,
+    F,
+    F2,
+    Fi,
+    D,
+    D2,
+    Di,
+    L,
+    L2,
+    Li,
+    B,
+    B2,
+    Bi,
+}
+
+/// Move cubes in `Move` order.
+static MOVE_CUBES: [Cube; 18] = Cube::moves();
+
+impl Move {
+    #[rustfmt::skip]
+    pub const ALL: [Move; 18] = [
+        Move::U, Move::U2, Move::Ui,
+        Move::R, Move::R2, Move::Ri,
+        Move::F, Move::F2, Move::Fi,
+        Move::D, Move::D2, Move::Di,
+        Move::L, Move::L2, Move::Li,
+        Move::B, Move::B2, Move::Bi,
+    ];
+
+    pub fn new(face: Face, power: u8) -> Self {
+        debug_assert!((1..=3).contains(&power));
+        Move::ALL[face as usize * 3 + (power as usize - 1)]
+    }
+
+    pub fn from_index(i: usize) -> Self {
+        Move::ALL[i]
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn face(self) -> Face {
+        Face::ALL[self as usize / 3]
+    }
+
+    /// Number of clockwise quarter turns: 1, 2 or 3.
+    pub fn power(self) -> u8 {
+        self as u8 % 3 + 1
+    }
+
+    pub fn inverse(self) -> Self {
+        Move::new(self.face(), 4 - self.power())
+    }
+
+    /// The state reached by applying this move to the solved cube.
+    pub fn cube(self) -> &'static Cube {
+        &MOVE_CUBES[self as usize]
+    }
+
+    /// The move `s` names, read as `FromStr` reads it but without
+    /// allocating an error.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.chars();
+        let face = match chars.next()? {
+            'U' => Face::U,
+            'R' => Face::R,
+            'F' => Face::F,
+            'D' => Face::D,
+            'L' => Face::L,
+            'B' => Face::B,
+            _ => return None,
+        };
+        let power = match chars.as_str() {
+            "" => 1,
+            "2" | "2'" => 2,
+            "'" => 3,
+            _ => return None,
+        };
+        Some(Move::new(face, power))
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = ["", "2", "'"][self.power() as usize - 1];
+        write!(f, "{}{}", self.face().to_char(), suffix)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Move {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Ok(*u.choose(&Move::ALL)?)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(1))
+    }
+}
+
+/// In notation, as `R'`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Move {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Move {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        d: D,
+    ) -> Result<Self, D::Error> {
+        let s: alloc::string::String = serde::Deserialize::deserialize(d)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Uniform over the 18 face turns.
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Move> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Move {
+        Move::ALL[rng.gen_range(0..18)]
+    }
+}
+
+/// A string that is not a face turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError(pub String);
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a move: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseMoveError {}
+
+impl core::str::FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, ParseMoveError> {
+        Move::parse(s).ok_or_else(|| ParseMoveError(s.to_string()))
+    }
+}
@@ -0,0 +1,517 @@
+//! Optimal solving, in the half-turn or quarter-turn metric, and random
+//! states at a given distance.
+//!
+//! The default tables are small, so this is only practical up to about 12
+//! moves; a `TableBudget` of 90 MB adds a table of the corners that makes
+//! searches several times faster. Use `solver` for arbitrary states.
+use crate::alg::Alg;
+use crate::canonical::{is_canonical, is_canonical_qtm, CanonicalSeq};
+use crate::coord::{
+    Coordinate, CornerOrient, CornerPerm, Corners, FlipSlice, TwistSlice,
+};
+use crate::cube::Cube;
+use crate::diverse::Distinct;
+use crate::notation::Move;
+use crate::pattern::Solved;
+use crate::prune::{NibbleTable, PruneTable};
+use crate::random::Rng;
+use crate::search::{Heuristic, Ida};
+use core::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// How solutions are counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Every face turn is one move.
+    #[default]
+    Htm,
+    /// Quarter turns are one move and half turns two.
+    Qtm,
+}
+
+/// The quarter turns, in `Move::ALL` order.
+pub const QUARTER_TURNS: [Move; 12] = [
+    Move::U,
+    Move::Ui,
+    Move::R,
+    Move::Ri,
+    Move::F,
+    Move::Fi,
+    Move::D,
+    Move::Di,
+    Move::L,
+    Move::Li,
+    Move::B,
+    Move::Bi,
+];
+
+impl Metric {
+    /// The moves of unit cost.
+    pub fn moves(self) -> &'static [Move] {
+        match self {
+            Metric::Htm => &Move::ALL,
+            Metric::Qtm => &QUARTER_TURNS,
+        }
+    }
+
+    pub fn cost(self, m: Move) -> u32 {
+        match self {
+            Metric::Qtm if m.power() == 2 => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn length(self, alg: &Alg) -> u32 {
+        alg.moves().iter().map(|&m| self.cost(m)).sum()
+    }
+
+    fn rule(self) -> fn(Option<Move>, Move) -> bool {
+        match self {
+            Metric::Htm => is_canonical::<Cube>,
+            Metric::Qtm => is_canonical_qtm::<Cube>,
+        }
+    }
+}
+
+/// Memory an `Optimal` solver may spend on pruning tables, from which it
+/// picks the tables to build. The small tables, about 2 MB, are always
+/// built; the default budget allows nothing more.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TableBudget {
+    pub bytes: usize,
+}
+
+/// How a pruning table is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    /// A byte per distance.
+    None,
+    /// Half a byte per distance.
+    Nibbles,
+}
+
+/// The tables chosen for a budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TablePlan {
+    /// The table of every corner state, 88 million entries, if any.
+    pub corners: Option<Compression>,
+}
+
+/// Bytes of the tables every solver builds.
+const SMALL_TABLE_BYTES: usize =
+    TwistSlice::SIZE + FlipSlice::SIZE + CornerPerm::SIZE;
+
+impl TableBudget {
+    pub const fn megabytes(n: usize) -> Self {
+        Self { bytes: n << 20 }
+    }
+
+    pub const fn gigabytes(n: usize) -> Self {
+        Self { bytes: n << 30 }
+    }
+
+    /// The strongest tables that fit in the budget.
+    pub fn plan(self) -> TablePlan {
+        [Some(Compression::None), Some(Compression::Nibbles)]
+            .iter()
+            .map(|&corners| TablePlan { corners })
+            .find(|p| p.bytes() <= self.bytes)
+            .unwrap_or_default()
+    }
+}
+
+impl TablePlan {
+    /// Memory the plan's tables take.
+    pub fn bytes(&self) -> usize {
+        SMALL_TABLE_BYTES
+            + match self.corners {
+                None => 0,
+                Some(Compression::None) => Corners::SIZE,
+                Some(Compression::Nibbles) => Corners::SIZE.div_ceil(2),
+            }
+    }
+}
+
+/// Settings of an `Optimal` solver, fixed when its tables are built.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveOptions {
+    pub metric: Metric,
+    pub budget: TableBudget,
+    /// Search successors in order, as `Ida::ordering`.
+    pub move_ordering: bool,
+}
+
+/// Number of states at each distance from solved in the half-turn metric,
+/// as far as it is known exactly.
+pub const STATES_AT_DISTANCE: [u64; 16] = [
+    1,
+    18,
+    243,
+    3240,
+    43239,
+    574908,
+    7618438,
+    100803036,
+    1332343288,
+    17596479795,
+    232248063316,
+    3063288809012,
+    40374425656248,
+    531653418284628,
+    6989320578825358,
+    91365146187124313,
+];
+
+/// The full corner table, as planned.
+enum CornerTable {
+    Bytes(PruneTable),
+    Nibbles(NibbleTable),
+}
+
+impl CornerTable {
+    fn build(metric: Metric, compression: Compression) -> Self {
+        let moves = metric.moves();
+        match compression {
+            Compression::None => {
+                CornerTable::Bytes(PruneTable::build_product::<
+                    Cube,
+                    CornerPerm,
+                    CornerOrient,
+                >(moves))
+            }
+            Compression::Nibbles => {
+                CornerTable::Nibbles(NibbleTable::build_product::<
+                    Cube,
+                    CornerPerm,
+                    CornerOrient,
+                >(moves))
+            }
+        }
+    }
+
+    fn distance(&self, c: &Cube) -> u8 {
+        match self {
+            CornerTable::Bytes(t) => t.distance(Corners::rank(c)),
+            CornerTable::Nibbles(t) => t.distance(Corners::rank(c)),
+        }
+    }
+}
+
+/// The largest of the twist-slice and flip-slice distances on each of the
+/// three axes, and the corner permutation distance, in one metric; with a
+/// larger budget, the distance of the whole corner state instead.
+pub struct OptimalHeuristic {
+    twist: PruneTable,
+    flip: PruneTable,
+    corners: PruneTable,
+    full_corners: Option<CornerTable>,
+}
+
+impl OptimalHeuristic {
+    pub fn build(metric: Metric) -> Self {
+        Self::build_with_plan(metric, TablePlan::default())
+    }
+
+    pub fn build_with_plan(metric: Metric, plan: TablePlan) -> Self {
+        let moves = metric.moves();
+        Self {
+            twist: PruneTable::build_with_moves::<Cube, TwistSlice>(moves),
+            flip: PruneTable::build_with_moves::<Cube, FlipSlice>(moves),
+            corners: PruneTable::build_with_moves::<Cube, CornerPerm>(moves),
+            full_corners: plan.corners.map(|c| CornerTable::build(metric, c)),
+        }
+    }
+}
+
+impl OptimalHeuristic {
+    fn estimate_one(&self, c: &Cube) -> u8 {
+        // Conjugating by S_URF3 turns the other axes into U-D, and keeps
+        // the distance.
+        let mut h = match &self.full_corners {
+            Some(t) => t.distance(c),
+            None => self.corners.distance(CornerPerm::rank(c)),
+        };
+        for s in [0, 16, 32] {
+            let d = c.sym_conjugate(s);
+            h = h
+                .max(self.twist.distance(TwistSlice::rank(&d)))
+                .max(self.flip.distance(FlipSlice::rank(&d)));
+        }
+        h
+    }
+}
+
+impl Heuristic<Cube> for OptimalHeuristic {
+    /// The inverse is as far from solved, so its estimate bounds too.
+    fn estimate(&self, c: &Cube) -> u32 {
+        self.estimate_one(c).max(self.estimate_one(&c.invert())) as u32
+    }
+}
+
+/// An optimal solver. Building its tables takes a few seconds; clones
+/// share them.
+#[derive(Clone)]
+pub struct Optimal {
+    options: SolveOptions,
+    heuristic: Arc<OptimalHeuristic>,
+    canonical: CanonicalSeq,
+}
+
+impl Default for Optimal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Optimal {
+    /// A half-turn metric solver.
+    pub fn new() -> Self {
+        Self::with_options(SolveOptions::default())
+    }
+
+    pub fn with_options(options: SolveOptions) -> Self {
+        let metric = options.metric;
+        Self {
+            heuristic: Arc::new(OptimalHeuristic::build_with_plan(
+                metric,
+                options.budget.plan(),
+            )),
+            canonical: CanonicalSeq::with_rule::<Cube>(
+                metric.moves(),
+                metric.rule(),
+            ),
+            options,
+        }
+    }
+
+    pub fn options(&self) -> &SolveOptions {
+        &self.options
+    }
+
+    pub fn heuristic(&self) -> &OptimalHeuristic {
+        &self.heuristic
+    }
+
+    /// Which of the metric's moves may follow which.
+    pub(crate) fn canonical(&self) -> &CanonicalSeq {
+        &self.canonical
+    }
+
+    pub(crate) fn ida(
+        &self,
+        max_length: u32,
+    ) -> Ida<'_, Cube, OptimalHeuristic, Solved> {
+        let mut ida = Ida::new(&*self.heuristic, &Solved, max_length);
+        ida.moves = self.options.metric.moves();
+        ida.canonical = self.options.metric.rule();
+        ida.ordering = self.options.move_ordering;
+        ida
+    }
+
+    /// A shortest solution in the solver's metric, if there is one of at
+    /// most `max_length`. In the quarter-turn metric, quarter turns of a
+    /// face in a row are written as a half turn.
+    pub fn solve(&self, cube: &Cube, max_length: u32) -> Option<Alg> {
+        self.ida(max_length)
+            .solve(cube)
+            .map(|moves| Alg(moves).simplify())
+    }
+
+    /// A shortest solution warm-started from `hint`, if there is one of at
+    /// most `max_length`.
+    ///
+    /// If `hint` solves `cube`, only lengths below the hint's are searched,
+    /// and the hint is the answer if none is shorter, which skips the
+    /// deepest and slowest iteration. Otherwise the hint is kept as the
+    /// start of the solution, followed by a shortest solution of the rest.
+    pub fn solve_with_hint(
+        &self,
+        cube: &Cube,
+        hint: &Alg,
+        max_length: u32,
+    ) -> Option<Alg> {
+        let hint = hint.simplify();
+        let length = self.options.metric.length(&hint);
+        let rest = hint.apply(cube);
+        if rest == Cube::identity() {
+            if length == 0 {
+                return Some(hint);
+            }
+            let shorter = self.solve(cube, max_length.min(length - 1));
+            return shorter.or_else(|| (length <= max_length).then_some(hint));
+        }
+        let mut alg = hint;
+        alg.0
+            .extend(self.solve(&rest, max_length.checked_sub(length)?)?.0);
+        Some(alg.simplify())
+    }
+
+    /// Up to `n` solutions of at most `max_length`, no two alike as
+    /// `diverse::key` has it, shortest first: the optimal ones, then longer
+    /// ones if there are too few.
+    pub fn solve_diverse(
+        &self,
+        cube: &Cube,
+        max_length: u32,
+        n: usize,
+    ) -> Vec<Alg> {
+        let mut distinct = Distinct::new(n);
+        let mut ida = self.ida(max_length);
+        for depth in self.heuristic.estimate(cube)..=max_length {
+            if ida.search_depth(cube, depth, |path, _| {
+                distinct.insert(Alg(path.to_vec()).simplify());
+                distinct.is_full()
+            }) {
+                break;
+            }
+        }
+        distinct.into_algs()
+    }
+
+    /// The distance from solved in the solver's metric, if at most
+    /// `max_length`.
+    pub fn distance(&self, cube: &Cube, max_length: u32) -> Option<u32> {
+        let alg = self.solve(cube, max_length)?;
+        Some(self.options.metric.length(&alg))
+    }
+
+    /// A uniformly random state at distance exactly `d`, in the solver's
+    /// metric.
+    ///
+    /// A random canonical sequence of `d` moves reaches each state at
+    /// distance `d` with probability proportional to its number of optimal
+    /// solutions; those at a shorter distance are rejected, and the rest
+    /// kept with probability one over that number. Panics if `d > 15`.
+    pub fn random_at_distance(&self, rng: &mut Rng, d: u32) -> Cube {
+        assert!(d <= 15, "distance {} out of range", d);
+        let ways = self.sequence_counts(d as usize);
+        loop {
+            let cube = self.random_sequence(rng, &ways).cube();
+            let mut ida = self.ida(d);
+            if (0..d).any(|k| ida.search_depth(&cube, k, |_, _| true)) {
+                continue;
+            }
+            let mut n = 0;
+            ida.search_depth(&cube, d, |_, _| {
+                n += 1;
+                false
+            });
+            if rng.below(n) == 0 {
+                return cube;
+            }
+        }
+    }
+
+    /// A uniformly random state with its half-turn distance in `range`.
+    /// Panics if the range reaches past 15, and for quarter-turn solvers.
+    pub fn random_in_range(
+        &self,
+        rng: &mut Rng,
+        range: RangeInclusive<u32>,
+    ) -> Cube {
+        assert_eq!(self.options.metric, Metric::Htm, "no QTM state counts");
+        assert!(*range.end() <= 15, "distance {} out of range", range.end());
+        let total: u64 =
+            range.clone().map(|d| STATES_AT_DISTANCE[d as usize]).sum();
+        let mut r = rng.below(total);
+        for d in range {
+            let n = STATES_AT_DISTANCE[d as usize];
+            if r < n {
+                return self.random_at_distance(rng, d);
+            }
+            r -= n;
+        }
+        panic!("empty distance range")
+    }
+
+    /// `ways[k][s]`: the number of canonical sequences of `k` moves that
+    /// may follow state `s` of `canonical` (0 at the start, `i + 1` after
+    /// move `i`).
+    fn sequence_counts(&self, len: usize) -> Vec<Vec<u64>> {
+        let n = self.canonical.len();
+        let mut ways = vec![vec![1u64; n + 1]];
+        for k in 1..=len {
+            let row = (0..=n)
+                .map(|s| {
+                    let prev = s.checked_sub(1);
+                    self.canonical
+                        .next_moves(prev)
+                        .map(|i| ways[k - 1][i + 1])
+                        .sum()
+                })
+                .collect();
+            ways.push(row);
+        }
+        ways
+    }
+
+    /// A uniformly random canonical sequence of `ways.len() - 1` moves.
+    fn random_sequence(&self, rng: &mut Rng, ways: &[Vec<u64>]) -> Alg {
+        let mut alg = Alg::new();
+        let mut prev = None;
+        for k in (1..ways.len()).rev() {
+            let mut r = rng.below(ways[k][prev.map_or(0, |i| i + 1)]);
+            for i in self.canonical.next_moves(prev) {
+                let w = ways[k - 1][i + 1];
+                if r < w {
+                    alg.push(self.options.metric.moves()[i]);
+                    prev = Some(i);
+                    break;
+                }
+                r -= w;
+            }
+        }
+        alg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that `alg` is a shortest solution of `cube` in `optimal`'s
+    /// metric, and returns its length.
+    fn check_optimal(optimal: &Optimal, cube: &Cube, alg: &Alg) -> u32 {
+        assert_eq!(cube.compose(&alg.cube()), Cube::identity());
+        let length = optimal.options().metric.length(alg);
+        if let Some(shorter) = length.checked_sub(1) {
+            let better = optimal.solve(cube, shorter);
+            assert_eq!(better, None, "{} is longer than needed", alg);
+        }
+        length
+    }
+
+    #[test]
+    fn solves_scrambles_optimally() {
+        let optimal = Optimal::new();
+        let mut rng = Rng::new(1);
+        for _ in 0..5 {
+            let scramble =
+                Alg((0..7).map(|_| *rng.choose(&Move::ALL)).collect());
+            let cube = scramble.cube();
+            let alg = optimal.solve(&cube, 7).unwrap();
+            assert!(check_optimal(&optimal, &cube, &alg) <= 7);
+            let hinted = optimal
+                .solve_with_hint(&cube, &scramble.inverse(), 7)
+                .unwrap();
+            assert_eq!(hinted.len(), alg.len());
+        }
+        for d in 0..5 {
+            let cube = optimal.random_at_distance(&mut rng, d);
+            assert_eq!(optimal.distance(&cube, d), Some(d));
+        }
+    }
+
+    #[test]
+    fn counts_quarter_turns() {
+        let optimal = Optimal::with_options(SolveOptions {
+            metric: Metric::Qtm,
+            ..SolveOptions::default()
+        });
+        for (s, d) in [("R2", 2), ("R U'", 2), ("R2 U2 F", 5)].iter() {
+            let cube = s.parse::<Alg>().unwrap().cube();
+            let alg = optimal.solve(&cube, *d).unwrap();
+            assert_eq!(check_optimal(&optimal, &cube, &alg), *d, "{}", s);
+        }
+    }
+}
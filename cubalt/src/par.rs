@@ -0,0 +1,50 @@
+//! Rayon adapters for batches of states.
+//!
+//! Each takes anything rayon can iterate in parallel over cubes, so they
+//! chain with each other and with rayon's own adapters:
+//!
+//! ```ignore
+//! let next = par::successors(frontier).map(|(_, c)| c);
+//! let classes: Vec<Cube> = par::canonical(next).collect();
+//! ```
+//!
+//! They run on the pool they are consumed in, rayon's global pool unless
+//! the consuming call is inside `ThreadPool::install`.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::notation::Move;
+use rayon::prelude::*;
+
+/// The 18 successors of every state, with their moves.
+pub fn successors<I>(cubes: I) -> impl ParallelIterator<Item = (Move, Cube)>
+where
+    I: IntoParallelIterator<Item = Cube>,
+{
+    cubes.into_par_iter().flat_map_iter(|c| c.successors())
+}
+
+/// Every state with `alg` applied.
+pub fn apply_alg<'a, I>(
+    cubes: I,
+    alg: &'a Alg,
+) -> impl ParallelIterator<Item = Cube> + 'a
+where
+    I: IntoParallelIterator<Item = Cube>,
+    I::Iter: 'a,
+{
+    cubes.into_par_iter().map(move |c| alg.apply(&c))
+}
+
+/// Applies `alg` to every state of `cubes`.
+pub fn apply_alg_in_place(cubes: &mut [Cube], alg: &Alg) {
+    cubes.par_iter_mut().for_each(|c| *c = alg.apply(c));
+}
+
+/// The representative of every state under the symmetries and
+/// antisymmetries; see `Cube::antisym_canonical`.
+pub fn canonical<I>(cubes: I) -> impl ParallelIterator<Item = Cube>
+where
+    I: IntoParallelIterator<Item = Cube>,
+{
+    cubes.into_par_iter().map(|c| c.antisym_canonical().0)
+}
@@ -0,0 +1,199 @@
+//! Goal patterns: which pieces have to be where.
+use crate::avx2;
+use crate::cube::Cube;
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::layout::PieceLayout;
+use crate::puzzle::Puzzle;
+use crate::types::*;
+use alloc::vec::Vec;
+
+/// Something a search can aim for.
+pub trait Goal<P: Puzzle> {
+    fn is_goal(&self, p: &P) -> bool;
+}
+
+/// The solved state.
+pub struct Solved;
+
+impl<P: Puzzle> Goal<P> for Solved {
+    fn is_goal(&self, p: &P) -> bool {
+        p.is_solved()
+    }
+}
+
+impl<P: Puzzle, F: Fn(&P) -> bool> Goal<P> for F {
+    fn is_goal(&self, p: &P) -> bool {
+        self(p)
+    }
+}
+
+/// Several goals, any of which will do. One search for all of them visits
+/// each state once, where a search per goal would visit it for every one.
+#[derive(Debug, Clone)]
+pub struct AnyOf<G>(pub Vec<G>);
+
+impl<G> AnyOf<G> {
+    /// The index of the first goal `p` reaches.
+    pub fn which<P: Puzzle>(&self, p: &P) -> Option<usize>
+    where
+        G: Goal<P>,
+    {
+        self.0.iter().position(|g| g.is_goal(p))
+    }
+}
+
+impl<P: Puzzle, G: Goal<P>> Goal<P> for AnyOf<G> {
+    fn is_goal(&self, p: &P) -> bool {
+        self.0.iter().any(|g| g.is_goal(p))
+    }
+}
+
+/// A partial 3x3 state: target piece bytes and a mask of the bits that
+/// matter, compared in one masked SIMD test.
+///
+/// Masking a whole byte asks for a specific piece, correctly oriented, in
+/// that slot; masking only the orientation bits asks for whatever piece is
+/// there to be oriented.
+#[derive(Debug, Clone, Copy)]
+pub struct Pattern {
+    target: Cube,
+    mask: m256i,
+}
+
+/// The F2L slots as (E-slice edge, D corner) pairs: FR, FL, BL, BR.
+pub const F2L_SLOTS: [(usize, usize); 4] = [(8, 4), (9, 5), (10, 6), (11, 7)];
+
+/// Edge slots of the D face: DR, DF, DL, DB.
+pub const CROSS_EDGES: u16 = 0x00f0;
+
+impl Pattern {
+    /// Match nothing but `target` itself.
+    pub fn exact(target: Cube) -> Self {
+        Self::slots(target, 0xfff, 0xff)
+    }
+
+    pub fn solved() -> Self {
+        Self::exact(Cube::identity())
+    }
+
+    /// Match the pieces of `target` in the given edge and corner slots
+    /// (bit i = slot i), including their orientation.
+    pub fn slots(target: Cube, edges: u16, corners: u8) -> Self {
+        Self::from_masks(target, edges, 0xff, corners, 0xff)
+    }
+
+    /// Match only the orientation of whatever is in the given slots.
+    pub fn orientation(edges: u16, corners: u8) -> Self {
+        Self::from_masks(
+            Cube::identity(),
+            edges,
+            EDGES.ori_mask(),
+            corners,
+            CORNERS.ori_mask(),
+        )
+    }
+
+    fn from_masks(
+        target: Cube,
+        edges: u16,
+        edge_bits: u8,
+        corners: u8,
+        corner_bits: u8,
+    ) -> Self {
+        let mut mask = [0u8; 32];
+        for i in 0..EDGES.count {
+            if edges & (1 << i) != 0 {
+                mask[EDGES.offset() + i] = edge_bits;
+            }
+        }
+        for i in 0..CORNERS.count {
+            if corners & (1 << i) != 0 {
+                mask[CORNERS.offset() + i] = corner_bits;
+            }
+        }
+        Self {
+            target,
+            mask: unsafe { core::mem::transmute::<[u8; 32], m256i>(mask) },
+        }
+    }
+
+    /// The D cross.
+    pub fn cross() -> Self {
+        Self::slots(Cube::identity(), CROSS_EDGES, 0)
+    }
+
+    /// Cross plus the given F2L slots (bit i = `F2L_SLOTS[i]`).
+    pub fn f2l_slots(slots: u8) -> Self {
+        let (mut edges, mut corners) = (CROSS_EDGES, 0u8);
+        for (i, &(e, c)) in F2L_SLOTS.iter().enumerate() {
+            if slots & (1 << i) != 0 {
+                edges |= 1 << e;
+                corners |= 1 << c;
+            }
+        }
+        Self::slots(Cube::identity(), edges, corners)
+    }
+
+    pub fn f2l() -> Self {
+        Self::f2l_slots(0xf)
+    }
+
+    /// F2L with `slot` (an index into `F2L_SLOTS`) left unsolved.
+    pub fn f2l_minus_one(slot: usize) -> Self {
+        Self::f2l_slots(0xf & !(1 << slot))
+    }
+
+    /// Both patterns at once. Where the masks overlap the targets must
+    /// agree, or nothing will match.
+    pub fn and(&self, other: &Pattern) -> Self {
+        unsafe {
+            use core::arch::x86_64::*;
+            let target = _mm256_or_si256(
+                _mm256_and_si256(self.target.0, self.mask),
+                _mm256_andnot_si256(self.mask, other.target.0),
+            );
+            Self {
+                target: Cube(target),
+                mask: _mm256_or_si256(self.mask, other.mask),
+            }
+        }
+    }
+
+    pub fn matches(&self, cube: &Cube) -> bool {
+        avx2::masked_equals(self.target.0, cube.0, self.mask)
+    }
+
+    /// Number of distinct contents the masked slots take over all
+    /// reachable states: the size of a pattern database on them. Counts
+    /// the orientation sums and, when both permutations are fixed, their
+    /// matching parities.
+    pub fn state_count(&self) -> u128 {
+        let mask: [u8; 32] = unsafe { core::mem::transmute(self.mask) };
+        // Orderings of the placed pieces, whether that fixes the whole
+        // permutation, and orientations.
+        let kind = |layout: &PieceLayout| {
+            let slots = &mask[layout.offset()..layout.offset() + layout.count];
+            let placed = slots.iter().filter(|&&m| m & layout.perm_mask != 0);
+            let placed = placed.count();
+            let oriented =
+                slots.iter().filter(|&&m| m & layout.ori_mask() != 0);
+            let oriented = oriented.count();
+            let n = layout.count;
+            let orders: u128 =
+                (n - placed + 1..=n).map(|k| k as u128).product();
+            let m = layout.ori_modulus as u128;
+            let oris = m.pow(oriented.min(n - 1) as u32);
+            (orders, placed + 1 >= n, oris)
+        };
+        let (e, e_fixed, eo) = kind(&EDGES);
+        let (c, c_fixed, co) = kind(&CORNERS);
+        let parity = if e_fixed && c_fixed { 2 } else { 1 };
+        e * c * eo * co / parity
+    }
+}
+
+impl Goal<Cube> for Pattern {
+    fn is_goal(&self, p: &Cube) -> bool {
+        self.matches(p)
+    }
+}
@@ -0,0 +1,175 @@
+//! Petrus: a 2x2x2 block, extended to 2x2x3, edge orientation, then the
+//! rest of F2L with R and U alone, and the last layer.
+//!
+//! The block is at DBL and grows towards the front, leaving the R and U
+//! layers free. Each block stage is solved optimally from a pruning table
+//! of its pieces, built in well under a second; the last layer is left to
+//! the two-phase solver, as there is no table of last layer algs here.
+use crate::alg::Alg;
+use crate::coord::{EdgeOrient, Pieces};
+use crate::cube::Cube;
+use crate::pattern::Pattern;
+use crate::prune::PruneTable;
+use crate::search::{Ida, TableHeuristic};
+use crate::solver::Solver;
+use crate::twogen;
+
+/// DL, DB, BL, and DBL.
+const BLOCK_EDGES: u16 = 0x4c0;
+const BLOCK_CORNERS: u8 = 0x40;
+
+/// DF, FL, and DLF: the 2x2x3 less the 2x2x2.
+const EXTENSION_EDGES: u16 = 0x220;
+const EXTENSION_CORNERS: u8 = 0x20;
+
+/// DR, FR, BR, DFR and DRB: the rest of F2L.
+const RIGHT_EDGES: u16 = 0x910;
+const RIGHT_CORNERS: u8 = 0x90;
+
+type Block = Pieces<BLOCK_EDGES, BLOCK_CORNERS>;
+type Extension = Pieces<EXTENSION_EDGES, EXTENSION_CORNERS>;
+type Right = Pieces<RIGHT_EDGES, RIGHT_CORNERS>;
+
+/// Longest solution looked for in each stage but the last layer, each
+/// beyond what any state needs.
+const BLOCK_LENGTH: u32 = 10;
+const EXTENSION_LENGTH: u32 = 14;
+const EO_LENGTH: u32 = 12;
+const F2L_LENGTH: u32 = 24;
+
+/// Longest last layer the two-phase solver is asked for.
+const LAST_LAYER_LENGTH: u32 = 24;
+
+fn two_by_three() -> Pattern {
+    Pattern::slots(
+        Cube::identity(),
+        BLOCK_EDGES | EXTENSION_EDGES,
+        BLOCK_CORNERS | EXTENSION_CORNERS,
+    )
+}
+
+/// The moves of each stage of a Petrus solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PetrusSolution {
+    pub block: Alg,
+    pub extension: Alg,
+    pub eo: Alg,
+    /// In R and U only.
+    pub f2l: Alg,
+    pub last_layer: Alg,
+}
+
+impl PetrusSolution {
+    /// Each stage with its name, in order.
+    pub fn stages(&self) -> [(&'static str, &Alg); 5] {
+        [
+            ("2x2x2", &self.block),
+            ("2x2x3", &self.extension),
+            ("EO", &self.eo),
+            ("F2L", &self.f2l),
+            ("LL", &self.last_layer),
+        ]
+    }
+
+    /// The whole solution, the stages one after another.
+    pub fn alg(&self) -> Alg {
+        Alg(self
+            .stages()
+            .iter()
+            .flat_map(|(_, alg)| alg.moves().iter().copied())
+            .collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages().iter().map(|(_, alg)| alg.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Petrus solutions, with the first four stages optimal. Clones share the
+/// tables.
+#[derive(Clone)]
+pub struct PetrusSolver {
+    block: TableHeuristic<Cube, Block>,
+    extension: TableHeuristic<Cube, Extension>,
+    eo: TableHeuristic<Cube, EdgeOrient>,
+    right: TableHeuristic<Cube, Right>,
+    solver: Solver,
+}
+
+impl Default for PetrusSolver {
+    fn default() -> Self {
+        Self::new(Solver::shared().clone())
+    }
+}
+
+impl PetrusSolver {
+    pub fn new(solver: Solver) -> Self {
+        Self {
+            block: TableHeuristic::build(),
+            extension: TableHeuristic::build(),
+            eo: TableHeuristic::build(),
+            right: TableHeuristic::new(PruneTable::build_with_moves::<
+                Cube,
+                Right,
+            >(&twogen::MOVES)),
+            solver,
+        }
+    }
+
+    /// A shortest 2x2x2 block of `cube`.
+    pub fn block(&self, cube: &Cube) -> Option<Alg> {
+        let goal = Pattern::slots(Cube::identity(), BLOCK_EDGES, BLOCK_CORNERS);
+        let mut ida = Ida::new(&self.block, &goal, BLOCK_LENGTH);
+        ida.solve(cube).map(Alg)
+    }
+
+    /// A shortest extension of the solved 2x2x2 block of `cube` to 2x2x3.
+    pub fn extension(&self, cube: &Cube) -> Option<Alg> {
+        let goal = two_by_three();
+        let bound = (&self.block, &self.extension);
+        let mut ida = Ida::new(&bound, &goal, EXTENSION_LENGTH);
+        ida.solve(cube).map(Alg)
+    }
+
+    /// A shortest orientation of the edges of `cube` keeping its 2x2x3.
+    pub fn eo(&self, cube: &Cube) -> Option<Alg> {
+        let goal = two_by_three().and(&Pattern::orientation(0xfff, 0));
+        let bound = ((&self.block, &self.extension), &self.eo);
+        let mut ida = Ida::new(&bound, &goal, EO_LENGTH);
+        ida.solve(cube).map(Alg)
+    }
+
+    /// A shortest ⟨R,U⟩ solution of the rest of F2L of `cube`, with the
+    /// 2x2x3 solved and the edges oriented.
+    pub fn f2l(&self, cube: &Cube) -> Option<Alg> {
+        let goal = Pattern::f2l();
+        let mut ida = Ida::new(&self.right, &goal, F2L_LENGTH);
+        ida.moves = &twogen::MOVES;
+        ida.solve(cube).map(Alg)
+    }
+
+    /// A Petrus solution of `cube`. `None` only if a stage is longer than
+    /// its bound, which does not happen.
+    pub fn solve(&self, cube: &Cube) -> Option<PetrusSolution> {
+        let block = self.block(cube)?;
+        let c = block.apply(cube);
+        let extension = self.extension(&c)?;
+        let c = extension.apply(&c);
+        let eo = self.eo(&c)?;
+        let c = eo.apply(&c);
+        let f2l = self.f2l(&c)?;
+        let c = f2l.apply(&c);
+        let last_layer = self.solver.solve(&c, LAST_LAYER_LENGTH)?;
+        Some(PetrusSolution {
+            block,
+            extension,
+            eo,
+            f2l,
+            last_layer,
+        })
+    }
+}
@@ -0,0 +1,170 @@
+//! Named slots, and iteration over what each slot of a cube holds.
+//!
+//! `cube.edge_pieces()` yields, for each edge slot in order, the slot, the
+//! piece in it (named by its home slot) and whether it is flipped;
+//! `cube.corner_pieces()` the same with the twist, 0 to 2 clockwise
+//! turns. Iterating over `&Cube` gives both, edges first.
+use crate::cube::{Corner, Cube, Edge};
+use crate::cycles::{CORNER_NAMES, EDGE_NAMES};
+use crate::layout::packed3::{CORNERS, EDGES};
+use core::fmt;
+use core::iter::{Chain, Enumerate, Map};
+use core::slice;
+
+/// An edge slot, or the edge whose home it is.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EdgePos {
+    UR,
+    UF,
+    UL,
+    UB,
+    DR,
+    DF,
+    DL,
+    DB,
+    FR,
+    FL,
+    BL,
+    BR,
+}
+
+impl EdgePos {
+    #[rustfmt::skip]
+    pub const ALL: [EdgePos; 12] = [
+        EdgePos::UR, EdgePos::UF, EdgePos::UL, EdgePos::UB,
+        EdgePos::DR, EdgePos::DF, EdgePos::DL, EdgePos::DB,
+        EdgePos::FR, EdgePos::FL, EdgePos::BL, EdgePos::BR,
+    ];
+
+    pub fn from_index(i: usize) -> Self {
+        EdgePos::ALL[i]
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for EdgePos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &c in EDGE_NAMES[self.index()].iter() {
+            write!(f, "{}", c as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// A corner slot, or the corner whose home it is.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CornerPos {
+    URF,
+    UFL,
+    ULB,
+    UBR,
+    DFR,
+    DLF,
+    DBL,
+    DRB,
+}
+
+impl CornerPos {
+    #[rustfmt::skip]
+    pub const ALL: [CornerPos; 8] = [
+        CornerPos::URF, CornerPos::UFL, CornerPos::ULB, CornerPos::UBR,
+        CornerPos::DFR, CornerPos::DLF, CornerPos::DBL, CornerPos::DRB,
+    ];
+
+    pub fn from_index(i: usize) -> Self {
+        CornerPos::ALL[i]
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for CornerPos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &c in CORNER_NAMES[self.index()].iter() {
+            write!(f, "{}", c as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// What a slot holds: the slot, the piece and its orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Piece {
+    /// Slot, piece, and whether it is flipped.
+    Edge(EdgePos, EdgePos, bool),
+    /// Slot, piece, and its clockwise twist, 0..3.
+    Corner(CornerPos, CornerPos, u8),
+}
+
+/// The slot, piece and flip of `edge`, in slot `i`.
+fn edge_piece((i, edge): (usize, &Edge)) -> (EdgePos, EdgePos, bool) {
+    let piece = EdgePos::from_index(EDGES.piece(edge.0) as usize);
+    (EdgePos::from_index(i), piece, EDGES.ori(edge.0) != 0)
+}
+
+/// The slot, piece and twist of `corner`, in slot `i`.
+fn corner_piece((i, corner): (usize, &Corner)) -> (CornerPos, CornerPos, u8) {
+    let piece = CornerPos::from_index(CORNERS.piece(corner.0) as usize);
+    (CornerPos::from_index(i), piece, CORNERS.ori(corner.0))
+}
+
+impl Cube {
+    /// Each edge slot, the edge in it, and whether that is flipped.
+    pub fn edge_pieces(
+        &self,
+    ) -> impl Iterator<Item = (EdgePos, EdgePos, bool)> + '_ {
+        self.edges().iter().enumerate().map(edge_piece)
+    }
+
+    /// Each corner slot, the corner in it, and its twist.
+    pub fn corner_pieces(
+        &self,
+    ) -> impl Iterator<Item = (CornerPos, CornerPos, u8)> + '_ {
+        self.corners().iter().enumerate().map(corner_piece)
+    }
+}
+
+type Slots<'a, T> =
+    Map<Enumerate<slice::Iter<'a, T>>, fn((usize, &T)) -> Piece>;
+
+/// The pieces of a cube, edges then corners.
+pub struct PieceIter<'a>(Chain<Slots<'a, Edge>, Slots<'a, Corner>>);
+
+impl Iterator for PieceIter<'_> {
+    type Item = Piece;
+
+    fn next(&mut self) -> Option<Piece> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for PieceIter<'_> {}
+
+impl<'a> IntoIterator for &'a Cube {
+    type Item = Piece;
+    type IntoIter = PieceIter<'a>;
+
+    fn into_iter(self) -> PieceIter<'a> {
+        let edges: Slots<Edge> = self.edges().iter().enumerate().map(|e| {
+            let (slot, piece, flip) = edge_piece(e);
+            Piece::Edge(slot, piece, flip)
+        });
+        let corners: Slots<Corner> =
+            self.corners().iter().enumerate().map(|c| {
+                let (slot, piece, twist) = corner_piece(c);
+                Piece::Corner(slot, piece, twist)
+            });
+        PieceIter(edges.chain(corners))
+    }
+}
@@ -0,0 +1,217 @@
+//! The 2x2 (pocket cube): the corners of the 3x3.
+//!
+//! Only U, R and F are turned, so the DBL corner stays put and fixes the
+//! frame; every state is reachable that way. There are 7! * 3^6 =
+//! 3,674,160 states, few enough to tabulate the distance of all of them.
+use crate::coord::{rank_perm, unrank_perm, Coordinate};
+use crate::cube::{Corner, Cube};
+use crate::layout::packed3::CORNERS;
+use crate::notation::Move;
+use crate::puzzle::Puzzle;
+#[cfg(feature = "tables")]
+use crate::{alg::Alg, prune::PruneTable};
+#[cfg(feature = "tables")]
+use alloc::vec::Vec;
+
+/// Corner slot of DBL, which no move touches.
+const DBL: usize = 6;
+
+/// The moves of the 2x2: the turns of U, R and F.
+pub const POCKET_MOVES: [Move; 9] = [
+    Move::U,
+    Move::U2,
+    Move::Ui,
+    Move::R,
+    Move::R2,
+    Move::Ri,
+    Move::F,
+    Move::F2,
+    Move::Fi,
+];
+
+const U: Cube = crate::cycles!(corners: (URF UFL ULB UBR));
+const R: Cube = crate::cycles!(corners: (FRD URF BRU DRB));
+const F: Cube = crate::cycles!(corners: (URF RDF DLF LUF));
+
+/// The powers of a quarter turn.
+const fn powers(q: Cube) -> [PocketCube; 3] {
+    let q2 = q.const_compose(&q);
+    [
+        PocketCube(q),
+        PocketCube(q2),
+        PocketCube(q2.const_compose(&q)),
+    ]
+}
+
+static MOVE_STATES: [[PocketCube; 3]; 3] = [powers(U), powers(R), powers(F)];
+
+/// A 2x2 state: a `Cube` whose edges are always solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PocketCube(Cube);
+
+impl PocketCube {
+    /// The corners of a 3x3 state, seen with DBL in place. `None` if DBL
+    /// is not.
+    pub fn from_cube(cube: &Cube) -> Option<Self> {
+        let dbl = cube.corners()[DBL].0;
+        if dbl != CORNERS.pack(DBL as u8, 0) {
+            return None;
+        }
+        let mut c = Cube::identity();
+        c.corners_mut().copy_from_slice(cube.corners());
+        Some(Self(c))
+    }
+
+    /// As a 3x3 state with solved edges.
+    pub fn cube(&self) -> &Cube {
+        &self.0
+    }
+}
+
+impl Puzzle for PocketCube {
+    type Move = Move;
+    type Coord = PocketCoord;
+
+    fn identity() -> Self {
+        Self(Cube::identity())
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Self(self.0.compose(&other.0))
+    }
+
+    fn invert(&self) -> Self {
+        Self(self.0.invert())
+    }
+
+    fn moves() -> &'static [Move] {
+        &POCKET_MOVES
+    }
+
+    /// Panics on a move of D, L or B.
+    fn move_state(m: Move) -> Self {
+        MOVE_STATES[m.face() as usize][m.power() as usize - 1]
+    }
+
+    fn move_axis_layer(m: Move) -> (u8, u8) {
+        Cube::move_axis_layer(m)
+    }
+}
+
+/// All of a 2x2 state: the permutation of the seven free corners, then
+/// the twist of the first six.
+pub struct PocketCoord;
+
+/// The corner slots that move.
+const FREE: [usize; 7] = [0, 1, 2, 3, 4, 5, 7];
+
+impl Coordinate<PocketCube> for PocketCoord {
+    const SIZE: usize = 5040 * 729;
+
+    fn rank(p: &PocketCube) -> usize {
+        let corners = p.0.corners();
+        let mut pieces = [0; 7];
+        let mut twist = 0;
+        for (i, &slot) in FREE.iter().enumerate() {
+            let piece = CORNERS.piece(corners[slot].0);
+            pieces[i] = if piece as usize == 7 { 6 } else { piece };
+            if i < 6 {
+                twist = twist * 3 + CORNERS.ori(corners[slot].0) as usize;
+            }
+        }
+        rank_perm(&pieces) * 729 + twist
+    }
+
+    fn unrank(c: usize) -> PocketCube {
+        let mut pieces = [0; 7];
+        unrank_perm(c / 729, &mut pieces);
+        let mut oris = [0; 7];
+        let mut twist = c % 729;
+        for i in (0..6).rev() {
+            oris[i] = (twist % 3) as u8;
+            twist /= 3;
+        }
+        oris[6] = (3 - oris[..6].iter().sum::<u8>() % 3) % 3;
+        let mut cube = Cube::identity();
+        let corners = cube.corners_mut();
+        for (i, &slot) in FREE.iter().enumerate() {
+            let piece = if pieces[i] == 6 { 7 } else { pieces[i] };
+            corners[slot] = Corner(CORNERS.pack(piece, oris[i]));
+        }
+        PocketCube(cube)
+    }
+}
+
+/// The distance of every 2x2 state, in face turns.
+#[cfg(feature = "tables")]
+pub struct PocketTable {
+    dist: PruneTable,
+}
+
+#[cfg(feature = "tables")]
+impl PocketTable {
+    /// Breadth-first search over all states; takes a second or two.
+    pub fn build() -> Self {
+        Self {
+            dist: PruneTable::build::<PocketCube, PocketCoord>(),
+        }
+    }
+
+    pub fn distance(&self, p: &PocketCube) -> u32 {
+        self.dist.distance(PocketCoord::rank(p)) as u32
+    }
+
+    /// A shortest solution, following the table down.
+    pub fn solve(&self, p: &PocketCube) -> Alg {
+        let mut alg = Alg::new();
+        let mut p = *p;
+        let mut d = self.distance(&p);
+        while d > 0 {
+            let (m, next) = POCKET_MOVES
+                .iter()
+                .map(|&m| (m, p.apply(m)))
+                .find(|(_, q)| self.distance(q) < d)
+                .expect("a move toward solved");
+            alg.push(m);
+            p = next;
+            d -= 1;
+        }
+        alg
+    }
+
+    /// Number of states at each distance: 1, 9, 54, ... up to 11 moves.
+    pub fn depth_counts(&self) -> Vec<usize> {
+        self.dist.depth_counts()
+    }
+}
+
+#[cfg(all(test, feature = "tables", feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_counts_and_solves_every_state() {
+        let table = PocketTable::build();
+        let counts = table.depth_counts();
+        assert_eq!(
+            counts,
+            [
+                1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748,
+                623800, 2644
+            ]
+        );
+        assert_eq!(counts.iter().sum::<usize>(), 3_674_160);
+
+        let mut rng = crate::random::Rng::new(1);
+        for _ in 0..20 {
+            let c = rng.below(PocketCoord::SIZE as u64) as usize;
+            let p = PocketCoord::unrank(c);
+            assert_eq!(PocketCoord::rank(&p), c);
+            assert_eq!(PocketCube::from_cube(p.cube()), Some(p));
+            let alg = table.solve(&p);
+            assert_eq!(alg.len() as u32, table.distance(&p));
+            let solved = alg.moves().iter().fold(p, |p, &m| p.apply(m));
+            assert!(solved.is_solved());
+        }
+    }
+}
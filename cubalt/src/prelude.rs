@@ -0,0 +1,12 @@
+//! The common types and traits: `use cubalt::prelude::*;`.
+pub use crate::alg::Alg;
+#[cfg(feature = "solver")]
+pub use crate::context::CubeContext;
+pub use crate::coord::Coordinate;
+pub use crate::cube::Cube;
+pub use crate::notation::{Face, Move};
+pub use crate::puzzle::Puzzle;
+#[cfg(feature = "solver")]
+pub use crate::search::Heuristic;
+#[cfg(feature = "solver")]
+pub use crate::solver::Solver;
@@ -0,0 +1,267 @@
+//! Pruning tables: exact distances in coordinate space.
+use crate::coord::Coordinate;
+use crate::movetable::MoveTable;
+use crate::puzzle::Puzzle;
+use alloc::{vec, vec::Vec};
+
+/// Distance to solved of every value of a coordinate, under the puzzle's
+/// full move set. Any such distance is a lower bound on the distance of a
+/// state with that coordinate.
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct PruneTable {
+    dist: Vec<u8>,
+}
+
+impl PruneTable {
+    /// Breadth-first search over coordinate `C` of puzzle `P`.
+    pub fn build<P: Puzzle, C: Coordinate<P>>() -> Self {
+        Self::build_with_moves::<P, C>(P::moves())
+    }
+
+    /// As `build`, but moving only with `moves`. Values unreachable with
+    /// them are left at `u8::MAX`.
+    pub fn build_with_moves<P: Puzzle, C: Coordinate<P>>(
+        moves: &[P::Move],
+    ) -> Self {
+        trace_span!(
+            "prune_table",
+            coord = core::any::type_name::<C>(),
+            size = C::SIZE
+        );
+        let mut dist = vec![u8::MAX; C::SIZE];
+        let start = C::rank(&P::identity());
+        dist[start] = 0;
+        let mut frontier = vec![start];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            for &c in frontier.iter() {
+                let p = C::unrank(c);
+                for &m in moves.iter() {
+                    let d = C::rank(&p.apply(m));
+                    if dist[d] == u8::MAX {
+                        dist[d] = depth + 1;
+                        next.push(d);
+                    }
+                }
+            }
+            trace_event!(depth = depth + 1, states = next.len());
+            frontier = next;
+            depth += 1;
+        }
+        Self { dist }
+    }
+
+    pub fn distance(&self, c: usize) -> u8 {
+        self.dist[c]
+    }
+
+    pub fn len(&self) -> usize {
+        self.dist.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dist.is_empty()
+    }
+
+    /// Number of coordinate values at each distance.
+    pub fn depth_counts(&self) -> Vec<usize> {
+        let mut counts = Vec::new();
+        for &d in self.dist.iter().filter(|&&d| d != u8::MAX) {
+            if counts.len() <= d as usize {
+                counts.resize(d as usize + 1, 0);
+            }
+            counts[d as usize] += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(feature = "std")]
+impl PruneTable {
+    /// Little-endian: the number of entries as a u32, then the distances
+    /// as bytes.
+    pub fn write_to<W: std::io::Write>(&self, mut w: W) -> std::io::Result<()> {
+        w.write_all(&(self.dist.len() as u32).to_le_bytes())?;
+        w.write_all(&self.dist)
+    }
+
+    /// Read a table written by `write_to`, checking that it has `size`
+    /// entries.
+    pub fn read_from<R: std::io::Read>(
+        mut r: R,
+        size: usize,
+    ) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind};
+        let mut word = [0; 4];
+        r.read_exact(&mut word)?;
+        let len = u32::from_le_bytes(word) as usize;
+        if len != size {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("prune table has {} entries, expected {}", len, size),
+            ));
+        }
+        let mut dist = vec![0; len];
+        r.read_exact(&mut dist)?;
+        Ok(Self { dist })
+    }
+}
+
+/// Storage of a table's distances.
+trait Distances {
+    const UNSEEN: u8;
+
+    fn get(&self, c: usize) -> u8;
+
+    fn set(&mut self, c: usize, d: u8);
+}
+
+impl Distances for Vec<u8> {
+    const UNSEEN: u8 = u8::MAX;
+
+    fn get(&self, c: usize) -> u8 {
+        self[c]
+    }
+
+    fn set(&mut self, c: usize, d: u8) {
+        self[c] = d;
+    }
+}
+
+/// Breadth-first search over the product of coordinates `A` and `B`,
+/// `A`-major, from the move tables of each: a sweep of `dist` per depth
+/// rather than a frontier, so that nothing but `dist` grows with the
+/// product.
+fn fill_product<P: Puzzle, A: Coordinate<P>, B: Coordinate<P>, D: Distances>(
+    moves: &[P::Move],
+    dist: &mut D,
+) {
+    let a_moves = MoveTable::build_with_moves::<P, A>(moves);
+    let b_moves = MoveTable::build_with_moves::<P, B>(moves);
+    let start = A::rank(&P::identity()) * B::SIZE + B::rank(&P::identity());
+    dist.set(start, 0);
+    let mut depth = 0;
+    loop {
+        let mut found = 0usize;
+        for c in 0..A::SIZE * B::SIZE {
+            if dist.get(c) != depth {
+                continue;
+            }
+            let (a, b) = (c / B::SIZE, c % B::SIZE);
+            for m in 0..moves.len() {
+                let d = a_moves.get(a, m) * B::SIZE + b_moves.get(b, m);
+                if dist.get(d) == D::UNSEEN {
+                    dist.set(d, depth + 1);
+                    found += 1;
+                }
+            }
+        }
+        trace_event!(depth = depth + 1, states = found);
+        if found == 0 {
+            break;
+        }
+        depth += 1;
+    }
+}
+
+impl PruneTable {
+    /// As `build_with_moves`, for the product of coordinates `A` and `B`
+    /// indexed `A`-major, too large to rank and unrank value by value.
+    pub fn build_product<P: Puzzle, A: Coordinate<P>, B: Coordinate<P>>(
+        moves: &[P::Move],
+    ) -> Self {
+        trace_span!("prune_table", size = A::SIZE * B::SIZE);
+        let mut dist = vec![u8::MAX; A::SIZE * B::SIZE];
+        fill_product::<P, A, B, _>(moves, &mut dist);
+        Self { dist }
+    }
+}
+
+/// A pruning table at half the size: two distances to a byte, each at
+/// most 14.
+pub struct NibbleTable {
+    len: usize,
+    nibbles: Vec<u8>,
+}
+
+impl Distances for NibbleTable {
+    const UNSEEN: u8 = 15;
+
+    fn get(&self, c: usize) -> u8 {
+        self.nibbles[c / 2] >> (c % 2 * 4) & 15
+    }
+
+    fn set(&mut self, c: usize, d: u8) {
+        assert!(d < 15, "distance {} does not fit a nibble", d);
+        let shift = c % 2 * 4;
+        let byte = &mut self.nibbles[c / 2];
+        *byte = *byte & !(15 << shift) | d << shift;
+    }
+}
+
+impl NibbleTable {
+    /// As `PruneTable::build_product`. Panics if a distance exceeds 14.
+    pub fn build_product<P: Puzzle, A: Coordinate<P>, B: Coordinate<P>>(
+        moves: &[P::Move],
+    ) -> Self {
+        trace_span!("nibble_table", size = A::SIZE * B::SIZE);
+        let len = A::SIZE * B::SIZE;
+        let mut table = Self {
+            len,
+            nibbles: vec![0xff; len.div_ceil(2)],
+        };
+        fill_product::<P, A, B, _>(moves, &mut table);
+        table
+    }
+
+    /// The distance of value `c`, 15 if unreachable.
+    pub fn distance(&self, c: usize) -> u8 {
+        self.get(c)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Zero-copy loading. An archive is validated in place and then read where
+/// it lies, so a table in a memory-mapped file needs one pass over it to
+/// check it and none to decode it.
+#[cfg(feature = "rkyv")]
+impl PruneTable {
+    /// The table in rkyv's archived format.
+    pub fn to_archived_bytes(&self) -> rkyv::util::AlignedVec {
+        rkyv::to_bytes::<rkyv::rancor::Error>(self).expect("serializing a Vec")
+    }
+
+    /// The table archived in `bytes` by `to_archived_bytes`. The bytes
+    /// must be 16-byte aligned, as mapped files and `AlignedVec` are.
+    pub fn access_archived(
+        bytes: &[u8],
+    ) -> Result<&ArchivedPruneTable, rkyv::rancor::Error> {
+        rkyv::access::<ArchivedPruneTable, rkyv::rancor::Error>(bytes)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedPruneTable {
+    pub fn distance(&self, c: usize) -> u8 {
+        self.dist[c]
+    }
+
+    pub fn len(&self) -> usize {
+        self.dist.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dist.is_empty()
+    }
+}
@@ -0,0 +1,280 @@
+//! Puzzle-agnostic interface for searches and table builders.
+use crate::coord::{Coordinate, Corners};
+use crate::cube::Cube;
+#[cfg(feature = "std")]
+use crate::cube4::{Cube4, LayerMove};
+#[cfg(feature = "std")]
+use crate::cube5::Cube5;
+use crate::notation::Move;
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// A permutation puzzle: a group of states generated by a set of moves.
+pub trait Puzzle: Copy + Eq + Debug {
+    type Move: Copy + Eq + Debug + 'static;
+
+    /// The coordinate tables over the whole puzzle are built on: all of the
+    /// state if it fits, else the corners.
+    type Coord: Coordinate<Self>;
+
+    fn identity() -> Self;
+    fn compose(&self, other: &Self) -> Self;
+    fn invert(&self) -> Self;
+
+    /// All generator moves.
+    fn moves() -> &'static [Self::Move];
+
+    /// The state reached by applying `m` to the identity.
+    fn move_state(m: Self::Move) -> Self;
+
+    /// Axis and layer of a move. Moves on the same axis commute, and two
+    /// moves on the same axis and layer merge into one (or cancel).
+    fn move_axis_layer(m: Self::Move) -> (u8, u8);
+
+    /// Number of symmetries, including the identity (always index 0).
+    fn sym_count() -> usize {
+        1
+    }
+
+    /// Conjugate by symmetry `s`: S * self * S^-1.
+    fn sym_conjugate(&self, s: usize) -> Self {
+        debug_assert_eq!(s, 0);
+        *self
+    }
+
+    fn apply(&self, m: Self::Move) -> Self {
+        self.compose(&Self::move_state(m))
+    }
+
+    fn is_solved(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// `Self::Coord` of this state.
+    fn coord(&self) -> usize {
+        Self::Coord::rank(self)
+    }
+}
+
+impl Puzzle for Cube {
+    type Move = Move;
+    type Coord = Corners;
+
+    fn identity() -> Self {
+        Cube::identity()
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Cube::compose(self, other)
+    }
+
+    fn invert(&self) -> Self {
+        Cube::invert(self)
+    }
+
+    fn moves() -> &'static [Move] {
+        &Move::ALL
+    }
+
+    fn move_state(m: Move) -> Self {
+        *m.cube()
+    }
+
+    fn move_axis_layer(m: Move) -> (u8, u8) {
+        let face = m.face() as u8;
+        (face % 3, face / 3)
+    }
+
+    fn sym_count() -> usize {
+        48
+    }
+
+    fn sym_conjugate(&self, s: usize) -> Self {
+        Cube::sym_conjugate(self, s)
+    }
+}
+
+#[cfg(feature = "std")]
+fn cube4_moves() -> &'static [Cube4; 36] {
+    static MOVES: OnceLock<[Cube4; 36]> = OnceLock::new();
+    MOVES.get_or_init(Cube4::moves)
+}
+
+#[cfg(feature = "std")]
+fn cube5_moves() -> &'static [Cube5; 36] {
+    static MOVES: OnceLock<[Cube5; 36]> = OnceLock::new();
+    MOVES.get_or_init(Cube5::moves)
+}
+
+#[cfg(feature = "std")]
+impl Puzzle for Cube4 {
+    type Move = LayerMove;
+    type Coord = BigCorners;
+
+    fn identity() -> Self {
+        Cube4::identity()
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Cube4::compose(self, other)
+    }
+
+    fn invert(&self) -> Self {
+        Cube4::invert(self)
+    }
+
+    fn moves() -> &'static [LayerMove] {
+        &LayerMove::ALL
+    }
+
+    fn move_state(m: LayerMove) -> Self {
+        cube4_moves()[m.index()]
+    }
+
+    fn move_axis_layer(m: LayerMove) -> (u8, u8) {
+        m.axis_layer()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Puzzle for Cube5 {
+    type Move = LayerMove;
+    type Coord = BigCorners;
+
+    fn identity() -> Self {
+        Cube5::identity()
+    }
+
+    fn compose(&self, other: &Self) -> Self {
+        Cube5::compose(self, other)
+    }
+
+    fn invert(&self) -> Self {
+        Cube5::invert(self)
+    }
+
+    fn moves() -> &'static [LayerMove] {
+        &LayerMove::ALL
+    }
+
+    fn move_state(m: LayerMove) -> Self {
+        cube5_moves()[m.index()]
+    }
+
+    fn move_axis_layer(m: LayerMove) -> (u8, u8) {
+        m.axis_layer()
+    }
+}
+
+/// The corners of a big cube, ranked as `Corners` ranks a 3x3's.
+#[cfg(feature = "std")]
+pub struct BigCorners;
+
+#[cfg(feature = "std")]
+impl Coordinate<Cube4> for BigCorners {
+    const SIZE: usize = Corners::SIZE;
+
+    fn rank(p: &Cube4) -> usize {
+        Corners::rank(&p.corners)
+    }
+
+    fn unrank(c: usize) -> Cube4 {
+        Cube4 {
+            corners: Corners::unrank(c),
+            ..Cube4::identity()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Coordinate<Cube5> for BigCorners {
+    const SIZE: usize = Corners::SIZE;
+
+    fn rank(p: &Cube5) -> usize {
+        Corners::rank(&p.outer)
+    }
+
+    fn unrank(c: usize) -> Cube5 {
+        Cube5 {
+            outer: Corners::unrank(c),
+            ..Cube5::identity()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Along a random walk, `P::Coord` round-trips and the coordinate after
+    /// a move depends only on the coordinate before it.
+    #[cfg(feature = "std")]
+    fn coord_follows_moves<P: Puzzle>(seed: u64) {
+        let mut rng = crate::random::Rng::new(seed);
+        let mut p = P::identity();
+        for _ in 0..100 {
+            let c = p.coord();
+            assert!(c < P::Coord::SIZE);
+            let q = P::Coord::unrank(c);
+            assert_eq!(q.coord(), c);
+            let m = *rng.choose(P::moves());
+            assert_eq!(q.apply(m).coord(), p.apply(m).coord());
+            p = p.apply(m);
+        }
+    }
+
+    /// The contract of `move_axis_layer`: moves on one axis commute, and
+    /// two on one axis and layer make one such move or none. No move needs
+    /// more than four turns to undo.
+    fn moves_fit_their_axes<P: Puzzle>() {
+        let moves = P::moves();
+        for &a in moves {
+            let (x, place) = (P::move_state(a), P::move_axis_layer(a));
+            let x4 = (0..3).fold(x, |c, _| c.compose(&x));
+            assert!(x4.is_solved(), "{:?}", a);
+            for &b in moves {
+                let y = P::move_state(b);
+                let (axis, layer) = P::move_axis_layer(b);
+                if axis != place.0 {
+                    continue;
+                }
+                let xy = x.compose(&y);
+                assert_eq!(xy, y.compose(&x), "{:?} {:?}", a, b);
+                if layer == place.1 {
+                    let merged = xy.is_solved()
+                        || moves.iter().any(|&m| {
+                            P::move_axis_layer(m) == place
+                                && P::move_state(m) == xy
+                        });
+                    assert!(merged, "{:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn moves_fit_their_axes_on_every_puzzle() {
+        moves_fit_their_axes::<Cube>();
+        moves_fit_their_axes::<crate::pocket::PocketCube>();
+        moves_fit_their_axes::<crate::axial::AxialCube>();
+        #[cfg(feature = "std")]
+        moves_fit_their_axes::<Cube4>();
+        #[cfg(feature = "std")]
+        moves_fit_their_axes::<Cube5>();
+        #[cfg(feature = "solver")]
+        moves_fit_their_axes::<crate::domino::Domino>();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn coordinates_follow_moves() {
+        coord_follows_moves::<Cube>(1);
+        coord_follows_moves::<Cube4>(2);
+        coord_follows_moves::<Cube5>(3);
+        coord_follows_moves::<crate::pocket::PocketCube>(4);
+        coord_follows_moves::<crate::axial::AxialCube>(5);
+        #[cfg(feature = "solver")]
+        coord_follows_moves::<crate::domino::Domino>(6);
+    }
+}
@@ -0,0 +1,92 @@
+//! Small, seedable pseudo-random generator.
+//!
+//! Scrambles and sampling experiments need reproducibility more than
+//! statistical strength, so this is SplitMix64 rather than a dependency.
+use crate::cube::Cube;
+use std::convert::Infallible;
+
+const GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// SplitMix64. Equal seeds give equal streams on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seeded from the clock and the address of a local; not reproducible.
+    pub fn from_entropy() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        let local = 0u8;
+        Self::new(nanos ^ (&local as *const u8 as u64).rotate_left(32))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Skip the next `n` outputs, in constant time.
+    pub fn jump(&mut self, n: u64) {
+        self.state = self.state.wrapping_add(n.wrapping_mul(GAMMA));
+    }
+
+    /// Uniform in 0..n, without modulo bias. `n` must be nonzero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        debug_assert!(n != 0);
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// A uniformly chosen element of a non-empty slice.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand::RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = Rng::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// A uniformly random reachable 3x3 state.
+pub fn random_cube(rng: &mut Rng) -> Cube {
+    let result: Result<Cube, Infallible> =
+        Cube::from_choices(|n| Ok(rng.below(n as u64) as u8));
+    match result {
+        Ok(cube) => cube,
+        Err(e) => match e {},
+    }
+}
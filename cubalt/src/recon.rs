@@ -0,0 +1,126 @@
+//! Reconstructions of timed human solves, split into method stages.
+use crate::alg::Alg;
+use crate::notation::Move;
+use crate::steps::Method;
+use std::fmt;
+use std::time::Duration;
+
+/// The part of a solve spent on one stage.
+#[derive(Debug, Clone)]
+pub struct Phase {
+    pub name: &'static str,
+    pub alg: Alg,
+    /// Time of the last move of the previous phase (zero for the first).
+    pub start: Duration,
+    /// Time of the last move of this phase.
+    pub end: Duration,
+    /// False if the solve ended before the stage was done.
+    pub complete: bool,
+}
+
+impl Phase {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Moves per second, if the phase took any time.
+    pub fn tps(&self) -> Option<f64> {
+        let secs = self.duration().as_secs_f64();
+        if secs > 0.0 {
+            Some(self.alg.len() as f64 / secs)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Reconstruction {
+    /// The rotation (an even symmetry index) the solver held the cube in,
+    /// judged by where the first stage was built.
+    pub orientation: usize,
+    pub phases: Vec<Phase>,
+}
+
+impl Reconstruction {
+    pub fn total_moves(&self) -> usize {
+        self.phases.iter().map(|p| p.alg.len()).sum()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.phases.last().map_or(Duration::ZERO, |p| p.end)
+    }
+}
+
+impl fmt::Display for Reconstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for p in self.phases.iter() {
+            write!(
+                f,
+                "{} // {} ({} moves, {:.2}s)",
+                p.alg,
+                p.name,
+                p.alg.len(),
+                p.duration().as_secs_f64()
+            )?;
+            if !p.complete {
+                write!(f, " incomplete")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a solve into the stages of `method`. Move times are measured
+/// from the start of the solve.
+///
+/// The cube may be held in any orientation; the one in which the first
+/// stage is finished soonest is used for every stage.
+pub fn analyze(
+    method: &Method,
+    scramble: &Alg,
+    moves: &[(Move, Duration)],
+) -> Reconstruction {
+    let mut states = Vec::with_capacity(moves.len() + 1);
+    states.push(scramble.cube());
+    for (m, _) in moves.iter() {
+        let next = states.last().unwrap().compose(m.cube());
+        states.push(next);
+    }
+    let first_done = |stage: usize, sym: usize, from: usize| {
+        (from..states.len())
+            .find(|&i| method.stages[stage].is_done(&states[i], sym))
+    };
+
+    let orientation = (0..24)
+        .map(|r| 2 * r)
+        .min_by_key(|&sym| first_done(0, sym, 0).unwrap_or(usize::MAX))
+        .unwrap();
+
+    let time_at = |i: usize| {
+        if i == 0 {
+            Duration::ZERO
+        } else {
+            moves[i - 1].1
+        }
+    };
+    let mut phases = Vec::new();
+    let mut from = 0;
+    for (k, stage) in method.stages.iter().enumerate() {
+        let done = first_done(k, orientation, from);
+        let to = done.unwrap_or(moves.len());
+        phases.push(Phase {
+            name: stage.name,
+            alg: Alg(moves[from..to].iter().map(|&(m, _)| m).collect()),
+            start: time_at(from),
+            end: time_at(to),
+            complete: done.is_some(),
+        });
+        from = to;
+    }
+    Reconstruction {
+        orientation,
+        phases,
+    }
+}
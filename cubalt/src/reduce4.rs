@@ -0,0 +1,332 @@
+//! 4x4 solutions by reduction: centers, then wing pairing, then parity,
+//! then the two-phase solver on the 3x3 that is left.
+//!
+//! Centers and edges are solved greedily, as a person solves them: each
+//! step takes the first sequence that solves more pieces without undoing
+//! any, trying a few plain moves, then a commutator or a slice-flip-slice
+//! set up by a few moves. These stages need no tables and take a
+//! fraction of a second, but their solutions are long: some 50 moves for
+//! the centers and 55 more for the edges.
+use crate::alg::Alg;
+use crate::canonical::is_canonical;
+use crate::cube4::{Cube4, LayerMove};
+use crate::layout::packed3::EDGES;
+use crate::notation::{Face, Move};
+use crate::puzzle::Puzzle;
+use crate::rotation::CubeRotation;
+use crate::solver::Solver;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Flips the dedge in slot UF, leaving the rest reduced (OLL parity).
+const FLIP_PARITY: &str = "r2 B2 U2 l U2 r' U2 r U2 F2 r F2 l' B2 r2";
+
+/// Swaps the dedges in slots UF and UB, leaving the rest reduced (PLL
+/// parity).
+const SWAP_PARITY: &str = "r2 U2 r2 U2 u2 r2 u2";
+
+/// Outer turns to go between a slice and its undoing, besides the short
+/// ones: the flip that pairs an edge, and the last-two-edges swap, as in
+/// `u R U R' F R' F' R u'` and `d R F' U R' F d'`. They are tried in
+/// every orientation, and undone.
+const SLICE_FLIPS: [&str; 2] = ["R U R' F R' F' R", "R F' U R' F"];
+
+/// Time the two-phase solver gets to shorten the 3x3 stage.
+const FINISH_TIME: Duration = Duration::from_millis(100);
+
+fn parse(s: &str) -> Vec<LayerMove> {
+    s.split_whitespace()
+        .map(|m| m.parse().expect("built-in algs parse"))
+        .collect()
+}
+
+fn apply(cube: &Cube4, moves: &[LayerMove]) -> Cube4 {
+    moves.iter().fold(*cube, |c, &m| c.apply(m))
+}
+
+fn inverse(moves: &[LayerMove]) -> Vec<LayerMove> {
+    moves.iter().rev().map(|m| m.inverse()).collect()
+}
+
+/// `moves` seen after `rotation`, as `CubeRotation::map_alg`.
+fn rotate(rotation: CubeRotation, moves: &[LayerMove]) -> Vec<LayerMove> {
+    let face = |f: Face| rotation.map_move(Move::new(f, 1)).face();
+    moves
+        .iter()
+        .map(|m| LayerMove::new(face(m.face()), m.is_inner(), m.power()))
+        .collect()
+}
+
+/// The canonical sequences of `moves` of at most `max_len`, shortest
+/// first, starting with the empty one.
+fn sequences(moves: &[LayerMove], max_len: usize) -> Vec<Vec<LayerMove>> {
+    let mut all = vec![Vec::new()];
+    let mut last = 0;
+    for _ in 0..max_len {
+        let longest = all.len();
+        for i in last..longest {
+            for &m in moves {
+                if is_canonical::<Cube4>(all[i].last().copied(), m) {
+                    let mut s = all[i].clone();
+                    s.push(m);
+                    all.push(s);
+                }
+            }
+        }
+        last = longest;
+    }
+    all
+}
+
+/// Number of centers on the face of their color.
+fn centers_solved(cube: &Cube4) -> u32 {
+    let c = cube.centers.as_bytes();
+    (0..24).filter(|&i| c[i] / 4 == i as u8 / 4).count() as u32
+}
+
+/// Number of edge slots holding a pair, if the centers are reduced.
+fn edges_paired(cube: &Cube4) -> Option<u32> {
+    if !cube.centers_reduced() {
+        return None;
+    }
+    let w = cube.wings.as_bytes();
+    let paired = (0..12).filter(|&e| {
+        w[2 * e] / 2 == w[2 * e + 1] / 2 && w[2 * e] != w[2 * e + 1]
+    });
+    Some(paired.count() as u32)
+}
+
+/// The moves of each stage of a reduction solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reduction {
+    pub centers: Vec<LayerMove>,
+    pub edges: Vec<LayerMove>,
+    /// Empty, or one or both parity algs.
+    pub parity: Vec<LayerMove>,
+    pub finish: Alg,
+}
+
+impl Reduction {
+    /// The whole solution, the stages one after another.
+    pub fn moves(&self) -> Vec<LayerMove> {
+        let mut moves = self.centers.clone();
+        moves.extend_from_slice(&self.edges);
+        moves.extend_from_slice(&self.parity);
+        moves.extend(self.finish.moves().iter().map(|&m| LayerMove::from(m)));
+        moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.centers.len()
+            + self.edges.len()
+            + self.parity.len()
+            + self.finish.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The sequences a greedy stage tries: each setup alone, then each macro
+/// conjugated by each setup.
+struct Stage {
+    setups: Vec<Vec<LayerMove>>,
+    macros: Vec<Vec<LayerMove>>,
+}
+
+impl Stage {
+    /// Moves taking `cube` to `target` by `score`, which gives `None` for
+    /// states that undo an earlier stage; `None` if a step finds nothing.
+    fn solve(
+        &self,
+        cube: &mut Cube4,
+        score: impl Fn(&Cube4) -> Option<u32>,
+        target: u32,
+    ) -> Option<Vec<LayerMove>> {
+        let mut moves = Vec::new();
+        let mut current = score(cube)?;
+        while current < target {
+            let here = *cube;
+            let better = |c: &Cube4| score(c).is_some_and(|s| s > current);
+            let step = self
+                .setups
+                .iter()
+                .find(|s| better(&apply(&here, s)))
+                .cloned()
+                .or_else(|| {
+                    self.setups.iter().find_map(|s| {
+                        let undo = inverse(s);
+                        let start = apply(&here, s);
+                        self.macros.iter().find_map(|m| {
+                            let end = apply(&apply(&start, m), &undo);
+                            better(&end)
+                                .then(|| [&s[..], &m[..], &undo[..]].concat())
+                        })
+                    })
+                })?;
+            *cube = apply(&here, &step);
+            current = score(cube)?;
+            moves.extend(step);
+        }
+        Some(moves)
+    }
+}
+
+/// Solves 4x4s by reduction. Building one enumerates the sequences its
+/// stages try, which is quick; the 3x3 solver's tables are shared.
+#[derive(Clone)]
+pub struct ReductionSolver {
+    solver: Solver,
+    centers: Arc<Stage>,
+    edges: Arc<Stage>,
+}
+
+impl Default for ReductionSolver {
+    fn default() -> Self {
+        Self::new(Solver::shared().clone())
+    }
+}
+
+impl ReductionSolver {
+    pub fn new(solver: Solver) -> Self {
+        let all = &LayerMove::ALL;
+        let inner: Vec<LayerMove> =
+            all.iter().copied().filter(|m| m.is_inner()).collect();
+        let outer: Vec<LayerMove> =
+            all.iter().copied().filter(|m| !m.is_inner()).collect();
+
+        // Commutators of a slice and a turn on another axis, which cycle
+        // a few centers.
+        let mut center_macros = Vec::new();
+        for &a in inner.iter() {
+            for &b in all.iter() {
+                if a.axis_layer().0 != b.axis_layer().0 {
+                    center_macros.push(vec![a, b, a.inverse(), b.inverse()]);
+                }
+            }
+        }
+
+        // A slice, outer turns, and the slice undone: these swap wings
+        // between edges, and keep the centers if each face the slice
+        // disturbs turns back as far as it turned.
+        let mut turns = sequences(&outer, 3);
+        turns.remove(0);
+        for x in SLICE_FLIPS.iter().map(|x| parse(x)) {
+            for r in CubeRotation::all() {
+                for y in [rotate(r, &x), inverse(&rotate(r, &x))] {
+                    if !turns.contains(&y) {
+                        turns.push(y);
+                    }
+                }
+            }
+        }
+        let identity = Cube4::identity();
+        let mut edge_macros = Vec::new();
+        for &a in inner.iter() {
+            for x in turns.iter() {
+                let m = [&[a][..], &x[..], &[a.inverse()][..]].concat();
+                if apply(&identity, &m).centers_reduced() {
+                    edge_macros.push(m);
+                }
+            }
+        }
+
+        Self {
+            solver,
+            centers: Arc::new(Stage {
+                setups: sequences(all, 2),
+                macros: center_macros,
+            }),
+            edges: Arc::new(Stage {
+                setups: sequences(&outer, 2),
+                macros: edge_macros,
+            }),
+        }
+    }
+
+    /// A solution of `cube`, or `None` if a greedy step finds no way on,
+    /// which the sequences tried are chosen to make rare. Solved means
+    /// solved to the eye: centers of a color may be in any order.
+    pub fn solve(&self, cube: &Cube4) -> Option<Reduction> {
+        let mut c = *cube;
+        let centers_score = |c: &Cube4| Some(centers_solved(c));
+        let centers = self.centers.solve(&mut c, centers_score, 24)?;
+        let edges = self.edges.solve(&mut c, edges_paired, 12)?;
+
+        let mut parity = Vec::new();
+        let reduced = c.to_reduced().expect("centers and edges are done");
+        let flip: u32 =
+            reduced.edges().iter().map(|e| EDGES.ori(e.0) as u32).sum();
+        if !flip.is_multiple_of(2) {
+            parity.extend(parse(FLIP_PARITY));
+        }
+        if reduced.parity() {
+            parity.extend(parse(SWAP_PARITY));
+        }
+        c = apply(&c, &parity);
+
+        let reduced = c.to_reduced().expect("parity algs keep reduction");
+        // The deadline only shortens the solution: on a slow machine the
+        // first one may take longer.
+        let finish = self
+            .solver
+            .solve_with_deadline(&reduced, FINISH_TIME)
+            .or_else(|| self.solver.solve(&reduced, 30))?;
+        Some(Reduction {
+            centers,
+            edges,
+            parity,
+            finish,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::random::Rng;
+
+    /// Checks that `cube` is solved to the eye by `solver`'s solution, and
+    /// returns the solution.
+    fn solve_and_check(solver: &ReductionSolver, cube: &Cube4) -> Reduction {
+        let r = solver.solve(cube).expect("a reduction");
+        let solved = apply(cube, &r.moves());
+        assert_eq!(solved.to_reduced(), Some(Cube::identity()));
+        r
+    }
+
+    #[test]
+    fn solves_random_scrambles() {
+        let solver = ReductionSolver::default();
+        let mut rng = Rng::new(1);
+        for _ in 0..5 {
+            let scramble: Vec<LayerMove> =
+                (0..40).map(|_| *rng.choose(&LayerMove::ALL)).collect();
+            let r =
+                solve_and_check(&solver, &apply(&Cube4::identity(), &scramble));
+            assert!(r.finish.len() <= 30);
+        }
+    }
+
+    #[test]
+    fn fixes_parities() {
+        let solver = ReductionSolver::default();
+        let (flip, swap) = (parse(FLIP_PARITY), parse(SWAP_PARITY));
+        let both = [&flip[..], &swap[..]].concat();
+        let moved_flip = [&parse("F R")[..], &flip, &parse("R' F'")].concat();
+        let cases = [
+            (flip.clone(), &flip),
+            (swap.clone(), &swap),
+            (both.clone(), &both),
+            (moved_flip, &flip),
+            (parse("R U R' F2 D"), &vec![]),
+        ];
+        for (scramble, parity) in cases.iter() {
+            let cube = apply(&Cube4::identity(), scramble);
+            let r = solve_and_check(&solver, &cube);
+            assert_eq!(&r.parity, *parity, "{:?}", scramble);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Drawing cubes.
+use crate::facelet::{Color, Facelets};
+use std::fmt::{self, Write};
+
+/// The RGB fill of each sticker color, in U R F D L B order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorScheme(pub [[u8; 3]; 6]);
+
+impl ColorScheme {
+    /// White opposite yellow, green front, red right.
+    pub const WESTERN: Self = Self([
+        [0xff, 0xff, 0xff],
+        [0xd0, 0x00, 0x00],
+        [0x00, 0xa0, 0x00],
+        [0xff, 0xd5, 0x00],
+        [0xff, 0x80, 0x00],
+        [0x00, 0x40, 0xd0],
+    ]);
+
+    /// White opposite blue, green front opposite yellow.
+    pub const JAPANESE: Self = Self([
+        [0xff, 0xff, 0xff],
+        [0xd0, 0x00, 0x00],
+        [0x00, 0xa0, 0x00],
+        [0x00, 0x40, 0xd0],
+        [0xff, 0x80, 0x00],
+        [0xff, 0xd5, 0x00],
+    ]);
+
+    /// The Western layout in colors told apart with any common color
+    /// blindness (Okabe-Ito): no red against green.
+    pub const COLOR_BLIND: Self = Self([
+        [0xff, 0xff, 0xff],
+        [0xd5, 0x5e, 0x00],
+        [0x00, 0x9e, 0x73],
+        [0xf0, 0xe4, 0x42],
+        [0xe6, 0x9f, 0x00],
+        [0x00, 0x72, 0xb2],
+    ]);
+
+    pub fn rgb(&self, c: Color) -> [u8; 3] {
+        self.0[c as usize]
+    }
+
+    /// `#rrggbb`.
+    pub fn hex(&self, c: Color) -> String {
+        let [r, g, b] = self.rgb(c);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::WESTERN
+    }
+}
+
+/// A color scheme name that is not `western`, `japanese` or
+/// `color-blind`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorSchemeError(pub String);
+
+impl fmt::Display for ParseColorSchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown color scheme: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorSchemeError {}
+
+impl core::str::FromStr for ColorScheme {
+    type Err = ParseColorSchemeError;
+
+    fn from_str(s: &str) -> Result<Self, ParseColorSchemeError> {
+        match s {
+            "western" => Ok(Self::WESTERN),
+            "japanese" => Ok(Self::JAPANESE),
+            "color-blind" => Ok(Self::COLOR_BLIND),
+            _ => Err(ParseColorSchemeError(s.to_string())),
+        }
+    }
+}
+
+/// Grid position (column, row) of the top-left sticker of each face in
+/// the unfolded net, in U R F D L B order.
+const FACE_ORIGIN: [(usize, usize); 6] =
+    [(3, 0), (6, 3), (3, 3), (3, 6), (0, 3), (9, 3)];
+
+/// The stickers as an SVG unfolded net, `size` pixels per sticker, in
+/// the Western scheme.
+pub fn svg(f: &Facelets, size: usize) -> String {
+    svg_with(f, size, &ColorScheme::WESTERN)
+}
+
+/// `svg` in another color scheme.
+pub fn svg_with(f: &Facelets, size: usize, scheme: &ColorScheme) -> String {
+    let mut s = String::new();
+    write!(
+        s,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        12 * size,
+        9 * size
+    )
+    .unwrap();
+    for (face, &(x0, y0)) in FACE_ORIGIN.iter().enumerate() {
+        for i in 0..9 {
+            let (x, y) = (x0 + i % 3, y0 + i / 3);
+            write!(
+                s,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                 fill=\"{}\" stroke=\"#000\"/>",
+                x * size,
+                y * size,
+                size,
+                size,
+                scheme.hex(f.0[9 * face + i])
+            )
+            .unwrap();
+        }
+    }
+    s.push_str("</svg>");
+    s
+}
+
+/// The stickers as `Facelets::net` lays them out, two spaces per sticker
+/// colored with 24-bit ANSI escapes, for terminals.
+pub fn ansi(f: &Facelets, scheme: &ColorScheme) -> String {
+    let mut s = String::new();
+    let row = |s: &mut String, face: usize, r: usize| {
+        for c in 0..3 {
+            let [red, green, blue] = scheme.rgb(f.0[9 * face + 3 * r + c]);
+            write!(s, "\x1b[48;2;{};{};{}m  ", red, green, blue).unwrap();
+        }
+        s.push_str("\x1b[0m");
+    };
+    for r in 0..3 {
+        s.push_str("      ");
+        row(&mut s, 0, r);
+        s.push('\n');
+    }
+    for r in 0..3 {
+        for face in [4, 2, 1, 5] {
+            row(&mut s, face, r);
+        }
+        s.push('\n');
+    }
+    for r in 0..3 {
+        s.push_str("      ");
+        row(&mut s, 3, r);
+        s.push('\n');
+    }
+    s
+}
@@ -0,0 +1,143 @@
+//! Translating solutions for robots that can turn only some faces.
+//!
+//! The robot's view of the cube changes as it regrips (rotates the whole
+//! cube), so each face turn is mapped to whichever physical position that
+//! face currently occupies, inserting the fewest regrips needed to bring
+//! it to a turnable position.
+use crate::alg::Alg;
+use crate::notation::Face;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A whole-cube rotation axis: x follows R, y follows U, z follows F.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// One step for the robot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// Turn the face at this physical position, 1..=3 quarter turns.
+    Turn(Face, u8),
+    /// Rotate the whole cube, 1..=3 quarter turns.
+    Rotate(Axis, u8),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, power) = match *self {
+            Instruction::Turn(face, power) => (face.to_char(), power),
+            Instruction::Rotate(axis, power) => {
+                (['x', 'y', 'z'][axis as usize], power)
+            }
+        };
+        write!(f, "{}{}", name, ["", "2", "'"][power as usize - 1])
+    }
+}
+
+/// What the robot can do.
+#[derive(Debug, Clone)]
+pub struct Mechanics {
+    /// Physical positions it can turn.
+    pub turnable: Vec<Face>,
+    /// Regrips available, as (axis, quarter turns).
+    pub rotations: Vec<(Axis, u8)>,
+}
+
+impl Mechanics {
+    /// Two grippers holding U and D, regripping about x and y.
+    pub fn two_gripper() -> Self {
+        Self {
+            turnable: vec![Face::U, Face::D],
+            rotations: vec![
+                (Axis::X, 1),
+                (Axis::X, 2),
+                (Axis::X, 3),
+                (Axis::Y, 1),
+                (Axis::Y, 2),
+                (Axis::Y, 3),
+            ],
+        }
+    }
+
+    /// Every face except B can be turned, and the cube is never rotated.
+    pub fn no_back() -> Self {
+        Self {
+            turnable: vec![Face::U, Face::R, Face::F, Face::D, Face::L],
+            rotations: Vec::new(),
+        }
+    }
+}
+
+/// Which face's center is at each physical position, indexed by `Face`.
+type Orientation = [Face; 6];
+
+const IDENTITY: Orientation = Face::ALL;
+
+/// Rotate about `axis` by one quarter turn.
+fn rotate_once(o: &Orientation, axis: Axis) -> Orientation {
+    use Face::*;
+    // Each cycle lists positions so that the center at one moves to the
+    // next.
+    let cycle = match axis {
+        Axis::X => [F, U, B, D],
+        Axis::Y => [F, L, B, R],
+        Axis::Z => [U, R, D, L],
+    };
+    let mut r = *o;
+    for i in 0..4 {
+        r[cycle[(i + 1) % 4] as usize] = o[cycle[i] as usize];
+    }
+    r
+}
+
+fn rotate(o: &Orientation, axis: Axis, power: u8) -> Orientation {
+    (0..power).fold(*o, |o, _| rotate_once(&o, axis))
+}
+
+/// Turns `alg` into instructions for `mechanics`, or `None` if some face
+/// can never be brought to a turnable position.
+pub fn translate(alg: &Alg, mechanics: &Mechanics) -> Option<Vec<Instruction>> {
+    let mut out = Vec::new();
+    let mut o = IDENTITY;
+    for m in alg.moves() {
+        let (rotations, next) = regrip(&o, m.face(), mechanics)?;
+        out.extend(rotations);
+        o = next;
+        let position = Face::ALL[o.iter().position(|&f| f == m.face())?];
+        out.push(Instruction::Turn(position, m.power()));
+    }
+    Some(out)
+}
+
+/// The fewest rotations bringing `face` to a turnable position.
+fn regrip(
+    o: &Orientation,
+    face: Face,
+    mechanics: &Mechanics,
+) -> Option<(Vec<Instruction>, Orientation)> {
+    let turnable = |o: &Orientation| {
+        mechanics.turnable.iter().any(|&p| o[p as usize] == face)
+    };
+    let mut queue = VecDeque::new();
+    let mut seen = vec![*o];
+    queue.push_back((*o, Vec::new()));
+    while let Some((o, path)) = queue.pop_front() {
+        if turnable(&o) {
+            return Some((path, o));
+        }
+        for &(axis, power) in mechanics.rotations.iter() {
+            let next = rotate(&o, axis, power);
+            if !seen.contains(&next) {
+                seen.push(next);
+                let mut path = path.clone();
+                path.push(Instruction::Rotate(axis, power));
+                queue.push_back((next, path));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,177 @@
+//! Whole-cube rotations: the 24 symmetries that are not reflections.
+//!
+//! Rotation `i` is symmetry `2 * i` of `Cube::sym`. A rotation acts on
+//! moves by renaming them: `R` turned after `y` is the face that was at the
+//! back before it, so `Y.map_move(R)` is `B`. It acts on states by the
+//! matching conjugation, so that `map_move(m).cube()` is
+//! `conjugate(m.cube())`.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::notation::{Face, Move};
+use alloc::string::{String, ToString};
+use core::fmt;
+
+static SYM: [Cube; 48] = Cube::sym();
+static SYM_INV: [u8; 48] = Cube::sym_inv();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CubeRotation(u8);
+
+impl CubeRotation {
+    pub const IDENTITY: CubeRotation = CubeRotation(0);
+    /// Follows R.
+    pub const X: CubeRotation = CubeRotation(14);
+    /// Follows U.
+    pub const Y: CubeRotation = CubeRotation(2);
+    /// Follows F.
+    pub const Z: CubeRotation = CubeRotation(19);
+
+    pub fn from_index(i: usize) -> Self {
+        assert!(i < 24, "rotation index out of range");
+        CubeRotation(i as u8)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn all() -> impl Iterator<Item = CubeRotation> {
+        (0..24).map(CubeRotation)
+    }
+
+    /// The index of this rotation in `Cube::sym`.
+    pub fn sym(self) -> usize {
+        2 * self.0 as usize
+    }
+
+    pub fn cube(self) -> &'static Cube {
+        &SYM[self.sym()]
+    }
+
+    /// This rotation followed by `other`, as in `x y`.
+    pub fn then(self, other: CubeRotation) -> Self {
+        let c = self.cube().compose(other.cube());
+        CubeRotation::all()
+            .find(|r| *r.cube() == c)
+            .expect("rotations are closed")
+    }
+
+    pub fn inverse(self) -> Self {
+        CubeRotation(SYM_INV[self.sym()] / 2)
+    }
+
+    /// This rotation `n` times over.
+    pub fn pow(self, n: u8) -> Self {
+        (0..n).fold(CubeRotation::IDENTITY, |r, _| r.then(self))
+    }
+
+    /// The move, in the frame before this rotation, that turns the face
+    /// `m` names in the frame after it.
+    pub fn map_move(self, m: Move) -> Move {
+        let c = self.conjugate(m.cube());
+        Move::ALL
+            .iter()
+            .copied()
+            .find(|x| *x.cube() == c)
+            .expect("rotations map moves to moves")
+    }
+
+    pub fn map_alg(self, alg: &Alg) -> Alg {
+        Alg(alg.moves().iter().map(|&m| self.map_move(m)).collect())
+    }
+
+    /// A rotation under which turns of `face` become turns of `to`: states
+    /// conjugated by it have on `to` what the originals had on `face`.
+    pub fn taking(face: Face, to: Face) -> Self {
+        let turn = Move::new(face, 1);
+        CubeRotation::all()
+            .find(|r| r.map_move(turn).face() == to)
+            .expect("some rotation takes every face to every face")
+    }
+
+    pub fn conjugate(self, cube: &Cube) -> Cube {
+        cube.sym_conjugate(self.sym())
+    }
+}
+
+impl Default for CubeRotation {
+    fn default() -> Self {
+        CubeRotation::IDENTITY
+    }
+}
+
+/// The rotations written `x`, `x2`, `x'`, then `y`, then `z`.
+const TOKENS: [(CubeRotation, u8, &str); 9] = [
+    (CubeRotation::X, 1, "x"),
+    (CubeRotation::X, 2, "x2"),
+    (CubeRotation::X, 3, "x'"),
+    (CubeRotation::Y, 1, "y"),
+    (CubeRotation::Y, 2, "y2"),
+    (CubeRotation::Y, 3, "y'"),
+    (CubeRotation::Z, 1, "z"),
+    (CubeRotation::Z, 2, "z2"),
+    (CubeRotation::Z, 3, "z'"),
+];
+
+/// The shortest way to write the rotation, in `TOKENS` order; every
+/// rotation takes at most two.
+impl fmt::Display for CubeRotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == CubeRotation::IDENTITY {
+            return Ok(());
+        }
+        for &(a, n, name) in TOKENS.iter() {
+            if a.pow(n) == *self {
+                return write!(f, "{}", name);
+            }
+        }
+        for &(a, n, first) in TOKENS.iter() {
+            for &(b, k, second) in TOKENS.iter() {
+                if a.pow(n).then(b.pow(k)) == *self {
+                    return write!(f, "{} {}", first, second);
+                }
+            }
+        }
+        unreachable!("every rotation is at most two turns")
+    }
+}
+
+/// A string that is not a sequence of rotations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCubeRotationError(pub String);
+
+impl fmt::Display for ParseCubeRotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not a rotation: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseCubeRotationError {}
+
+/// Whitespace-separated `x`, `y` and `z` turns, composed in order.
+impl core::str::FromStr for CubeRotation {
+    type Err = ParseCubeRotationError;
+
+    fn from_str(s: &str) -> Result<Self, ParseCubeRotationError> {
+        let mut r = CubeRotation::IDENTITY;
+        for token in s.split_whitespace() {
+            let err = || ParseCubeRotationError(token.to_string());
+            let mut chars = token.chars();
+            let axis = match chars.next() {
+                Some('x') => CubeRotation::X,
+                Some('y') => CubeRotation::Y,
+                Some('z') => CubeRotation::Z,
+                _ => return Err(err()),
+            };
+            let power = match chars.as_str() {
+                "" => 1,
+                "2" | "2'" => 2,
+                "'" => 3,
+                _ => return Err(err()),
+            };
+            r = r.then(axis.pow(power));
+        }
+        Ok(r)
+    }
+}
@@ -0,0 +1,264 @@
+//! Schreier–Sims stabilizer chains over the cube group.
+//!
+//! Cubes act as permutations of the 54 stickers. A stabilizer chain built
+//! from a set of generator cubes answers "is this state in the generated
+//! subgroup?" and "how do I write it in the generators?" without
+//! enumerating the subgroup. Uses Knuth's incremental formulation, with
+//! every sticker as a base point.
+use crate::cube::Cube;
+use crate::facelet::sticker_permutation;
+
+const N: usize = 54;
+
+/// A permutation of stickers, as the image of each sticker.
+type Perm = [u8; N];
+
+const IDENTITY: Perm = {
+    let mut p = [0; N];
+    let mut i = 0;
+    while i < N {
+        p[i] = i as u8;
+        i += 1;
+    }
+    p
+};
+
+/// The sticker permutation of a cube: sticker `i` moves to `p[i]`.
+fn perm_of(cube: &Cube) -> Perm {
+    let mut p = IDENTITY;
+    for (dst, &src) in sticker_permutation(cube).iter().enumerate() {
+        p[src] = dst as u8;
+    }
+    p
+}
+
+/// `a`, then `b`.
+fn mul(a: &Perm, b: &Perm) -> Perm {
+    let mut p = [0; N];
+    for i in 0..N {
+        p[i] = b[a[i] as usize];
+    }
+    p
+}
+
+fn inv(a: &Perm) -> Perm {
+    let mut p = [0; N];
+    for i in 0..N {
+        p[a[i] as usize] = i as u8;
+    }
+    p
+}
+
+/// One generator or its inverse, in a factorization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Letter {
+    /// Index into the generators the chain was built from.
+    pub generator: usize,
+    pub inverse: bool,
+}
+
+impl Letter {
+    fn invert(self) -> Self {
+        Self {
+            generator: self.generator,
+            inverse: !self.inverse,
+        }
+    }
+}
+
+/// A permutation together with a word in the generators that equals it.
+#[derive(Clone)]
+struct Element {
+    perm: Perm,
+    word: Vec<Letter>,
+}
+
+impl Element {
+    /// `self`, then `other`, cancelling adjacent inverse letters.
+    fn mul(&self, other: &Element) -> Element {
+        let mut word = self.word.clone();
+        for &l in other.word.iter() {
+            if word.last() == Some(&l.invert()) {
+                word.pop();
+            } else {
+                word.push(l);
+            }
+        }
+        Element {
+            perm: mul(&self.perm, &other.perm),
+            word,
+        }
+    }
+
+    fn inv(&self) -> Element {
+        Element {
+            perm: inv(&self.perm),
+            word: self.word.iter().rev().map(|l| l.invert()).collect(),
+        }
+    }
+}
+
+struct Level {
+    /// Strong generators fixing every earlier base point.
+    gens: Vec<Element>,
+    /// For each sticker in the base point's orbit, an element taking the
+    /// base point there.
+    transversal: Vec<Option<Element>>,
+}
+
+/// A stabilizer chain for the subgroup generated by some cubes.
+pub struct StabilizerChain {
+    levels: Vec<Level>,
+}
+
+impl StabilizerChain {
+    pub fn new(generators: &[Cube]) -> Self {
+        let identity = Element {
+            perm: IDENTITY,
+            word: Vec::new(),
+        };
+        let mut levels: Vec<Level> = (0..N)
+            .map(|b| {
+                let mut transversal = vec![None; N];
+                transversal[b] = Some(identity.clone());
+                Level {
+                    gens: Vec::new(),
+                    transversal,
+                }
+            })
+            .collect();
+        for (i, g) in generators.iter().enumerate() {
+            let g = Element {
+                perm: perm_of(g),
+                word: vec![Letter {
+                    generator: i,
+                    inverse: false,
+                }],
+            };
+            add_generator(&mut levels, 0, g);
+        }
+        Self { levels }
+    }
+
+    /// Number of elements of the subgroup.
+    pub fn order(&self) -> u128 {
+        self.levels
+            .iter()
+            .map(|l| l.transversal.iter().flatten().count() as u128)
+            .product()
+    }
+
+    pub fn contains(&self, cube: &Cube) -> bool {
+        sift(&self.levels, 0, &perm_of(cube)).is_none()
+    }
+
+    /// A word in the generators equal to `cube`, if it is in the subgroup.
+    /// The word is correct but far from short.
+    pub fn factorize(&self, cube: &Cube) -> Option<Vec<Letter>> {
+        let mut g = perm_of(cube);
+        let mut reps = Vec::new();
+        for (b, level) in self.levels.iter().enumerate() {
+            let u = level.transversal[g[b] as usize].as_ref()?;
+            g = mul(&g, &inv(&u.perm));
+            reps.push(u);
+        }
+        // g = u_n ... u_1, each applied left to right.
+        let mut word = Element {
+            perm: IDENTITY,
+            word: Vec::new(),
+        };
+        for u in reps.iter().rev() {
+            word = word.mul(u);
+        }
+        Some(word.word)
+    }
+}
+
+/// Sift `g` from level `k` down; `None` if it reduces to the identity,
+/// else the residue.
+fn sift(levels: &[Level], k: usize, g: &Perm) -> Option<Perm> {
+    let mut g = *g;
+    for (b, level) in levels.iter().enumerate().skip(k) {
+        match &level.transversal[g[b] as usize] {
+            Some(u) => g = mul(&g, &inv(&u.perm)),
+            None => return Some(g),
+        }
+    }
+    if g == IDENTITY {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+fn add_generator(levels: &mut [Level], k: usize, g: Element) {
+    if sift(levels, k, &g.perm).is_none() {
+        return;
+    }
+    levels[k].gens.push(g.clone());
+    let reps: Vec<Element> =
+        levels[k].transversal.iter().flatten().cloned().collect();
+    for r in reps.iter() {
+        extend_orbit(levels, k, r.mul(&g));
+    }
+}
+
+fn extend_orbit(levels: &mut [Level], k: usize, t: Element) {
+    let j = t.perm[k] as usize;
+    match &levels[k].transversal[j] {
+        None => {
+            levels[k].transversal[j] = Some(t.clone());
+            let gens = levels[k].gens.clone();
+            for g in gens.iter() {
+                extend_orbit(levels, k, t.mul(g));
+            }
+        }
+        Some(u) => {
+            let s = t.mul(&u.inv());
+            add_generator(levels, k + 1, s);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alg::Alg;
+    use crate::notation::Move;
+
+    fn cube(alg: &str) -> Cube {
+        alg.parse::<Alg>().unwrap().cube()
+    }
+
+    #[test]
+    fn two_generator_group_order() {
+        let chain = StabilizerChain::new(&[*Move::R.cube(), *Move::U.cube()]);
+        assert_eq!(chain.order(), 73_483_200);
+    }
+
+    #[test]
+    fn cube_group_order() {
+        let quarters: Vec<Cube> =
+            Move::ALL.iter().step_by(3).map(|m| *m.cube()).collect();
+        let chain = StabilizerChain::new(&quarters);
+        assert_eq!(chain.order(), 43_252_003_274_489_856_000);
+    }
+
+    #[test]
+    fn factorizations_give_back_the_state() {
+        let generators = [*Move::R.cube(), *Move::U.cube()];
+        let chain = StabilizerChain::new(&generators);
+        let inside = cube("R U R' U R U2 R' U'");
+        assert!(chain.contains(&inside));
+        let word = chain.factorize(&inside).unwrap();
+        let product = word.iter().fold(Cube::identity(), |c, l| {
+            let g = generators[l.generator];
+            c.compose(&if l.inverse { g.invert() } else { g })
+        });
+        assert_eq!(product, inside);
+
+        let outside = cube("R F");
+        assert!(!chain.contains(&outside));
+        assert_eq!(chain.factorize(&outside), None);
+    }
+}
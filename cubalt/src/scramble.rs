@@ -0,0 +1,200 @@
+//! Reproducible scramble sets.
+//!
+//! Scramble `i` of seed `s` is derived as follows, and the derivation will
+//! not change:
+//!
+//! 1. `k` is output `i` (counting from 0) of `Rng::new(s)`.
+//! 2. The state is `random_cube(&mut Rng::new(k))`.
+//! 3. The scramble is the two-phase solution of the inverse state chosen
+//!    below; applied to the solved cube, it gives the state.
+//!
+//! Move sequences in step 3 are canonical: never two turns of one face in
+//! a row, and of two turns of opposite faces, U before D, R before L and F
+//! before B. They are ordered by length, then lexicographically with the
+//! moves in the order `U U2 U' R R2 R' F F2 F' D D2 D' L L2 L' B B2 B'`.
+//! The first phase is the first sequence taking the inverse state into
+//! G1 = <U, D, R2, L2, F2, B2>, not ending in one of those generators, for
+//! which a second phase fits in 22 moves in all. The second phase is the
+//! first of the shortest sequences of `U U2 U' R2 F2 D D2 D' L2 B2`, in
+//! that order, solving the rest. Turns of one face where the phases meet
+//! are merged.
+//!
+//! The pruning tables only skip sequences that cannot reach the goal, so
+//! they do not change which sequence is first. Everything is integer
+//! arithmetic, and the scrambles are the same on every platform.
+//!
+//! A filter on the cross length keeps the scrambles whose states pass it,
+//! in order, so filtered sets are as reproducible as the rest.
+use crate::alg::Alg;
+use crate::canonical::is_canonical;
+use crate::cross::CrossSolver;
+use crate::cube::Cube;
+use crate::notation::Move;
+use crate::random::{random_cube, Rng};
+use crate::search::Ida;
+use crate::solver::{in_g1, Solver, PHASE2_MOVES};
+use std::ops::RangeInclusive;
+
+/// Longest scramble; every state has a two-phase solution this long.
+const LENGTH: u32 = 22;
+
+/// The scrambles of one seed, in order.
+pub struct Scrambles<'a> {
+    solver: &'a Solver,
+    seed: u64,
+    next: u64,
+    cross: Option<(&'a CrossSolver, RangeInclusive<u32>)>,
+}
+
+impl<'a> Scrambles<'a> {
+    pub fn new(solver: &'a Solver, seed: u64) -> Self {
+        Self {
+            solver,
+            seed,
+            next: 0,
+            cross: None,
+        }
+    }
+
+    /// Only the scrambles whose D cross takes a number of moves in
+    /// `lengths`, such as `7..=8` for hard crosses or `0..=3` for
+    /// beginners.
+    pub fn cross_length(
+        mut self,
+        cross: &'a CrossSolver,
+        lengths: RangeInclusive<u32>,
+    ) -> Self {
+        self.cross = Some((cross, lengths));
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The state scrambled to by scramble `i`.
+    pub fn state(&self, i: u64) -> Cube {
+        let mut master = Rng::new(self.seed);
+        master.jump(i);
+        random_cube(&mut Rng::new(master.next_u64()))
+    }
+
+    /// Scramble `i`, independently of the iterator's position.
+    pub fn get(&self, i: u64) -> Alg {
+        scramble(self.solver, &self.state(i))
+    }
+}
+
+/// The scramble for `state`, as the module documentation gives it. The
+/// searches are set up here, move order and all, rather than left to
+/// `Solver`, whose search order may change.
+fn scramble(solver: &Solver, state: &Cube) -> Alg {
+    let cube = state.invert();
+    let (bound1, bound2) = solver.heuristics();
+    let goal = in_g1;
+    let solved = |c: &Cube| *c == Cube::identity();
+    let mut phase1 = Ida::new(bound1, &goal, LENGTH);
+    phase1.moves = &Move::ALL;
+    phase1.canonical = is_canonical::<Cube>;
+    phase1.ordering = false;
+    let mut result = None;
+    for depth in 0..=LENGTH {
+        let found = phase1.search_depth(&cube, depth, |path, g1| {
+            if path.last().is_some_and(|m| PHASE2_MOVES.contains(m)) {
+                return false;
+            }
+            let mut phase2 = Ida::new(bound2, &solved, LENGTH - depth);
+            phase2.moves = &PHASE2_MOVES;
+            phase2.canonical = is_canonical::<Cube>;
+            phase2.ordering = false;
+            match phase2.solve(g1) {
+                Some(rest) => {
+                    let mut alg = Alg(path.to_vec());
+                    alg.0.extend(rest);
+                    result = Some(alg.simplify());
+                    true
+                }
+                None => false,
+            }
+        });
+        if found {
+            break;
+        }
+    }
+    result.expect("every state is within 22 moves")
+}
+
+impl Scrambles<'_> {
+    /// The index of the next scramble passing the filter, consumed.
+    fn next_index(&mut self) -> u64 {
+        loop {
+            let i = self.next;
+            self.next += 1;
+            match &self.cross {
+                Some((cross, lengths))
+                    if !lengths.contains(&cross.length(&self.state(i))) => {}
+                _ => return i,
+            }
+        }
+    }
+}
+
+impl Iterator for Scrambles<'_> {
+    type Item = Alg;
+
+    fn next(&mut self) -> Option<Alg> {
+        let i = self.next_index();
+        Some(self.get(i))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Alg> {
+        if self.cross.is_some() {
+            for _ in 0..n {
+                self.next_index();
+            }
+        } else {
+            self.next += n as u64;
+        }
+        self.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first scrambles of two seeds. These must never change.
+    const GOLDEN: [(u64, [&str; 3]); 2] = [
+        (
+            0,
+            [
+                "L B' R D R B L' D L U R2 U R2 U B2 R2 U' L2 D R2 B2 U'",
+                "U L F B' U D F' D R L F' U R2 F2 U' D' R2 D B2 D F2 B2",
+                "U L2 B R F2 U R' D2 R' D L' B2 D2 R2 F2 U' B2 U' B2 R2 D' B2",
+            ],
+        ),
+        (
+            2025,
+            [
+                "L' U B' U B D' L2 B' U L' D' L2 U F2 B2 U2 B2 L2 U' R2 U R2",
+                "F' L D2 R U L F B' R D F2 U2 F2 U' B2 L2 U F2 D B2 U' L2",
+                "U' L' U2 F' R L' D B D2 L R2 U2 L2 F2 D' R2 U' F2 D2 B2 L2 D",
+            ],
+        ),
+    ];
+
+    #[test]
+    fn golden_scrambles() {
+        for (seed, golden) in GOLDEN {
+            let scrambles = Scrambles::new(Solver::shared(), seed);
+            for (i, want) in golden.iter().enumerate() {
+                let alg = scrambles.get(i as u64);
+                assert_eq!(alg.to_string(), *want, "seed {}, {}", seed, i);
+                assert_eq!(alg.cube(), scrambles.state(i as u64));
+            }
+            let all: Vec<String> =
+                scrambles.take(3).map(|a| a.to_string()).collect();
+            assert_eq!(all, golden);
+        }
+    }
+}
@@ -0,0 +1,492 @@
+//! Generic search: iterative-deepening A* and breadth-first enumeration.
+//!
+//! Both work on any `Puzzle`, toward any `Goal`, and prune move sequences
+//! that are not canonical (see `is_canonical`).
+pub use crate::canonical::is_canonical;
+use crate::canonical::CanonicalSeq;
+use crate::coord::Coordinate;
+use crate::cube::Cube;
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::pattern::{AnyOf, Goal};
+use crate::prune::PruneTable;
+use crate::puzzle::Puzzle;
+use crate::rotation::CubeRotation;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A lower bound on the number of moves from a state to the goal.
+///
+/// It must never overestimate, or IDA* may return non-optimal solutions.
+/// Bounds combine: a pair gives the larger of its two, `MinOf` the least
+/// of several.
+pub trait Heuristic<P: Puzzle> {
+    fn estimate(&self, p: &P) -> u32;
+}
+
+/// The trivial bound; turns IDA* into plain iterative deepening.
+pub struct NoHeuristic;
+
+impl<P: Puzzle> Heuristic<P> for NoHeuristic {
+    fn estimate(&self, _: &P) -> u32 {
+        0
+    }
+}
+
+/// The larger of two bounds.
+impl<P: Puzzle, A: Heuristic<P>, B: Heuristic<P>> Heuristic<P> for (A, B) {
+    fn estimate(&self, p: &P) -> u32 {
+        self.0.estimate(p).max(self.1.estimate(p))
+    }
+}
+
+/// The least of several bounds: a bound on the distance to the nearest of
+/// several goals, from one bound per goal.
+pub struct MinOf<H>(pub Vec<H>);
+
+impl<P: Puzzle, H: Heuristic<P>> Heuristic<P> for MinOf<H> {
+    fn estimate(&self, p: &P) -> u32 {
+        self.0.iter().map(|h| h.estimate(p)).min().unwrap_or(0)
+    }
+}
+
+impl<P: Puzzle, H: Heuristic<P>> Heuristic<P> for &H {
+    fn estimate(&self, p: &P) -> u32 {
+        (*self).estimate(p)
+    }
+}
+
+/// A goal or bound applied to the cube seen after `rotation`: conjugating
+/// by it turns each goal on one face into the same goal on another, and
+/// keeps distances.
+#[derive(Debug, Clone, Copy)]
+pub struct Rotated<T> {
+    pub inner: T,
+    pub rotation: CubeRotation,
+}
+
+impl<G: Goal<Cube>> Goal<Cube> for Rotated<G> {
+    fn is_goal(&self, p: &Cube) -> bool {
+        self.inner.is_goal(&self.rotation.conjugate(p))
+    }
+}
+
+impl<H: Heuristic<Cube>> Heuristic<Cube> for Rotated<H> {
+    fn estimate(&self, p: &Cube) -> u32 {
+        self.inner.estimate(&self.rotation.conjugate(p))
+    }
+}
+
+/// A bound computed by a function, as a learned or hand-written estimate.
+/// Admissible only if the function never overestimates.
+#[derive(Debug, Clone, Copy)]
+pub struct FnHeuristic<F>(pub F);
+
+impl<P: Puzzle, F: Fn(&P) -> u32> Heuristic<P> for FnHeuristic<F> {
+    fn estimate(&self, p: &P) -> u32 {
+        (self.0)(p)
+    }
+}
+
+/// Slots of `edges` and `corners` (bit i = slot i) not holding their own
+/// piece unturned, a quarter of each rounded up: a turn moves four edges
+/// and four corners. Admissible when the goal needs those slots solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MisplacedPieces {
+    pub edges: u16,
+    pub corners: u8,
+}
+
+impl MisplacedPieces {
+    pub const ALL: MisplacedPieces = MisplacedPieces {
+        edges: 0xfff,
+        corners: 0xff,
+    };
+}
+
+impl Heuristic<Cube> for MisplacedPieces {
+    fn estimate(&self, p: &Cube) -> u32 {
+        let edges = p.edges().iter().enumerate().filter(|&(i, e)| {
+            self.edges >> i & 1 == 1 && e.0 != EDGES.pack(i as u8, 0)
+        });
+        let corners = p.corners().iter().enumerate().filter(|&(i, c)| {
+            self.corners >> i & 1 == 1 && c.0 != CORNERS.pack(i as u8, 0)
+        });
+        let edges = edges.count() as u32;
+        let corners = corners.count() as u32;
+        edges.div_ceil(4).max(corners.div_ceil(4))
+    }
+}
+
+/// Slots of `edges` and `corners` holding a flipped or twisted piece, a
+/// quarter of each rounded up, as for `MisplacedPieces`. Admissible when
+/// the goal needs those slots oriented, whatever is in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Misoriented {
+    pub edges: u16,
+    pub corners: u8,
+}
+
+impl Misoriented {
+    pub const ALL: Misoriented = Misoriented {
+        edges: 0xfff,
+        corners: 0xff,
+    };
+}
+
+impl Heuristic<Cube> for Misoriented {
+    fn estimate(&self, p: &Cube) -> u32 {
+        let edges =
+            p.edges().iter().enumerate().filter(|&(i, e)| {
+                self.edges >> i & 1 == 1 && EDGES.ori(e.0) != 0
+            });
+        let corners = p.corners().iter().enumerate().filter(|&(i, c)| {
+            self.corners >> i & 1 == 1 && CORNERS.ori(c.0) != 0
+        });
+        let edges = edges.count() as u32;
+        let corners = corners.count() as u32;
+        edges.div_ceil(4).max(corners.div_ceil(4))
+    }
+}
+
+/// Distance of a coordinate to solved, looked up in a pruning table (a
+/// pattern database).
+/// Only admissible when the goal is the solved state. The table is shared:
+/// clones are cheap and look up the same one.
+pub struct TableHeuristic<P, C> {
+    table: Arc<PruneTable>,
+    marker: PhantomData<fn() -> (P, C)>,
+}
+
+impl<P, C> Clone for TableHeuristic<P, C> {
+    fn clone(&self) -> Self {
+        Self {
+            table: Arc::clone(&self.table),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P: Puzzle, C: Coordinate<P>> TableHeuristic<P, C> {
+    pub fn new(table: PruneTable) -> Self {
+        Self::shared(Arc::new(table))
+    }
+
+    /// A heuristic looking up a table that others may be using too.
+    pub fn shared(table: Arc<PruneTable>) -> Self {
+        Self {
+            table,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn build() -> Self {
+        Self::new(PruneTable::build::<P, C>())
+    }
+
+    pub fn table(&self) -> &Arc<PruneTable> {
+        &self.table
+    }
+}
+
+impl<P: Puzzle, C: Coordinate<P>> Heuristic<P> for TableHeuristic<P, C> {
+    fn estimate(&self, p: &P) -> u32 {
+        self.table.distance(C::rank(p)) as u32
+    }
+}
+
+/// Which moves a state allows, for puzzles where pieces can block turns.
+pub trait Constraint<P: Puzzle> {
+    fn allows(&self, p: &P, m: P::Move) -> bool;
+}
+
+impl<P: Puzzle, F: Fn(&P, P::Move) -> bool> Constraint<P> for F {
+    fn allows(&self, p: &P, m: P::Move) -> bool {
+        self(p, m)
+    }
+}
+
+/// Iterative-deepening A*.
+pub struct Ida<'a, P: Puzzle, H, G> {
+    heuristic: &'a H,
+    goal: &'a G,
+    /// Moves to search with; all of `P::moves()` by default.
+    pub moves: &'a [P::Move],
+    /// Which moves may follow which; `is_canonical` by default.
+    pub canonical: fn(Option<P::Move>, P::Move) -> bool,
+    /// Moves it rejects in a state are skipped there.
+    pub constraint: Option<&'a dyn Constraint<P>>,
+    /// Give up past this many moves.
+    pub max_depth: u32,
+    /// Nodes visited so far, over all calls.
+    pub nodes: u64,
+    /// Asked every 1024 nodes whether to give up, as on a deadline; once
+    /// it says so, searches return having found nothing more.
+    pub stop: Option<&'a dyn Fn() -> bool>,
+    stopped: bool,
+    /// Try the successors of a node in order: least estimate first, then
+    /// the move that last reached the full depth from this far in (the
+    /// killer), then the moves that most often passed the bound (their
+    /// history). Successors past the bound are then never visited. Off by
+    /// default; it pays on deep searches with costly goals or estimates.
+    pub ordering: bool,
+    /// Per move, a score for passing the bound, kept over all calls.
+    history: Vec<u64>,
+    /// Per depth, the killer move.
+    killers: Vec<Option<usize>>,
+    /// Leaves reached, to tell which subtrees reached one.
+    leaves: u64,
+    /// Per depth, space for the successors, kept to save allocations.
+    successors: Vec<Vec<(u32, usize, P)>>,
+    marker: PhantomData<P>,
+}
+
+impl<'a, P: Puzzle, H: Heuristic<P>, G: Goal<P>> Ida<'a, P, H, G> {
+    pub fn new(heuristic: &'a H, goal: &'a G, max_depth: u32) -> Self {
+        Self {
+            heuristic,
+            goal,
+            moves: P::moves(),
+            canonical: is_canonical::<P>,
+            constraint: None,
+            max_depth,
+            nodes: 0,
+            stop: None,
+            stopped: false,
+            ordering: false,
+            history: Vec::new(),
+            killers: Vec::new(),
+            leaves: 0,
+            successors: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// A shortest canonical move sequence from `start` to the goal, if one
+    /// exists within `max_depth` moves.
+    pub fn solve(&mut self, start: &P) -> Option<Vec<P::Move>> {
+        trace_span!("ida", max_depth = self.max_depth);
+        let mut result = None;
+        for depth in self.heuristic.estimate(start)..=self.max_depth {
+            let found = self.search_depth(start, depth, |path, _| {
+                result = Some(path.to_vec());
+                true
+            });
+            trace_event!(depth, nodes = self.nodes);
+            if found || self.stopped {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Whether `stop` has ended the search.
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Call `found` with each canonical sequence of exactly `depth` moves
+    /// from `start` to the goal, and the state it reaches, until `found`
+    /// returns true. Returns whether it did.
+    pub fn search_depth<F>(&mut self, start: &P, depth: u32, found: F) -> bool
+    where
+        F: FnMut(&[P::Move], &P) -> bool,
+    {
+        self.search_depth_after(start, depth, None, found)
+    }
+
+    /// As `search_depth`, for sequences that follow `prev`, one of
+    /// `moves`: they are canonical continuations of it.
+    pub fn search_depth_after<F>(
+        &mut self,
+        start: &P,
+        depth: u32,
+        prev: Option<P::Move>,
+        mut found: F,
+    ) -> bool
+    where
+        F: FnMut(&[P::Move], &P) -> bool,
+    {
+        let canonical =
+            CanonicalSeq::with_rule::<P>(self.moves, self.canonical);
+        let prev = prev.map(|m| {
+            self.moves
+                .iter()
+                .position(|&x| x == m)
+                .expect("prev is one of the moves")
+        });
+        self.history.resize(self.moves.len(), 0);
+        self.killers.resize(depth as usize, None);
+        self.successors.resize(depth as usize, Vec::new());
+        let mut path = Vec::with_capacity(depth as usize);
+        self.dfs(&canonical, start, depth, prev, &mut path, &mut found)
+    }
+
+    fn dfs<F>(
+        &mut self,
+        canonical: &CanonicalSeq,
+        p: &P,
+        remaining: u32,
+        prev: Option<usize>,
+        path: &mut Vec<P::Move>,
+        found: &mut F,
+    ) -> bool
+    where
+        F: FnMut(&[P::Move], &P) -> bool,
+    {
+        if self.stopped {
+            return false;
+        }
+        if self.nodes.is_multiple_of(1024)
+            && self.stop.is_some_and(|stop| stop())
+        {
+            self.stopped = true;
+            return false;
+        }
+        self.nodes += 1;
+        if remaining == 0 {
+            self.leaves += 1;
+            return self.goal.is_goal(p) && found(path, p);
+        }
+        if self.ordering {
+            // The caller has checked the bound, but for the start.
+            if path.is_empty() && self.heuristic.estimate(p) > remaining {
+                return false;
+            }
+            return self
+                .dfs_ordered(canonical, p, remaining, prev, path, found);
+        }
+        if self.heuristic.estimate(p) > remaining {
+            return false;
+        }
+        for i in canonical.next_moves(prev) {
+            let m = self.moves[i];
+            if self.constraint.is_some_and(|c| !c.allows(p, m)) {
+                continue;
+            }
+            path.push(m);
+            let q = p.apply(m);
+            if self.dfs(canonical, &q, remaining - 1, Some(i), path, found) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// The successors of `p` within the bound, in order. Leaves are goal
+    /// tested without an estimate, as unordered.
+    fn dfs_ordered<F>(
+        &mut self,
+        canonical: &CanonicalSeq,
+        p: &P,
+        remaining: u32,
+        prev: Option<usize>,
+        path: &mut Vec<P::Move>,
+        found: &mut F,
+    ) -> bool
+    where
+        F: FnMut(&[P::Move], &P) -> bool,
+    {
+        let ply = path.len();
+        let killer = self.killers[ply];
+        let mut next = core::mem::take(&mut self.successors[ply]);
+        next.clear();
+        for i in canonical.next_moves(prev) {
+            let m = self.moves[i];
+            if self.constraint.is_some_and(|c| !c.allows(p, m)) {
+                continue;
+            }
+            let q = p.apply(m);
+            let h = match remaining {
+                1 => 0,
+                _ => self.heuristic.estimate(&q),
+            };
+            if h < remaining {
+                let score = 1 << remaining.min(32);
+                self.history[i] = self.history[i].saturating_add(score);
+                next.push((h, i, q));
+            }
+        }
+        next.sort_by_key(|&(h, i, _)| {
+            (h, killer != Some(i), core::cmp::Reverse(self.history[i]))
+        });
+        let mut done = false;
+        for &(_, i, q) in next.iter() {
+            let leaves = self.leaves;
+            path.push(self.moves[i]);
+            if self.dfs(canonical, &q, remaining - 1, Some(i), path, found) {
+                done = true;
+                break;
+            }
+            path.pop();
+            if self.leaves != leaves {
+                self.killers[ply] = Some(i);
+            }
+        }
+        self.successors[ply] = next;
+        done
+    }
+}
+
+impl<'a, P: Puzzle, H: Heuristic<P>, G: Goal<P>> Ida<'a, P, H, AnyOf<G>> {
+    /// A shortest canonical move sequence from `start` to any of the
+    /// goals, with the index of the goal it reaches, if one is within
+    /// `max_depth` moves.
+    pub fn solve_any(&mut self, start: &P) -> Option<(usize, Vec<P::Move>)> {
+        let goals = self.goal;
+        let mut result = None;
+        for depth in self.heuristic.estimate(start)..=self.max_depth {
+            let found = self.search_depth(start, depth, |path, p| {
+                let which = goals.which(p).expect("a goal was reached");
+                result = Some((which, path.to_vec()));
+                true
+            });
+            if found || self.stopped {
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// Breadth-first enumeration of the states within `max_depth` moves of
+/// `start`, nearest first. `visit` sees each distinct state once with its
+/// distance; returning false stops the search.
+///
+/// Returns the number of states found at each distance.
+pub fn bfs<P, F>(start: &P, max_depth: u32, mut visit: F) -> Vec<usize>
+where
+    P: Puzzle + Hash,
+    F: FnMut(&P, u32) -> bool,
+{
+    let mut seen = HashSet::new();
+    seen.insert(*start);
+    let mut counts = vec![1];
+    if !visit(start, 0) {
+        return counts;
+    }
+    let mut frontier = vec![*start];
+    for depth in 1..=max_depth {
+        let mut next = Vec::new();
+        for p in frontier.iter() {
+            for &m in P::moves() {
+                let q = p.apply(m);
+                if seen.insert(q) {
+                    if !visit(&q, depth) {
+                        counts.push(next.len() + 1);
+                        return counts;
+                    }
+                    next.push(q);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        counts.push(next.len());
+        frontier = next;
+    }
+    counts
+}
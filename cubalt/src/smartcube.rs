@@ -0,0 +1,129 @@
+//! Live state tracking for Bluetooth smart cubes.
+//!
+//! This works on move codes already extracted from the (often encrypted)
+//! notification packets, one code per face turn:
+//!
+//! - GAN: `face << 1 | ccw`, faces in U R F D L B order.
+//! - Giiker: `face << 4 | amount`, faces 1..=6 in B D L U R F order,
+//!   amount 1 clockwise, 3 counterclockwise, 2 or 9 a half turn.
+//! - MoYu: `face << 1 | ccw`, faces in F B U D L R order.
+use crate::cube::Cube;
+use crate::notation::{Face, Move};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Gan,
+    Giiker,
+    MoYu,
+}
+
+impl Protocol {
+    /// The move a code stands for, or `None` for an unknown code.
+    pub fn decode(self, code: u8) -> Option<Move> {
+        use Face::*;
+        let (face, power) = match self {
+            Protocol::Gan => {
+                let face = *[U, R, F, D, L, B].get(code as usize >> 1)?;
+                (face, if code & 1 == 0 { 1 } else { 3 })
+            }
+            Protocol::Giiker => {
+                let face = *[B, D, L, U, R, F]
+                    .get((code >> 4).checked_sub(1)? as usize)?;
+                let power = match code & 0x0f {
+                    1 => 1,
+                    2 | 9 => 2,
+                    3 => 3,
+                    _ => return None,
+                };
+                (face, power)
+            }
+            Protocol::MoYu => {
+                let face = *[F, B, U, D, L, R].get(code as usize >> 1)?;
+                (face, if code & 1 == 0 { 1 } else { 3 })
+            }
+        };
+        Some(Move::new(face, power))
+    }
+}
+
+/// A smart cube's state, kept in step with its move codes.
+#[derive(Debug, Clone)]
+pub struct SmartCube {
+    protocol: Protocol,
+    state: Cube,
+}
+
+impl SmartCube {
+    /// Starts solved; use `sync` if the hardware reports otherwise.
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            state: Cube::identity(),
+        }
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub fn state(&self) -> &Cube {
+        &self.state
+    }
+
+    /// Overwrite the tracked state, e.g. from a facelet report.
+    pub fn sync(&mut self, state: Cube) {
+        self.state = state;
+    }
+
+    /// Apply one move code, returning the move and the new state. Unknown
+    /// codes are ignored.
+    pub fn feed(&mut self, code: u8) -> Option<(Move, Cube)> {
+        let m = self.protocol.decode(code)?;
+        self.state = self.state.compose(m.cube());
+        Some((m, self.state))
+    }
+}
+
+/// Adapts a stream of move codes into a stream of `(Move, Cube)` updates.
+#[cfg(feature = "stream")]
+pub struct MoveStream<S> {
+    codes: S,
+    cube: SmartCube,
+}
+
+#[cfg(feature = "stream")]
+impl<S> MoveStream<S> {
+    pub fn new(codes: S, cube: SmartCube) -> Self {
+        Self { codes, cube }
+    }
+
+    pub fn cube(&self) -> &SmartCube {
+        &self.cube
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S> futures_core::Stream for MoveStream<S>
+where
+    S: futures_core::Stream<Item = u8> + Unpin,
+{
+    type Item = (Move, Cube);
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<(Move, Cube)>> {
+        use std::task::Poll;
+        loop {
+            match std::pin::Pin::new(&mut self.codes).poll_next(cx) {
+                Poll::Ready(Some(code)) => {
+                    if let Some(update) = self.cube.feed(code) {
+                        return Poll::Ready(Some(update));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,466 @@
+//! Kociemba's two-phase solver.
+//!
+//! Phase 1 searches all moves for a state in the subgroup
+//! G1 = <U, D, R2, L2, F2, B2>: corners and edges oriented, E-slice edges
+//! in the E slice. Phase 2 solves within G1 using only its moves. Both
+//! phases are IDA* searches from `search`, pruned by coordinate tables.
+use crate::alg::Alg;
+use crate::coord::{
+    Coordinate, CornerOrient, CornerSlicePerm, EdgeOrient, EdgeSlicePerm,
+    FlipSlice, TwistSlice, UDSlice,
+};
+use crate::cube::Cube;
+use crate::diverse::Distinct;
+use crate::notation::{Face, Move};
+use crate::pattern::AnyOf;
+use crate::prune::PruneTable;
+use crate::rotation::CubeRotation;
+use crate::search::{Ida, MinOf, Rotated, TableHeuristic};
+use crate::tablecache::TableCache;
+use std::io;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// The moves generating G1.
+pub const PHASE2_MOVES: [Move; 10] = [
+    Move::U,
+    Move::U2,
+    Move::Ui,
+    Move::R2,
+    Move::F2,
+    Move::D,
+    Move::D2,
+    Move::Di,
+    Move::L2,
+    Move::B2,
+];
+
+/// Longest solution `Solver::solve_with_deadline` starts from: 12 moves
+/// reach G1 and 18 more solve it.
+const ANYTIME_LENGTH: u32 = 30;
+
+/// Longest setup `Solver::setup` asks for; every state has one.
+const SETUP_LENGTH: u32 = 22;
+
+/// Whether a state is in G1.
+pub fn in_g1(c: &Cube) -> bool {
+    CornerOrient::rank(c) == 0
+        && EdgeOrient::rank(c) == 0
+        && UDSlice::rank(c) == 0
+}
+
+/// Nodes a two-phase search visited in each phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    pub phase1_nodes: u64,
+    pub phase2_nodes: u64,
+}
+
+/// Pruning tables for both phases. Building them takes a few seconds;
+/// share one solver between solves. Clones share the tables, so a solver
+/// can be cloned into each thread that solves.
+#[derive(Clone)]
+pub struct Solver {
+    phase1: Phase1,
+    phase2: Phase2,
+}
+
+/// The bound of phase 1.
+type Phase1 = (
+    TableHeuristic<Cube, TwistSlice>,
+    TableHeuristic<Cube, FlipSlice>,
+);
+/// The bound of phase 2.
+type Phase2 = (
+    TableHeuristic<Cube, CornerSlicePerm>,
+    TableHeuristic<Cube, EdgeSlicePerm>,
+);
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        trace_span!("solver_tables");
+        Self::from_tables(
+            PruneTable::build::<Cube, TwistSlice>(),
+            PruneTable::build::<Cube, FlipSlice>(),
+            PruneTable::build_with_moves::<Cube, CornerSlicePerm>(
+                &PHASE2_MOVES,
+            ),
+            PruneTable::build_with_moves::<Cube, EdgeSlicePerm>(&PHASE2_MOVES),
+        )
+    }
+
+    /// One solver for the whole process, built on first use.
+    pub fn shared() -> &'static Solver {
+        static SHARED: OnceLock<Solver> = OnceLock::new();
+        SHARED.get_or_init(Solver::new)
+    }
+
+    /// The solver with its tables from `cache`, where those missing are
+    /// built and stored.
+    pub fn from_cache(cache: &TableCache) -> io::Result<Self> {
+        Ok(Self::from_tables(
+            cache.prune_table("twist-slice", TwistSlice::SIZE, || {
+                PruneTable::build::<Cube, TwistSlice>()
+            })?,
+            cache.prune_table("flip-slice", FlipSlice::SIZE, || {
+                PruneTable::build::<Cube, FlipSlice>()
+            })?,
+            cache.prune_table(
+                "corner-slice-perm",
+                CornerSlicePerm::SIZE,
+                || {
+                    PruneTable::build_with_moves::<Cube, CornerSlicePerm>(
+                        &PHASE2_MOVES,
+                    )
+                },
+            )?,
+            cache.prune_table(
+                "edge-slice-perm",
+                EdgeSlicePerm::SIZE,
+                || {
+                    PruneTable::build_with_moves::<Cube, EdgeSlicePerm>(
+                        &PHASE2_MOVES,
+                    )
+                },
+            )?,
+        ))
+    }
+
+    fn from_tables(
+        twist: PruneTable,
+        flip: PruneTable,
+        corners: PruneTable,
+        edges: PruneTable,
+    ) -> Self {
+        Self {
+            phase1: (TableHeuristic::new(twist), TableHeuristic::new(flip)),
+            phase2: (TableHeuristic::new(corners), TableHeuristic::new(edges)),
+        }
+    }
+
+    /// The bounds of phase 1, to G1, and phase 2, to solved within G1.
+    pub(crate) fn heuristics(&self) -> (&Phase1, &Phase2) {
+        (&self.phase1, &self.phase2)
+    }
+
+    /// The phase 2 pruning tables, of `CornerSlicePerm` and
+    /// `EdgeSlicePerm`.
+    pub(crate) fn phase2_tables(&self) -> (&PruneTable, &PruneTable) {
+        (&**self.phase2.0.table(), &**self.phase2.1.table())
+    }
+
+    /// A solution of at most `max_length` moves, if the search finds one.
+    /// The first solution found is returned, which is not in general the
+    /// shortest; with `max_length` 21 or more one is found quickly.
+    pub fn solve(&self, cube: &Cube, max_length: u32) -> Option<Alg> {
+        self.solve_with_stats(cube, max_length).0
+    }
+
+    /// As `solve`, with the nodes the search visited.
+    pub fn solve_with_stats(
+        &self,
+        cube: &Cube,
+        max_length: u32,
+    ) -> (Option<Alg>, SolveStats) {
+        trace_span!(
+            "solve",
+            backend = crate::context::Backend::current().name(),
+            max_length
+        );
+        let goal = in_g1;
+        let mut phase1 = Ida::new(&self.phase1, &goal, max_length);
+        let mut result = None;
+        let mut stats = SolveStats::default();
+        for depth in 0..=max_length {
+            let found = phase1.search_depth(cube, depth, |path, g1| {
+                // A phase 1 ending in a G1 move means a shorter phase 1
+                // reaches G1 too, and has been tried already.
+                if path.last().is_some_and(|m| PHASE2_MOVES.contains(m)) {
+                    return false;
+                }
+                let solved = |c: &Cube| *c == Cube::identity();
+                let mut phase2 =
+                    Ida::new(&self.phase2, &solved, max_length - depth);
+                phase2.moves = &PHASE2_MOVES;
+                let rest = phase2.solve(g1);
+                stats.phase2_nodes += phase2.nodes;
+                match rest {
+                    Some(rest) => {
+                        let mut alg = Alg(path.to_vec());
+                        alg.0.extend(rest);
+                        // The phases may meet on one face, as in F F2.
+                        result = Some(alg.simplify());
+                        true
+                    }
+                    None => false,
+                }
+            });
+            trace_event!(phase1_depth = depth, nodes = phase1.nodes);
+            if found {
+                break;
+            }
+        }
+        stats.phase1_nodes = phase1.nodes;
+        (result, stats)
+    }
+
+    /// Up to `n` solutions of at most `max_length`, no two alike as
+    /// `diverse::key` has it: one for each phase 1 solution, in the order
+    /// `solve` tries them, while they are unlike those before.
+    pub fn solve_diverse(
+        &self,
+        cube: &Cube,
+        max_length: u32,
+        n: usize,
+    ) -> Vec<Alg> {
+        let mut distinct = Distinct::new(n);
+        let goal = in_g1;
+        let mut phase1 = Ida::new(&self.phase1, &goal, max_length);
+        for depth in 0..=max_length {
+            let full = phase1.search_depth(cube, depth, |path, g1| {
+                if path.last().is_some_and(|m| PHASE2_MOVES.contains(m)) {
+                    return false;
+                }
+                let solved = |c: &Cube| *c == Cube::identity();
+                let mut phase2 =
+                    Ida::new(&self.phase2, &solved, max_length - depth);
+                phase2.moves = &PHASE2_MOVES;
+                if let Some(rest) = phase2.solve(g1) {
+                    let mut alg = Alg(path.to_vec());
+                    alg.0.extend(rest);
+                    distinct.insert(alg.simplify());
+                }
+                distinct.is_full()
+            });
+            if full {
+                break;
+            }
+        }
+        distinct.into_algs()
+    }
+
+    /// The shortest solution found in `time`: the search goes on after its
+    /// first solution, looking for shorter ones, and returns when it has
+    /// shown the last optimal for two-phase or the time is up. `None` only
+    /// if no solution was found in time, which takes milliseconds.
+    pub fn solve_with_deadline(
+        &self,
+        cube: &Cube,
+        time: Duration,
+    ) -> Option<Alg> {
+        self.improve(cube, Instant::now() + time, None, ANYTIME_LENGTH, false)
+    }
+
+    /// As `solve`, but giving up when `time` is up.
+    pub fn solve_until(
+        &self,
+        cube: &Cube,
+        max_length: u32,
+        time: Duration,
+    ) -> Option<Alg> {
+        self.improve(cube, Instant::now() + time, None, max_length, true)
+    }
+
+    /// As `solve_with_deadline`, warm-started from `hint`: either a whole
+    /// solution of `cube`, such as one found before, or the start of one,
+    /// which is completed. Only solutions shorter than that are then looked
+    /// for, so the result is never longer, and is `None` only if `hint`
+    /// cannot be completed within 30 moves before the time is up.
+    pub fn solve_with_hint(
+        &self,
+        cube: &Cube,
+        hint: &Alg,
+        time: Duration,
+    ) -> Option<Alg> {
+        let deadline = Instant::now() + time;
+        let rest = self.improve(
+            &hint.apply(cube),
+            deadline,
+            None,
+            ANYTIME_LENGTH,
+            true,
+        )?;
+        let mut first = hint.clone();
+        first.0.extend(rest.0);
+        let first = Some(first.simplify());
+        self.improve(cube, deadline, first, ANYTIME_LENGTH, false)
+    }
+
+    /// The anytime search: solutions of at most `max_length` and shorter
+    /// than `best` until `deadline`, or only until the first if `first`.
+    fn improve(
+        &self,
+        cube: &Cube,
+        deadline: Instant,
+        mut best: Option<Alg>,
+        max_length: u32,
+        first: bool,
+    ) -> Option<Alg> {
+        let expired = || Instant::now() >= deadline;
+        let goal = in_g1;
+        let mut phase1 = Ida::new(&self.phase1, &goal, max_length);
+        phase1.stop = Some(&expired);
+        // Solutions shorter than this are wanted.
+        let mut bound = best
+            .as_ref()
+            .map_or(max_length + 1, |b| b.len() as u32)
+            .min(max_length + 1);
+        for depth in 0..=max_length {
+            if depth >= bound {
+                break;
+            }
+            let done = phase1.search_depth(cube, depth, |path, g1| {
+                // A solution found at this depth may be as short as the
+                // depth itself; then nothing here is shorter.
+                if bound <= depth {
+                    return true;
+                }
+                if path.last().is_some_and(|m| PHASE2_MOVES.contains(m)) {
+                    return false;
+                }
+                let solved = |c: &Cube| *c == Cube::identity();
+                let mut phase2 =
+                    Ida::new(&self.phase2, &solved, bound - 1 - depth);
+                phase2.moves = &PHASE2_MOVES;
+                phase2.stop = Some(&expired);
+                if let Some(rest) = phase2.solve(g1) {
+                    let mut alg = Alg(path.to_vec());
+                    alg.0.extend(rest);
+                    let alg = alg.simplify();
+                    bound = alg.len() as u32;
+                    best = Some(alg);
+                }
+                // Nothing is shorter than an empty solution.
+                bound == 0 || (first && best.is_some())
+            });
+            if done || phase1.stopped() {
+                break;
+            }
+        }
+        best
+    }
+
+    /// A shortest sequence of at most `max_length` moves into G1 on any
+    /// axis (domino reduction), with that axis as `Face::axis` numbers it.
+    pub fn reach_g1_any_axis(
+        &self,
+        cube: &Cube,
+        max_length: u32,
+    ) -> Option<(u8, Alg)> {
+        let rotations = [Face::U, Face::R, Face::F]
+            .map(|f| CubeRotation::taking(f, Face::U));
+        let goal: fn(&Cube) -> bool = in_g1;
+        let goals = AnyOf(
+            rotations
+                .iter()
+                .map(|&rotation| Rotated {
+                    inner: goal,
+                    rotation,
+                })
+                .collect(),
+        );
+        let bound = MinOf(
+            rotations
+                .iter()
+                .map(|&rotation| Rotated {
+                    inner: &self.phase1,
+                    rotation,
+                })
+                .collect(),
+        );
+        let mut ida = Ida::new(&bound, &goals, max_length);
+        let (axis, moves) = ida.solve_any(cube)?;
+        Some((axis as u8, Alg(moves)))
+    }
+
+    /// An alg taking state `a` to state `b`: applying it to `a` gives `b`.
+    ///
+    /// With states composed left to right (`a.compose(b)` is `a`, then
+    /// `b`), that alg equals a^-1 * b, found by solving b^-1 * a.
+    pub fn relative_solution(
+        &self,
+        a: &Cube,
+        b: &Cube,
+        max_length: u32,
+    ) -> Option<Alg> {
+        self.solve(&b.invert().compose(a), max_length)
+    }
+
+    /// An alg taking the solved state to `target`, as a setup for practice
+    /// or a test fixture: short, at most 22 moves, but not the shortest.
+    pub fn setup(&self, target: &Cube) -> Alg {
+        self.solve(&target.invert(), SETUP_LENGTH)
+            .expect("every state is within 22 moves")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::{random_cube, Rng};
+
+    #[test]
+    fn solves_seeded_scrambles() {
+        let solver = Solver::shared();
+        let mut rng = Rng::new(3);
+        for _ in 0..5 {
+            let (a, b) = (random_cube(&mut rng), random_cube(&mut rng));
+            let alg = solver.solve(&a, 24).unwrap();
+            assert!(alg.len() <= 24);
+            assert_eq!(alg.apply(&a), Cube::identity());
+            let setup = solver.setup(&a);
+            assert_eq!(setup.cube(), a);
+            let relative = solver.relative_solution(&a, &b, 24).unwrap();
+            assert_eq!(relative.apply(&a), b);
+        }
+    }
+
+    #[test]
+    fn deadline_solves_trivial_states() {
+        let solver = Solver::shared();
+        let time = Duration::from_secs(2);
+        let alg = solver.solve_with_deadline(&Cube::identity(), time);
+        assert_eq!(alg, Some(Alg::default()));
+        for m in Move::ALL {
+            let alg = solver.solve_with_deadline(m.cube(), time).unwrap();
+            assert_eq!(alg.0, [m.inverse()]);
+        }
+    }
+
+    #[test]
+    fn deadline_solves_random_states() {
+        let solver = Solver::shared();
+        let mut rng = Rng::new(1);
+        for _ in 0..5 {
+            let cube = random_cube(&mut rng);
+            let start = Instant::now();
+            let time = Duration::from_secs(10);
+            let alg = solver.solve_with_deadline(&cube, time).unwrap();
+            assert!(start.elapsed() < time + Duration::from_millis(500));
+            assert!(alg.len() <= ANYTIME_LENGTH as usize);
+            assert_eq!(alg.apply(&cube), Cube::identity());
+        }
+    }
+
+    #[test]
+    fn bad_hints_finish_in_time() {
+        let solver = Solver::shared();
+        let mut rng = Rng::new(2);
+        let cube = random_cube(&mut rng);
+        let hint = Alg((0..20).map(|_| *rng.choose(&Move::ALL)).collect());
+        let start = Instant::now();
+        let time = Duration::from_secs(10);
+        let alg = solver.solve_with_hint(&cube, &hint, time).unwrap();
+        assert!(start.elapsed() < time + Duration::from_millis(500));
+        assert_eq!(alg.apply(&cube), Cube::identity());
+        let again = solver.solve_with_hint(&cube, &alg, time).unwrap();
+        assert!(again.len() <= alg.len());
+        assert_eq!(again.apply(&cube), Cube::identity());
+    }
+}
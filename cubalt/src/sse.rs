@@ -1,6 +1,6 @@
 #![allow(overflowing_literals)]
 use crate::types::*;
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
 
 #[inline(always)]
 pub fn identity() -> m128i {
@@ -44,7 +44,7 @@ pub fn compose_edge(a: m128i, b: m128i) -> m128i {
 pub fn xor_edge_orient(v: m128i, eori: Eori) -> m128i {
     unsafe {
         let mut vori: m128i = _mm_shuffle_epi8(
-            _mm_set1_epi32(std::mem::transmute(eori.0)),
+            _mm_set1_epi32(core::mem::transmute(eori.0)),
             _mm_set_epi64x(0xffffffff01010101, 0),
         );
         vori = _mm_or_si128(vori, _mm_set1_epi64x(!0x8040201008040201));
@@ -71,11 +71,58 @@ pub fn corner_orient(v: m128i) -> Cori {
         let mut r: i64 =
             _mm_extract_epi64(vorient, 0) + _mm_extract_epi64(vorient, 1);
         r += r >> 32;
-        r >>= 4;
 
-        debug_assert!(r < u32::max_value() as i64);
+        // Only the low 32 bits hold the sum
+        let r = (r as u32) >> 4;
+        debug_assert!(r < 2187);
 
-        // FIXME transmute?
-        Cori(r as u32)
+        Cori(r)
     }
 }
+
+#[inline(always)]
+pub fn compose_corner(a: m128i, b: m128i) -> m128i {
+    unsafe {
+        let vperm = _mm_shuffle_epi8(a, b);
+        let vori = _mm_and_si128(b, _mm_set1_epi8(0xf0));
+        let vsum = _mm_add_epi8(vperm, vori);
+        _mm_min_epu8(vsum, _mm_sub_epi8(vsum, _mm_set1_epi8(0x30)))
+    }
+}
+
+/// Inverse of a lane whose orientations wrap at `carry`: 0x10 for edges,
+/// 0x30 for corners. Unused bytes hold their own index, and keep it.
+#[inline(always)]
+fn invert(v: m128i, carry: i8) -> m128i {
+    unsafe {
+        let vperm = _mm_and_si128(v, _mm_set1_epi8(0x0f));
+        let mut vori = _mm_xor_si128(v, vperm);
+
+        // "Brute force" the inverse of the permutation
+        let videntity = _mm_set_epi64x(0x0f0e0d0c0b0a0908, 0x0706050403020100);
+        let mut vi = _mm_setzero_si128();
+        for i in 0..16 {
+            let vtrial = _mm_set1_epi8(i);
+            let vcorrect =
+                _mm_cmpeq_epi8(videntity, _mm_shuffle_epi8(vperm, vtrial));
+            vi = _mm_or_si128(vi, _mm_and_si128(vtrial, vcorrect));
+        }
+
+        // Negate the orientations, then move them with their pieces
+        vori = _mm_add_epi8(vori, vori);
+        vori = _mm_min_epu8(vori, _mm_sub_epi8(vori, _mm_set1_epi8(carry)));
+        vori = _mm_shuffle_epi8(vori, vi);
+
+        _mm_or_si128(vi, vori)
+    }
+}
+
+#[inline(always)]
+pub fn invert_edge(v: m128i) -> m128i {
+    invert(v, 0x10)
+}
+
+#[inline(always)]
+pub fn invert_corner(v: m128i) -> m128i {
+    invert(v, 0x30)
+}
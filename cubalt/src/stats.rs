@@ -0,0 +1,237 @@
+//! Statistics over batches of solutions, with CSV and JSON export.
+use crate::puzzle::Puzzle;
+use std::fmt::{self, Display, Write as _};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+/// Counts of small non-negative integers, such as solution lengths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Histogram {
+    counts: Vec<u64>,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, value: usize) {
+        if self.counts.len() <= value {
+            self.counts.resize(value + 1, 0);
+        }
+        self.counts[value] += 1;
+    }
+
+    /// Count at each value, from 0 to the largest value seen.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.counts.iter().position(|&c| c != 0)
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.counts.iter().rposition(|&c| c != 0)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let sum: u64 = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(v, &c)| v as u64 * c)
+            .sum();
+        Some(sum as f64 / total as f64)
+    }
+
+    /// The smallest value `v` with at least fraction `q` of the samples at
+    /// or below it.
+    pub fn quantile(&self, q: f64) -> Option<usize> {
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+        let target = (q * total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (v, &c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= target {
+                return Some(v);
+            }
+        }
+        self.max()
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (v, &c) in other.counts.iter().enumerate() {
+            if c != 0 {
+                if self.counts.len() <= v {
+                    self.counts.resize(v + 1, 0);
+                }
+                self.counts[v] += c;
+            }
+        }
+    }
+}
+
+/// Aggregates over a batch of solves: total length, length of each phase,
+/// and how often each move is used.
+pub struct SolveStats<P: Puzzle> {
+    lengths: Histogram,
+    phases: Vec<Histogram>,
+    moves: Vec<u64>,
+    marker: PhantomData<P>,
+}
+
+impl<P: Puzzle> Default for SolveStats<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Puzzle> SolveStats<P> {
+    pub fn new() -> Self {
+        Self {
+            lengths: Histogram::new(),
+            phases: Vec::new(),
+            moves: vec![0; P::moves().len()],
+            marker: PhantomData,
+        }
+    }
+
+    /// Record a single-phase solution.
+    pub fn record(&mut self, solution: &[P::Move]) {
+        self.record_phases(&[solution]);
+    }
+
+    /// Record a solution given as consecutive phases.
+    pub fn record_phases(&mut self, phases: &[&[P::Move]]) {
+        if self.phases.len() < phases.len() {
+            self.phases.resize(phases.len(), Histogram::new());
+        }
+        let mut length = 0;
+        for (i, phase) in phases.iter().enumerate() {
+            self.phases[i].add(phase.len());
+            length += phase.len();
+            for m in phase.iter() {
+                if let Some(j) = P::moves().iter().position(|x| x == m) {
+                    self.moves[j] += 1;
+                }
+            }
+        }
+        self.lengths.add(length);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.lengths.merge(&other.lengths);
+        if self.phases.len() < other.phases.len() {
+            self.phases.resize(other.phases.len(), Histogram::new());
+        }
+        for (a, b) in self.phases.iter_mut().zip(other.phases.iter()) {
+            a.merge(b);
+        }
+        for (a, b) in self.moves.iter_mut().zip(other.moves.iter()) {
+            *a += b;
+        }
+    }
+
+    /// Number of solves recorded.
+    pub fn solves(&self) -> u64 {
+        self.lengths.total()
+    }
+
+    /// Total solution lengths.
+    pub fn lengths(&self) -> &Histogram {
+        &self.lengths
+    }
+
+    /// Lengths of phase `i`.
+    pub fn phase(&self, i: usize) -> Option<&Histogram> {
+        self.phases.get(i)
+    }
+
+    pub fn phase_count(&self) -> usize {
+        self.phases.len()
+    }
+
+    /// How often each move was used, in `P::moves()` order.
+    pub fn move_counts(&self) -> impl Iterator<Item = (P::Move, u64)> + '_ {
+        P::moves().iter().copied().zip(self.moves.iter().copied())
+    }
+}
+
+impl<P: Puzzle> SolveStats<P>
+where
+    P::Move: Display,
+{
+    /// Long-format CSV of `table,key,count` rows: `length` and `phaseN`
+    /// rows for each length seen, and a `move` row for every move.
+    pub fn write_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "table,key,count")?;
+        write_histogram_csv(&mut w, "length", &self.lengths)?;
+        for (i, h) in self.phases.iter().enumerate() {
+            write_histogram_csv(&mut w, &format!("phase{}", i), h)?;
+        }
+        for (m, c) in self.move_counts() {
+            writeln!(w, "move,{},{}", m, c)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        self.write_json(&mut s).expect("writing to a String");
+        s
+    }
+
+    fn write_json(&self, s: &mut String) -> fmt::Result {
+        write!(s, "{{\"solves\":{},\"length\":", self.solves())?;
+        write_histogram_json(s, &self.lengths)?;
+        write!(s, ",\"phases\":[")?;
+        for (i, h) in self.phases.iter().enumerate() {
+            if i != 0 {
+                write!(s, ",")?;
+            }
+            write_histogram_json(s, h)?;
+        }
+        write!(s, "],\"moves\":{{")?;
+        for (i, (m, c)) in self.move_counts().enumerate() {
+            if i != 0 {
+                write!(s, ",")?;
+            }
+            write!(s, "\"{}\":{}", m, c)?;
+        }
+        write!(s, "}}}}")
+    }
+}
+
+fn write_histogram_csv<W: Write>(
+    w: &mut W,
+    table: &str,
+    h: &Histogram,
+) -> io::Result<()> {
+    for (v, &c) in h.counts().iter().enumerate() {
+        if c != 0 {
+            writeln!(w, "{},{},{}", table, v, c)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_histogram_json(s: &mut String, h: &Histogram) -> fmt::Result {
+    write!(s, "{{\"counts\":{:?}", h.counts())?;
+    match h.mean() {
+        Some(mean) => write!(s, ",\"mean\":{}", mean)?,
+        None => write!(s, ",\"mean\":null")?,
+    }
+    write!(s, "}}")
+}
@@ -0,0 +1,95 @@
+//! Step classifiers: the stages of human solving methods as patterns.
+use crate::cube::Cube;
+use crate::pattern::Pattern;
+use std::sync::OnceLock;
+
+/// One stage of a method: done once the cube matches `pattern`.
+#[derive(Debug, Clone, Copy)]
+pub struct Stage {
+    pub name: &'static str,
+    pub pattern: Pattern,
+    /// Match even when the centers have moved, as slice moves do: the
+    /// pieces need only be right relative to one another.
+    pub up_to_rotation: bool,
+}
+
+impl Stage {
+    pub fn new(name: &'static str, pattern: Pattern) -> Self {
+        Self {
+            name,
+            pattern,
+            up_to_rotation: false,
+        }
+    }
+
+    /// Whether the stage is done on `cube` held in orientation `sym` (a
+    /// rotation, i.e. an even symmetry index).
+    pub fn is_done(&self, cube: &Cube, sym: usize) -> bool {
+        if self.up_to_rotation {
+            rotations().iter().any(|r| {
+                self.pattern.matches(&cube.compose(r).sym_conjugate(sym))
+            })
+        } else {
+            self.pattern.matches(&cube.sym_conjugate(sym))
+        }
+    }
+}
+
+/// The 24 whole-cube rotations, as cubes.
+pub fn rotations() -> &'static [Cube; 24] {
+    static ROTATIONS: OnceLock<[Cube; 24]> = OnceLock::new();
+    ROTATIONS.get_or_init(|| {
+        let sym = Cube::sym();
+        let mut r = [Cube::identity(); 24];
+        for (i, c) in r.iter_mut().enumerate() {
+            *c = sym[2 * i];
+        }
+        r
+    })
+}
+
+/// A solving method: stages in the order they are completed.
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: &'static str,
+    pub stages: Vec<Stage>,
+}
+
+impl Method {
+    /// Cross, F2L, OLL, PLL, with the cross on D.
+    pub fn cfop() -> Self {
+        let f2l = Pattern::f2l();
+        let oll = f2l.and(&Pattern::orientation(0x00f, 0x0f));
+        Self {
+            name: "CFOP",
+            stages: vec![
+                Stage::new("cross", Pattern::cross()),
+                Stage::new("F2L", f2l),
+                Stage::new("OLL", oll),
+                Stage::new("PLL", Pattern::solved()),
+            ],
+        }
+    }
+
+    /// First block on the left, second block on the right, CMLL, LSE.
+    pub fn roux() -> Self {
+        let id = Cube::identity();
+        // DL, FL, BL with DLF, DBL.
+        let first = Pattern::slots(id, 0x640, 0x60);
+        // DR, FR, BR with DFR, DRB.
+        let second = first.and(&Pattern::slots(id, 0x910, 0x90));
+        let cmll = second.and(&Pattern::slots(id, 0, 0x0f));
+        Self {
+            name: "Roux",
+            stages: vec![
+                Stage::new("first block", first),
+                Stage::new("second block", second),
+                Stage::new("CMLL", cmll),
+                Stage {
+                    up_to_rotation: true,
+                    ..Stage::new("LSE", Pattern::solved())
+                },
+            ],
+        }
+    }
+}
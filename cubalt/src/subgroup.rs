@@ -0,0 +1,79 @@
+//! Enumerating the subgroup generated by arbitrary cubes.
+use crate::cube::Cube;
+use crate::cubemap::CubeSet;
+
+/// The result of `generate`.
+pub struct Subgroup {
+    /// Every element found.
+    pub elements: CubeSet,
+    /// Number of elements at each distance from the identity, counted in
+    /// the given generators.
+    pub depths: Vec<usize>,
+    /// False if the search stopped at the size cap; `elements` is then a
+    /// ball around the identity, not the whole subgroup.
+    pub complete: bool,
+}
+
+impl Subgroup {
+    /// Order of the subgroup, or a lower bound when incomplete.
+    pub fn order(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Largest distance reached (God's number in these generators when
+    /// complete).
+    pub fn diameter(&self) -> usize {
+        self.depths.len() - 1
+    }
+}
+
+/// Breadth-first enumeration of the subgroup generated by `generators`,
+/// stopping once more than `cap` elements are known.
+///
+/// Distances use the generators exactly as given. A finite group is
+/// generated by a set without its inverses, but for the usual metrics pass
+/// e.g. R, R2, R' rather than R alone.
+pub fn generate(generators: &[Cube], cap: usize) -> Subgroup {
+    let mut elements = CubeSet::new();
+    let identity = Cube::identity();
+    let _ = elements.insert(identity);
+    let mut depths = vec![1];
+    let mut frontier = vec![identity];
+    while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for c in frontier.iter() {
+            for g in generators.iter() {
+                let d = c.compose(g);
+                match elements.insert(d) {
+                    Ok(true) => next.push(d),
+                    Ok(false) => {}
+                    Err(_) => {
+                        depths.push(next.len());
+                        return Subgroup {
+                            elements,
+                            depths,
+                            complete: false,
+                        };
+                    }
+                }
+                if elements.len() > cap {
+                    depths.push(next.len());
+                    return Subgroup {
+                        elements,
+                        depths,
+                        complete: false,
+                    };
+                }
+            }
+        }
+        if !next.is_empty() {
+            depths.push(next.len());
+        }
+        frontier = next;
+    }
+    Subgroup {
+        elements,
+        depths,
+        complete: true,
+    }
+}
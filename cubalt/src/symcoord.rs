@@ -0,0 +1,354 @@
+//! Symmetry-reduced coordinates, built from a raw coordinate.
+//!
+//! A raw coordinate `C` is split into equivalence classes under a group of
+//! symmetries. A value is then described by its class and a symmetry that
+//! takes it to the class representative.
+use crate::coord::Coordinate;
+use crate::cube::Cube;
+use crate::notation::Move;
+use crate::puzzle::Puzzle;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+/// Class tables of coordinate `C` under a subgroup of `P`'s symmetries.
+///
+/// The symmetries must form a group (closed under composition; index 0,
+/// the identity, included) and must act on `C`: conjugating two states with
+/// equal coordinates must give states with equal coordinates.
+pub struct SymCoord<P, C> {
+    /// Symmetry indices used, as accepted by `Puzzle::sym_conjugate`.
+    syms: Vec<usize>,
+    /// Raw value -> class << 6 | sym.
+    class: Vec<u32>,
+    /// Class -> raw value of its representative.
+    reps: Vec<u32>,
+    /// Class -> bitmask of the syms that fix the representative.
+    stabilizers: Vec<u64>,
+    /// Class * moves + move -> class << 6 | sym, after moving the
+    /// representative.
+    moves: Vec<u32>,
+    /// Sym * moves + move -> index of the conjugated move.
+    move_conj: Vec<u8>,
+    /// Sym * syms + sym -> index of the composite.
+    sym_mult: Vec<u8>,
+    marker: PhantomData<(P, C)>,
+}
+
+fn pack(class: usize, sym: usize) -> u32 {
+    (class as u32) << 6 | sym as u32
+}
+
+fn unpack(v: u32) -> (usize, usize) {
+    ((v >> 6) as usize, (v & 0x3f) as usize)
+}
+
+impl<P: Puzzle, C: Coordinate<P>> SymCoord<P, C> {
+    /// Build all tables. Panics if `syms` is not a group of at most 64.
+    pub fn build(syms: &[usize]) -> Self {
+        assert!(!syms.is_empty() && syms.len() <= 64 && syms[0] == 0);
+        trace_span!(
+            "sym_coord",
+            coord = core::any::type_name::<C>(),
+            syms = syms.len()
+        );
+        let moves = P::moves();
+        let move_states: Vec<P> =
+            moves.iter().map(|&m| P::move_state(m)).collect();
+
+        // Conjugation is an automorphism, so it is fixed by its action on
+        // the generators; moves identify symmetries and their products.
+        let mut move_conj = Vec::with_capacity(syms.len() * moves.len());
+        for &s in syms {
+            for m in move_states.iter() {
+                let c = m.sym_conjugate(s);
+                let j = move_states.iter().position(|x| *x == c);
+                move_conj.push(
+                    j.expect("symmetry does not map moves to moves") as u8,
+                );
+            }
+        }
+        let n = moves.len();
+        let mut sym_mult = Vec::with_capacity(syms.len() * syms.len());
+        for i in 0..syms.len() {
+            for j in 0..syms.len() {
+                let l = (0..syms.len()).find(|&l| {
+                    (0..n).all(|m| {
+                        move_conj[j * n + move_conj[i * n + m] as usize]
+                            == move_conj[l * n + m]
+                    })
+                });
+                sym_mult.push(l.expect("symmetries are not closed") as u8);
+            }
+        }
+
+        let conj = |raw: usize, i: usize| {
+            C::rank(&C::unrank(raw).sym_conjugate(syms[i]))
+        };
+        let inverse: Vec<usize> = (0..syms.len())
+            .map(|i| {
+                (0..syms.len())
+                    .find(|&j| sym_mult[i * syms.len() + j] == 0)
+                    .expect("symmetries are not closed")
+            })
+            .collect();
+
+        let mut class = vec![u32::MAX; C::SIZE];
+        let mut reps = Vec::new();
+        let mut stabilizers = Vec::new();
+        for raw in 0..C::SIZE {
+            if class[raw] != u32::MAX {
+                continue;
+            }
+            let k = reps.len();
+            let mut stabilizer = 0u64;
+            for (i, &inv) in inverse.iter().enumerate() {
+                let r = conj(raw, i);
+                if r == raw {
+                    stabilizer |= 1 << i;
+                }
+                if class[r] == u32::MAX {
+                    class[r] = pack(k, inv);
+                }
+            }
+            reps.push(raw as u32);
+            stabilizers.push(stabilizer);
+        }
+
+        trace_event!(classes = reps.len());
+        let mut sym_moves = Vec::with_capacity(reps.len() * n);
+        for &rep in reps.iter() {
+            let p = C::unrank(rep as usize);
+            for m in move_states.iter() {
+                sym_moves.push(class[C::rank(&p.compose(m))]);
+            }
+        }
+
+        Self {
+            syms: syms.to_vec(),
+            class,
+            reps,
+            stabilizers,
+            moves: sym_moves,
+            move_conj,
+            sym_mult,
+            marker: PhantomData,
+        }
+    }
+
+    /// Number of classes.
+    pub fn classes(&self) -> usize {
+        self.reps.len()
+    }
+
+    pub fn syms(&self) -> &[usize] {
+        &self.syms
+    }
+
+    /// Class of a raw value, and the index (into `syms`) of a symmetry
+    /// conjugating the value to the class representative.
+    pub fn class_of(&self, raw: usize) -> (usize, usize) {
+        unpack(self.class[raw])
+    }
+
+    /// Raw value of a class representative.
+    pub fn rep(&self, class: usize) -> usize {
+        self.reps[class] as usize
+    }
+
+    /// Bitmask of the syms (by index into `syms`) that fix the class
+    /// representative.
+    pub fn stabilizer(&self, class: usize) -> u64 {
+        self.stabilizers[class]
+    }
+
+    /// Class and sym of the representative of `class` after move `m` (an
+    /// index into `P::moves()`).
+    pub fn sym_move(&self, class: usize, m: usize) -> (usize, usize) {
+        unpack(self.moves[class * P::moves().len() + m])
+    }
+
+    /// Index of the move S * m * S^-1, for `sym` an index into `syms`.
+    pub fn conjugate_move(&self, sym: usize, m: usize) -> usize {
+        self.move_conj[sym * P::moves().len() + m] as usize
+    }
+
+    /// Index of the symmetry equal to conjugating by `a`, then by `b`.
+    pub fn sym_product(&self, a: usize, b: usize) -> usize {
+        self.sym_mult[a * self.syms.len() + b] as usize
+    }
+
+    /// Class and sym after move `m` from a value with class `class` and sym
+    /// `sym`, without going through raw coordinates.
+    pub fn apply_move(
+        &self,
+        class: usize,
+        sym: usize,
+        m: usize,
+    ) -> (usize, usize) {
+        let (next, s) = self.sym_move(class, self.conjugate_move(sym, m));
+        (next, self.sym_product(sym, s))
+    }
+}
+
+/// The classes of coordinate `C` under `syms`, each by its least raw
+/// value, in increasing order: the representatives `SymCoord::build` picks,
+/// without its tables. Each value is checked against its conjugates, so a
+/// walk takes `C::SIZE * syms.len()` conjugations.
+pub fn class_reps<'a, P: Puzzle, C: Coordinate<P>>(
+    syms: &'a [usize],
+) -> impl Iterator<Item = usize> + 'a {
+    (0..C::SIZE).filter(move |&raw| {
+        let p = C::unrank(raw);
+        syms.iter().all(|&s| C::rank(&p.sym_conjugate(s)) >= raw)
+    })
+}
+
+/// Bytes ordering cubes, to pick the least of a class.
+fn cube_key(c: &Cube) -> [u8; 20] {
+    let mut key = [0; 20];
+    let pieces = c.edges().iter().map(|e| e.0);
+    for (k, x) in key
+        .iter_mut()
+        .zip(pieces.chain(c.corners().iter().map(|c| c.0)))
+    {
+        *k = x;
+    }
+    key
+}
+
+/// The least conjugate of `c` under `syms`.
+pub fn canonical(c: &Cube, syms: &[usize]) -> Cube {
+    syms.iter()
+        .map(|&s| c.sym_conjugate(s))
+        .min_by_key(cube_key)
+        .expect("syms include the identity")
+}
+
+/// The states within some number of moves of solved, or of another state,
+/// one per class under a group of symmetries, breadth first: `(distance,
+/// representative)`, the representative being the least conjugate by piece
+/// bytes.
+pub struct SymStates<'a> {
+    syms: &'a [usize],
+    max_depth: u32,
+    depth: u32,
+    /// Representatives at `depth`, and at the depth before.
+    frontier: Vec<Cube>,
+    previous: HashSet<Cube>,
+    next: usize,
+}
+
+impl<'a> SymStates<'a> {
+    /// `syms` must be a group of symmetries of `Cube`, such as `0..48`.
+    pub fn new(syms: &'a [usize], max_depth: u32) -> Self {
+        Self::around(&Cube::identity(), syms, max_depth)
+    }
+
+    /// The states near `start`, which every symmetry of `syms` must fix.
+    pub fn around(start: &Cube, syms: &'a [usize], max_depth: u32) -> Self {
+        debug_assert!(syms.iter().all(|&s| start.sym_conjugate(s) == *start));
+        Self {
+            syms,
+            max_depth,
+            depth: 0,
+            frontier: vec![canonical(start, syms)],
+            previous: HashSet::new(),
+            next: 0,
+        }
+    }
+
+    /// Moves on to the next distance.
+    fn expand(&mut self) {
+        let current: HashSet<Cube> = self.frontier.iter().copied().collect();
+        let mut next = HashSet::new();
+        for c in self.frontier.iter() {
+            for m in Move::ALL.iter() {
+                let d = canonical(&c.compose(m.cube()), self.syms);
+                if !current.contains(&d) && !self.previous.contains(&d) {
+                    next.insert(d);
+                }
+            }
+        }
+        let mut next: Vec<Cube> = next.into_iter().collect();
+        next.sort_by_key(cube_key);
+        self.previous = current;
+        self.frontier = next;
+        self.depth += 1;
+        self.next = 0;
+    }
+}
+
+impl Iterator for SymStates<'_> {
+    type Item = (u32, Cube);
+
+    fn next(&mut self) -> Option<(u32, Cube)> {
+        while self.next == self.frontier.len() {
+            if self.depth == self.max_depth || self.frontier.is_empty() {
+                return None;
+            }
+            self.expand();
+        }
+        self.next += 1;
+        Some((self.depth, self.frontier[self.next - 1]))
+    }
+}
+
+/// The symmetries, of all 48, that fix `c`.
+pub fn stabilizer(c: &Cube) -> Vec<usize> {
+    (0..48).filter(|&s| c.sym_conjugate(s) == *c).collect()
+}
+
+/// Number of distinct states at each distance up to `n` from any one
+/// state: every state has the same counts, as the moves act alike
+/// everywhere. The walk is around solved, over classes under all 48
+/// symmetries, each counted with its size.
+pub fn distance_counts(n: u32) -> Vec<u128> {
+    let syms: Vec<usize> = (0..48).collect();
+    let mut counts = vec![0; n as usize + 1];
+    for (d, c) in SymStates::new(&syms, n) {
+        let fixed = syms.iter().filter(|&&s| c.sym_conjugate(s) == c).count();
+        counts[d as usize] += (syms.len() / fixed) as u128;
+    }
+    counts
+}
+
+/// Number of distinct states within `n` moves of any one state.
+pub fn count_within(n: u32) -> u128 {
+    distance_counts(n).iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord::CornerPerm;
+    use crate::random::Rng;
+
+    #[test]
+    fn corner_perm_classes_follow_moves() {
+        // The symmetries keeping the U-D axis, as in Kociemba's solver.
+        let syms: Vec<usize> = (0..16).collect();
+        let sym = SymCoord::<Cube, CornerPerm>::build(&syms);
+        assert_eq!(sym.classes(), 2768);
+        let reps: Vec<usize> = class_reps::<Cube, CornerPerm>(&syms).collect();
+        assert_eq!(reps, (0..2768).map(|k| sym.rep(k)).collect::<Vec<_>>());
+
+        let mut rng = Rng::new(1);
+        let mut cube = Cube::identity();
+        let (mut class, mut s) = sym.class_of(0);
+        for _ in 0..200 {
+            let m = rng.below(18) as usize;
+            cube = cube.apply(Move::ALL[m]);
+            (class, s) = sym.apply_move(class, s, m);
+            let raw = CornerPerm::rank(&cube);
+            assert_eq!(sym.class_of(raw).0, class);
+            let conj = cube.sym_conjugate(syms[s]);
+            assert_eq!(CornerPerm::rank(&conj), sym.rep(class));
+        }
+    }
+
+    #[test]
+    fn counts_in_the_half_turn_metric() {
+        assert_eq!(distance_counts(5), [1, 18, 243, 3240, 43239, 574908]);
+        assert_eq!(count_within(3), 1 + 18 + 243 + 3240);
+    }
+}
@@ -0,0 +1,241 @@
+//! A directory of generated tables, shared by every program that uses
+//! them.
+//!
+//! Tables are stored by name, one file each, and generated on first use.
+//! Generators take a lock file per table, so two processes that miss at
+//! once generate it once: the second waits, then reads what the first
+//! wrote. Files are written under a temporary name and renamed into place,
+//! so a reader never sees half a table.
+//!
+//! Table `name` is stored as `name-vN.tbl`, for `N` the `FORMAT`, so
+//! tables written in an older layout are ignored and rebuilt rather than
+//! misread.
+use crate::movetable::MoveTable;
+use crate::prune::PruneTable;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Extension of table files.
+const EXTENSION: &str = "tbl";
+
+/// Version of the table layouts and file format; bump it on any change to
+/// either.
+pub const FORMAT: u32 = 1;
+
+/// A table in the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub name: String,
+    pub bytes: u64,
+}
+
+/// A table cache directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCache {
+    dir: PathBuf,
+}
+
+impl TableCache {
+    /// The cache in `dir`, created if missing.
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    /// The cache in `default_dir()`.
+    pub fn open_default() -> io::Result<Self> {
+        let dir = default_dir().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no cache directory: set CUBALT_CACHE_DIR",
+            )
+        })?;
+        Self::new(dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Table `name`'s file name, without extension.
+    fn key(name: &str) -> String {
+        format!("{}-v{}", name, FORMAT)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", Self::key(name), EXTENSION))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.path(name).is_file()
+    }
+
+    /// The table `name`, read with `read`; if it is missing, made with
+    /// `generate`, written with `write` and kept.
+    pub fn get_or_generate<T, R, G, W>(
+        &self,
+        name: &str,
+        read: R,
+        generate: G,
+        write: W,
+    ) -> io::Result<T>
+    where
+        R: Fn(BufReader<File>) -> io::Result<T>,
+        G: FnOnce() -> T,
+        W: FnOnce(&T, &mut BufWriter<File>) -> io::Result<()>,
+    {
+        let path = self.path(name);
+        if let Ok(file) = File::open(&path) {
+            return read(BufReader::new(file));
+        }
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(format!("{}.lock", Self::key(name))))?;
+        lock.lock()?;
+        // Another process may have made it while this one waited.
+        if let Ok(file) = File::open(&path) {
+            return read(BufReader::new(file));
+        }
+        let table = generate();
+        let tmp = self.dir.join(format!("{}.tmp", Self::key(name)));
+        let mut w = BufWriter::new(File::create(&tmp)?);
+        write(&table, &mut w)?;
+        w.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        Ok(table)
+    }
+
+    /// The pruning table `name` of `size` entries, built by `build` if it
+    /// is missing.
+    pub fn prune_table<B: FnOnce() -> PruneTable>(
+        &self,
+        name: &str,
+        size: usize,
+        build: B,
+    ) -> io::Result<PruneTable> {
+        self.get_or_generate(
+            name,
+            |r| PruneTable::read_from(r, size),
+            build,
+            |t, w| t.write_to(w),
+        )
+    }
+
+    /// The move table `name` of `size` values and `moves` moves, built by
+    /// `build` if it is missing.
+    pub fn move_table<B: FnOnce() -> MoveTable>(
+        &self,
+        name: &str,
+        size: usize,
+        moves: usize,
+        build: B,
+    ) -> io::Result<MoveTable> {
+        self.get_or_generate(
+            name,
+            |r| MoveTable::read_from(r, size, moves),
+            build,
+            |t, w| t.write_to(w),
+        )
+    }
+
+    /// The tables in the cache, by name. Files of other formats are left
+    /// out.
+    pub fn entries(&self) -> io::Result<Vec<CacheEntry>> {
+        let suffix = format!("-v{}", FORMAT);
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == EXTENSION) {
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| s.strip_suffix(suffix.as_str()));
+                if let Some(name) = name {
+                    entries.push(CacheEntry {
+                        name: name.to_string(),
+                        bytes: fs::metadata(&path)?.len(),
+                    });
+                }
+            }
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Bytes the cache's tables take.
+    pub fn size(&self) -> io::Result<u64> {
+        Ok(self.entries()?.iter().map(|e| e.bytes).sum())
+    }
+
+    /// Deletes table `name`, if present.
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(name)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes every table.
+    pub fn clear(&self) -> io::Result<()> {
+        for e in self.entries()? {
+            self.remove(&e.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where tables go by default: `$CUBALT_CACHE_DIR` if set, otherwise a
+/// `cubalt` directory in the platform's cache directory
+/// (`$XDG_CACHE_HOME` or `~/.cache` on Linux and other Unixes,
+/// `~/Library/Caches` on macOS, `%LOCALAPPDATA%` on Windows).
+pub fn default_dir() -> Option<PathBuf> {
+    let var = |name| env::var_os(name).filter(|v| !v.is_empty());
+    if let Some(dir) = var("CUBALT_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let base = if cfg!(windows) {
+        var("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|h| PathBuf::from(h).join("Library/Caches"))
+    } else {
+        var("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var("HOME").map(|h| PathBuf::from(h).join(".cache")))
+    };
+    base.map(|b| b.join("cubalt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord::{Coordinate, CornerOrient};
+    use crate::cube::Cube;
+
+    #[test]
+    fn files_are_keyed_by_format() {
+        let dir = env::temp_dir()
+            .join(format!("cubalt-tablecache-{}", std::process::id()));
+        let cache = TableCache::new(&dir).unwrap();
+        fs::write(dir.join("twist.tbl"), b"old").unwrap();
+        let build = PruneTable::build::<Cube, CornerOrient>;
+        let table = cache
+            .prune_table("twist", CornerOrient::SIZE, build)
+            .unwrap();
+        let file = dir.join(format!("twist-v{}.tbl", FORMAT));
+        assert!(file.is_file());
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "twist");
+        let read = cache
+            .prune_table("twist", CornerOrient::SIZE, || unreachable!())
+            .unwrap();
+        assert_eq!(read.depth_counts(), table.depth_counts());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+//! Case drills for training apps: random setups for chosen cases.
+use crate::alg::Alg;
+use crate::auf::{self, AUFS};
+use crate::cube::{Corner, Cube, Edge};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::random::Rng;
+use crate::solver::Solver;
+
+/// A case to drill, given by an alg that solves it.
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub name: String,
+    pub alg: Alg,
+}
+
+impl Case {
+    pub fn new(name: &str, alg: Alg) -> Self {
+        Self {
+            name: name.to_string(),
+            alg,
+        }
+    }
+
+    /// The state the case's alg solves.
+    pub fn state(&self) -> Cube {
+        self.alg.inverse().cube()
+    }
+}
+
+/// A generated drill.
+#[derive(Debug, Clone)]
+pub struct Setup {
+    /// Index of the case in the drill.
+    pub case: usize,
+    pub state: Cube,
+    /// Solver-generated, so it does not give the case away.
+    pub setup: Alg,
+}
+
+/// Generates setups for a selection of cases.
+pub struct Drill<'a> {
+    solver: &'a Solver,
+    cases: Vec<Case>,
+    /// U turn before the case, so it is seen from a random angle.
+    pub pre_auf: bool,
+    /// U turn after the case, so the solved state needs a random AUF.
+    pub post_auf: bool,
+    /// Relabel the last-layer pieces at random (keeping orientations), so
+    /// an orientation case comes with a random permutation, as for OLL.
+    pub random_permutation: bool,
+}
+
+impl<'a> Drill<'a> {
+    pub fn new(solver: &'a Solver, cases: Vec<Case>) -> Self {
+        Self {
+            solver,
+            cases,
+            pre_auf: true,
+            post_auf: true,
+            random_permutation: false,
+        }
+    }
+
+    pub fn cases(&self) -> &[Case] {
+        &self.cases
+    }
+
+    /// A setup for a uniformly chosen case.
+    pub fn next(&self, rng: &mut Rng) -> Setup {
+        let case = rng.below(self.cases.len() as u64) as usize;
+        self.setup(case, rng)
+    }
+
+    /// A setup for case `case`.
+    pub fn setup(&self, case: usize, rng: &mut Rng) -> Setup {
+        let mut state = self.cases[case].state();
+        if self.random_permutation {
+            state = random_ll_relabel(rng).compose(&state);
+        }
+        if self.pre_auf {
+            state = state.compose(&random_auf(rng));
+        }
+        if self.post_auf {
+            state = random_auf(rng).compose(&state);
+        }
+        let setup = self.solver.setup(&state);
+        Setup { case, state, setup }
+    }
+
+    /// A setup for every case, each once, in random order.
+    pub fn each_once(&self, rng: &mut Rng) -> Vec<Setup> {
+        let mut order: Vec<usize> = (0..self.cases.len()).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, rng.below(i as u64 + 1) as usize);
+        }
+        order
+            .into_iter()
+            .map(|case| self.setup(case, rng))
+            .collect()
+    }
+}
+
+/// The 21 PLLs, each with an alg in face turns.
+#[rustfmt::skip]
+const PLLS: [(&str, &str); 21] = [
+    ("Aa", "R' F R' B2 R F' R' B2 R2"),
+    ("Ab", "R2 B2 R F R' B2 R F' R"),
+    ("E", "L' B L F' L' B' L F L' B' L F' L' B L F"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 D U' R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 D' U R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "R2 U2 R U2 R2 U2 R2 U2 R U2 R2"),
+    ("Ja", "L' U' L F L' U' L U L F' L2 U L"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    ("Na", "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'"),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "R U' R U R U R U' R' U' R2"),
+    ("Ub", "R2 U R U R' U' R' U' R' U R'"),
+    ("V", "R' U R' U' B' R' B2 U' B' U B' R B R"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "R' U' R U' R U R U' R' U R U R2 U' R' U"),
+];
+
+/// The 21 PLL cases, by name.
+pub fn pll_cases() -> Vec<Case> {
+    PLLS.iter()
+        .map(|&(name, alg)| Case::new(name, alg.parse().expect("a valid alg")))
+        .collect()
+}
+
+/// A PLL time attack: setups for all 21 PLLs, each once, in random order,
+/// seen from a random angle and with a random AUF to finish if `aufs`.
+pub fn pll_time_attack(
+    solver: &Solver,
+    aufs: bool,
+    rng: &mut Rng,
+) -> Vec<Setup> {
+    let mut drill = Drill::new(solver, pll_cases());
+    drill.pre_auf = aufs;
+    drill.post_auf = aufs;
+    drill.each_once(rng)
+}
+
+fn random_auf(rng: &mut Rng) -> Cube {
+    auf::cube(*rng.choose(&AUFS))
+}
+
+/// A random even permutation of the U-layer pieces, as a relabeling.
+fn random_ll_relabel(rng: &mut Rng) -> Cube {
+    let corners = shuffle4(rng);
+    let mut edges = shuffle4(rng);
+    if parity4(&corners) != parity4(&edges) {
+        edges.swap(0, 1);
+    }
+    let mut c = Cube::identity();
+    for (i, &p) in corners.iter().enumerate() {
+        c.corners_mut()[i] = Corner(CORNERS.pack(p, 0));
+    }
+    for (i, &p) in edges.iter().enumerate() {
+        c.edges_mut()[i] = Edge(EDGES.pack(p, 0));
+    }
+    c
+}
+
+fn shuffle4(rng: &mut Rng) -> [u8; 4] {
+    let mut p = [0, 1, 2, 3];
+    for i in (1..4).rev() {
+        p.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    p
+}
+
+fn parity4(p: &[u8; 4]) -> bool {
+    let mut inversions = 0;
+    for i in 0..4 {
+        for j in i + 1..4 {
+            inversions += (p[i] > p[j]) as u32;
+        }
+    }
+    inversions % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether some U turn `auf` solves `setup`, played before its case's
+    /// alg if `before`, else after it.
+    fn solved_with_auf(drill: &Drill, setup: &Setup, before: bool) -> bool {
+        let alg = drill.cases()[setup.case].alg.cube();
+        AUFS.iter().any(|&m| {
+            let u = auf::cube(m);
+            let end = if before {
+                setup.state.compose(&u).compose(&alg)
+            } else {
+                setup.state.compose(&alg).compose(&u)
+            };
+            end == Cube::identity()
+        })
+    }
+
+    #[test]
+    fn aufs_go_where_documented() {
+        let mut rng = Rng::new(1);
+        for before in [true, false] {
+            let mut drill = Drill::new(Solver::shared(), pll_cases());
+            drill.pre_auf = before;
+            drill.post_auf = !before;
+            for setup in drill.each_once(&mut rng) {
+                assert_eq!(setup.setup.cube(), setup.state);
+                assert!(
+                    solved_with_auf(&drill, &setup, before),
+                    "{}",
+                    drill.cases()[setup.case].name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Optimal solutions in the ⟨R,U⟩ subgroup.
+//!
+//! R and U move six corners and seven edges, never flip an edge, and reach
+//! only 120 arrangements of the corners, so a state of the group is a
+//! corner arrangement, five corner twists and an edge permutation. Half of
+//! these coordinates have the wrong parity; the other 73,483,200 are the
+//! group, and a byte per coordinate holds the distance of every one of them
+//! in half-turn moves of R and U.
+use crate::alg::Alg;
+use crate::coord::{rank_perm, unrank_perm};
+use crate::cube::{Corner, Cube, Edge};
+use crate::layout::packed3::{CORNERS, EDGES};
+use crate::notation::Move;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// The moves of the group, in table order.
+pub const MOVES: [Move; 6] =
+    [Move::R, Move::R2, Move::Ri, Move::U, Move::U2, Move::Ui];
+
+/// Slots of the corners and edges that R and U move.
+const CORNER_SLOTS: [u8; 6] = [0, 1, 2, 3, 4, 7];
+const EDGE_SLOTS: [u8; 7] = [0, 1, 2, 3, 4, 8, 11];
+
+const CORNER_PERMS: usize = 120;
+const TWISTS: usize = 243;
+const EDGE_PERMS: usize = 5040;
+const SIZE: usize = CORNER_PERMS * TWISTS * EDGE_PERMS;
+const UNSEEN: u8 = u8::MAX;
+
+/// The coordinates of a group state.
+#[derive(Clone, Copy)]
+struct Coords {
+    cp: usize,
+    co: usize,
+    ep: usize,
+}
+
+impl Coords {
+    fn index(self) -> usize {
+        (self.cp * TWISTS + self.co) * EDGE_PERMS + self.ep
+    }
+
+    fn from_index(i: usize) -> Self {
+        Coords {
+            cp: i / (TWISTS * EDGE_PERMS),
+            co: i / EDGE_PERMS % TWISTS,
+            ep: i % EDGE_PERMS,
+        }
+    }
+}
+
+/// Distances of every ⟨R,U⟩ state, about 150 MB built in seconds. Clones
+/// share the table.
+#[derive(Clone)]
+pub struct TwoGenSolver {
+    /// Arrangement of the six corners, 0..6!, to its index among the 120
+    /// the group reaches, or `u16::MAX`.
+    cp_index: Vec<u16>,
+    /// Move tables, value * 6 + move -> value after the move.
+    cp_moves: Vec<u16>,
+    co_moves: Vec<u16>,
+    ep_moves: Vec<u16>,
+    /// Distance of each coordinate, `UNSEEN` where the parities disagree.
+    dist: Arc<Vec<u8>>,
+}
+
+impl Default for TwoGenSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TwoGenSolver {
+    pub fn new() -> Self {
+        trace_span!("two_gen_table", size = SIZE);
+        let (cp_index, cp_moves) = corner_perms();
+        let mut co_moves = Vec::with_capacity(TWISTS * MOVES.len());
+        for co in 0..TWISTS {
+            let c = unrank_twist(co);
+            for m in MOVES.iter() {
+                co_moves.push(rank_twist(&c.compose(m.cube())) as u16);
+            }
+        }
+        let mut ep_moves = Vec::with_capacity(EDGE_PERMS * MOVES.len());
+        for ep in 0..EDGE_PERMS {
+            let c = unrank_edges(ep);
+            for m in MOVES.iter() {
+                let r = rank_edges(&c.compose(m.cube())).expect("R and U");
+                ep_moves.push(r as u16);
+            }
+        }
+        let mut solver = Self {
+            cp_index,
+            cp_moves,
+            co_moves,
+            ep_moves,
+            dist: Arc::default(),
+        };
+        solver.fill();
+        solver
+    }
+
+    /// Breadth-first from solved, a sweep of the table per depth.
+    fn fill(&mut self) {
+        let mut dist = vec![UNSEEN; SIZE];
+        let solved = self.coords(&Cube::identity()).expect("in the group");
+        dist[solved.index()] = 0;
+        let mut depth = 0;
+        loop {
+            let mut found = 0;
+            for i in 0..SIZE {
+                if dist[i] != depth {
+                    continue;
+                }
+                for m in 0..MOVES.len() {
+                    let j = self.apply(Coords::from_index(i), m).index();
+                    if dist[j] == UNSEEN {
+                        dist[j] = depth + 1;
+                        found += 1;
+                    }
+                }
+            }
+            if found == 0 {
+                break;
+            }
+            depth += 1;
+        }
+        self.dist = Arc::new(dist);
+    }
+
+    fn apply(&self, c: Coords, m: usize) -> Coords {
+        Coords {
+            cp: self.cp_moves[c.cp * MOVES.len() + m] as usize,
+            co: self.co_moves[c.co * MOVES.len() + m] as usize,
+            ep: self.ep_moves[c.ep * MOVES.len() + m] as usize,
+        }
+    }
+
+    /// The coordinates of `cube`, if R and U leave the rest of it solved
+    /// and its corners are in an arrangement they reach.
+    fn coords(&self, cube: &Cube) -> Option<Coords> {
+        let outside_solved = (0..8)
+            .filter(|s| !CORNER_SLOTS.contains(s))
+            .all(|s| cube.corners()[s as usize].0 == CORNERS.pack(s, 0))
+            && (0..12)
+                .filter(|s| !EDGE_SLOTS.contains(s))
+                .all(|s| cube.edges()[s as usize].0 == EDGES.pack(s, 0))
+            && EDGE_SLOTS
+                .iter()
+                .all(|&s| EDGES.ori(cube.edges()[s as usize].0) == 0);
+        if !outside_solved {
+            return None;
+        }
+        let cp = *self.cp_index.get(rank_corners(cube)?)?;
+        if cp == u16::MAX {
+            return None;
+        }
+        Some(Coords {
+            cp: cp as usize,
+            co: rank_twist(cube),
+            ep: rank_edges(cube)?,
+        })
+    }
+
+    /// The number of moves in an optimal ⟨R,U⟩ solution of `cube`, or
+    /// `None` if R and U cannot solve it.
+    pub fn distance(&self, cube: &Cube) -> Option<u32> {
+        let d = self.dist[self.coords(cube)?.index()];
+        (d != UNSEEN).then_some(d as u32)
+    }
+
+    /// An optimal ⟨R,U⟩ solution of `cube`, if R and U can solve it.
+    pub fn solve(&self, cube: &Cube) -> Option<Alg> {
+        let mut c = self.coords(cube)?;
+        let mut d = self.dist[c.index()];
+        if d == UNSEEN {
+            return None;
+        }
+        let mut moves = Vec::with_capacity(d as usize);
+        while d > 0 {
+            let (m, next) = (0..MOVES.len())
+                .map(|m| (m, self.apply(c, m)))
+                .find(|&(_, n)| self.dist[n.index()] == d - 1)
+                .expect("a move towards solved");
+            moves.push(MOVES[m]);
+            c = next;
+            d -= 1;
+        }
+        Some(Alg(moves))
+    }
+
+    /// Every optimal ⟨R,U⟩ solution of `cube`, at most `limit` of them.
+    pub fn solutions(&self, cube: &Cube, limit: usize) -> Vec<Alg> {
+        let mut found = Vec::new();
+        if let Some(c) = self.coords(cube) {
+            if self.dist[c.index()] != UNSEEN {
+                self.descend(c, &mut Vec::new(), &mut found, limit);
+            }
+        }
+        found
+    }
+
+    fn descend(
+        &self,
+        c: Coords,
+        moves: &mut Vec<Move>,
+        found: &mut Vec<Alg>,
+        limit: usize,
+    ) {
+        let d = self.dist[c.index()];
+        if d == 0 {
+            found.push(Alg(moves.clone()));
+            return;
+        }
+        for (m, &mv) in MOVES.iter().enumerate() {
+            if found.len() == limit {
+                return;
+            }
+            let next = self.apply(c, m);
+            if self.dist[next.index()] == d - 1 {
+                moves.push(mv);
+                self.descend(next, moves, found, limit);
+                moves.pop();
+            }
+        }
+    }
+
+    /// How many states are at each distance from solved.
+    pub fn distance_counts(&self) -> Vec<u64> {
+        let mut counts = Vec::new();
+        for &d in self.dist.iter().filter(|&&d| d != UNSEEN) {
+            if counts.len() <= d as usize {
+                counts.resize(d as usize + 1, 0);
+            }
+            counts[d as usize] += 1;
+        }
+        counts
+    }
+}
+
+/// The reachable corner arrangements, found breadth-first, and their move
+/// table.
+fn corner_perms() -> (Vec<u16>, Vec<u16>) {
+    let mut index = vec![u16::MAX; 720];
+    let mut perms = vec![0];
+    index[0] = 0;
+    let mut i = 0;
+    while i < perms.len() {
+        let c = unrank_corners(perms[i]);
+        for m in MOVES.iter() {
+            let r = rank_corners(&c.compose(m.cube())).expect("R and U");
+            if index[r] == u16::MAX {
+                index[r] = perms.len() as u16;
+                perms.push(r);
+            }
+        }
+        i += 1;
+    }
+    debug_assert_eq!(perms.len(), CORNER_PERMS);
+    let mut moves = Vec::with_capacity(CORNER_PERMS * MOVES.len());
+    for &p in perms.iter() {
+        let c = unrank_corners(p);
+        for m in MOVES.iter() {
+            let r = rank_corners(&c.compose(m.cube())).expect("R and U");
+            moves.push(index[r]);
+        }
+    }
+    (index, moves)
+}
+
+/// Rank of the pieces in `SLOTS`, if they are the pieces of `SLOTS`.
+fn rank_slots<const N: usize>(
+    slots: &[u8; N],
+    pieces: [u8; N],
+) -> Option<usize> {
+    let mut local = [0; N];
+    for (x, p) in local.iter_mut().zip(pieces) {
+        *x = slots.iter().position(|&s| s == p)? as u8;
+    }
+    Some(rank_perm(&local))
+}
+
+fn rank_corners(c: &Cube) -> Option<usize> {
+    let pieces = CORNER_SLOTS.map(|s| CORNERS.piece(c.corners()[s as usize].0));
+    rank_slots(&CORNER_SLOTS, pieces)
+}
+
+fn unrank_corners(r: usize) -> Cube {
+    let mut local = [0; 6];
+    unrank_perm(r, &mut local);
+    let mut c = Cube::identity();
+    for (&s, &p) in CORNER_SLOTS.iter().zip(local.iter()) {
+        c.corners_mut()[s as usize] =
+            Corner(CORNERS.pack(CORNER_SLOTS[p as usize], 0));
+    }
+    c
+}
+
+/// Twists of the first five corners, base 3; the sixth makes the sum 0.
+fn rank_twist(c: &Cube) -> usize {
+    CORNER_SLOTS[..5].iter().fold(0, |r, &s| {
+        r * 3 + CORNERS.ori(c.corners()[s as usize].0) as usize
+    })
+}
+
+fn unrank_twist(mut r: usize) -> Cube {
+    let mut c = Cube::identity();
+    let mut sum = 0;
+    for &s in CORNER_SLOTS[..5].iter().rev() {
+        let t = (r % 3) as u8;
+        c.corners_mut()[s as usize] = Corner(CORNERS.pack(s, t));
+        sum += t;
+        r /= 3;
+    }
+    let last = CORNER_SLOTS[5];
+    c.corners_mut()[last as usize] =
+        Corner(CORNERS.pack(last, (3 - sum % 3) % 3));
+    c
+}
+
+fn rank_edges(c: &Cube) -> Option<usize> {
+    let pieces = EDGE_SLOTS.map(|s| EDGES.piece(c.edges()[s as usize].0));
+    rank_slots(&EDGE_SLOTS, pieces)
+}
+
+fn unrank_edges(r: usize) -> Cube {
+    let mut local = [0; 7];
+    unrank_perm(r, &mut local);
+    let mut c = Cube::identity();
+    for (&s, &p) in EDGE_SLOTS.iter().zip(local.iter()) {
+        c.edges_mut()[s as usize] = Edge(EDGES.pack(EDGE_SLOTS[p as usize], 0));
+    }
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn solves_the_group() {
+        let solver = TwoGenSolver::new();
+        let counts = solver.distance_counts();
+        assert_eq!(counts.iter().sum::<u64>(), 73_483_200);
+
+        // The first few distances, breadth-first over whole cubes.
+        let mut seen = HashSet::from([Cube::identity()]);
+        let mut frontier = vec![Cube::identity()];
+        for &count in counts[1..6].iter() {
+            frontier = frontier
+                .iter()
+                .flat_map(|c| MOVES.iter().map(move |m| c.compose(m.cube())))
+                .filter(|c| seen.insert(*c))
+                .collect();
+            assert_eq!(frontier.len() as u64, count);
+        }
+
+        let mut rng = Rng::new(1);
+        for _ in 0..10 {
+            let cube = (0..40).fold(Cube::identity(), |c, _| {
+                c.compose(rng.choose(&MOVES).cube())
+            });
+            let d = solver.distance(&cube).unwrap();
+            let solutions = solver.solutions(&cube, 4);
+            assert!(!solutions.is_empty());
+            for alg in solutions
+                .iter()
+                .chain([solver.solve(&cube).unwrap()].iter())
+            {
+                assert_eq!(alg.len() as u32, d);
+                assert_eq!(cube.compose(&alg.cube()), Cube::identity());
+            }
+        }
+        assert_eq!(solver.distance(Move::F.cube()), None);
+        assert_eq!(solver.solve(Move::F.cube()), None);
+    }
+}
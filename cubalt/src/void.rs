@@ -0,0 +1,45 @@
+//! The void cube: a 3x3 without centers.
+//!
+//! With nothing to fix the frame, states that differ by a whole-cube
+//! rotation look the same, and a slice turn is a face turn followed by a
+//! rotation. So a void state is a class `{ cube * r }` over the 24
+//! rotations `r`. Half of the rotations are odd edge permutations, which
+//! is how void cubes reach the 3x3's parity-mismatched states.
+use crate::alg::Alg;
+use crate::cube::Cube;
+use crate::solver::Solver;
+use crate::steps::rotations;
+
+/// The representative of `cube`'s class: the rotated state with the
+/// smallest key.
+pub fn canonical(cube: &Cube) -> Cube {
+    rotations()
+        .iter()
+        .map(|r| cube.compose(r))
+        .min_by_key(Cube::to_key)
+        .expect("24 rotations")
+}
+
+/// Whether two states look the same on a void cube.
+pub fn equivalent(a: &Cube, b: &Cube) -> bool {
+    is_solved(&a.invert().compose(b))
+}
+
+/// Solved up to a rotation.
+pub fn is_solved(cube: &Cube) -> bool {
+    rotations().contains(cube)
+}
+
+/// A face-turn solution reaching any of the rotations, of at most
+/// `max_length` moves.
+///
+/// The 12 rotations whose parity matches `cube` give 12 ordinary 3x3
+/// states to solve; the shortest solution found among them is returned.
+pub fn solve(solver: &Solver, cube: &Cube, max_length: u32) -> Option<Alg> {
+    rotations()
+        .iter()
+        .map(|r| r.invert().compose(cube))
+        .filter(|target| !target.parity())
+        .filter_map(|target| solver.solve(&target, max_length))
+        .min_by_key(Alg::len)
+}
@@ -0,0 +1,109 @@
+//! JavaScript bindings through wasm-bindgen.
+//!
+//! Cubes cross the boundary as 54-letter facelet strings and algs as
+//! strings; errors become thrown strings. The bindings use the same
+//! backend as the rest of the crate and need no wasm simd128 one: they
+//! build wherever the crate does.
+use crate::alg::Alg;
+use crate::context::CubeContext;
+use crate::facelet::Facelets;
+use crate::keyframes::{Animation, Timing};
+use crate::render;
+use crate::solver::Solver;
+use std::sync::OnceLock;
+use wasm_bindgen::prelude::*;
+
+fn solver() -> &'static Solver {
+    static CONTEXT: OnceLock<CubeContext> = OnceLock::new();
+    CONTEXT.get_or_init(CubeContext::default).solver()
+}
+
+fn alg(s: &str) -> Result<Alg, JsValue> {
+    s.parse().map_err(|e: crate::notation::ParseMoveError| {
+        JsValue::from_str(&e.to_string())
+    })
+}
+
+fn facelets(s: &str) -> Result<Facelets, JsValue> {
+    s.parse().map_err(|e: crate::facelet::FaceletError| {
+        JsValue::from_str(&e.to_string())
+    })
+}
+
+/// Normalize an alg: parse it and print it back, simplified.
+#[wasm_bindgen]
+pub fn parse(s: &str) -> Result<String, JsValue> {
+    Ok(alg(s)?.simplify().to_string())
+}
+
+/// Apply an alg to a facelet string.
+#[wasm_bindgen]
+pub fn apply(cube: &str, moves: &str) -> Result<String, JsValue> {
+    let f = facelets(cube)?;
+    let m = alg(moves)?.cube();
+    Ok(f.apply(&m).to_string())
+}
+
+/// A solution for a facelet string. The first call builds the tables.
+#[wasm_bindgen]
+pub fn solve(cube: &str, max_length: u32) -> Result<String, JsValue> {
+    let c = facelets(cube)?
+        .to_cube()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    solver()
+        .solve(&c, max_length)
+        .map(|a| a.to_string())
+        .ok_or_else(|| JsValue::from_str("no solution found"))
+}
+
+/// An SVG net of a facelet string.
+#[wasm_bindgen]
+pub fn render_svg(cube: &str, size: usize) -> Result<String, JsValue> {
+    Ok(render::svg(&facelets(cube)?, size))
+}
+
+/// `render_svg` in a named color scheme: `western`, `japanese` or
+/// `color-blind`.
+#[wasm_bindgen]
+pub fn render_svg_with(
+    cube: &str,
+    size: usize,
+    scheme: &str,
+) -> Result<String, JsValue> {
+    let scheme = scheme
+        .parse::<render::ColorScheme>()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(render::svg_with(&facelets(cube)?, size, &scheme))
+}
+
+/// Keyframes, as JSON, animating `scramble` and then `solution` from
+/// solved; see `keyframes`.
+#[wasm_bindgen]
+pub fn animate(scramble: &str, solution: &str) -> Result<String, JsValue> {
+    let animation =
+        Animation::new(&alg(scramble)?, &alg(solution)?, &Timing::default());
+    Ok(animation.to_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVED: &str =
+        "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+
+    #[test]
+    fn exports() {
+        assert_eq!(parse("R R U U'").unwrap(), "R2");
+        let scrambled = apply(SOLVED, "R U F'").unwrap();
+        assert_eq!(
+            Facelets::from_cube(&alg("R U F'").unwrap().cube()).to_string(),
+            scrambled
+        );
+        let solution = solve(&scrambled, 21).unwrap();
+        assert_eq!(apply(&scrambled, &solution).unwrap(), SOLVED);
+        assert!(render_svg(SOLVED, 20).unwrap().starts_with("<svg"));
+        assert!(render_svg_with(SOLVED, 20, "japanese").is_ok());
+        assert!(animate("R U", "U' R'").unwrap().starts_with('{'));
+    }
+}
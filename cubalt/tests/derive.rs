@@ -0,0 +1,42 @@
+//! A coordinate derived with `#[derive(Coordinate)]`, checked against the
+//! puzzle through its move table. The derive expands to `::cubalt` paths,
+//! so it is tested from outside the crate.
+#![cfg(feature = "derive")]
+use cubalt::coord::{self, Coordinate};
+use cubalt::cube::Cube;
+use cubalt::puzzle::Puzzle;
+use cubalt::random::Rng;
+
+fn twist(p: &Cube) -> usize {
+    coord::CornerOrient::rank(p)
+}
+
+fn untwist(c: usize) -> Cube {
+    coord::CornerOrient::unrank(c)
+}
+
+#[derive(Coordinate)]
+#[coordinate(puzzle = Cube, size = 2187, rank = twist, unrank = untwist)]
+struct Twist;
+
+#[test]
+fn derived_coordinate_round_trips_through_its_move_table() {
+    for c in 0..Twist::SIZE {
+        assert_eq!(Twist::rank(&Twist::unrank(c)), c);
+    }
+    let table = Twist::move_table();
+    assert_eq!(table.len(), Twist::SIZE);
+    let moves = <Cube as Puzzle>::moves();
+    let mut rng = Rng::new(1);
+    let (mut p, mut c) = (Cube::identity(), 0);
+    for _ in 0..1000 {
+        let i = rng.below(moves.len() as u64) as usize;
+        p = Puzzle::apply(&p, moves[i]);
+        c = table.get(c, i);
+        assert_eq!(c, Twist::rank(&p));
+    }
+
+    let mut bytes = Vec::new();
+    table.write_to(&mut bytes).unwrap();
+    assert_eq!(Twist::read_move_table(&bytes[..]).unwrap(), table);
+}
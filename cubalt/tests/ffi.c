@@ -0,0 +1,57 @@
+/* Calls every function in cubalt.h; built and run by tests/ffi.rs. */
+#include <assert.h>
+#include <stdio.h>
+
+#include "cubalt.h"
+
+int main(void) {
+    char buf[128];
+    uint8_t bytes[32];
+    float one_hot[480];
+
+    CubaltCube *solved = cubalt_cube_new();
+    assert(cubalt_cube_is_solved(solved));
+
+    CubaltCube *sexy = cubalt_cube_from_alg("R U R' U'");
+    assert(sexy != NULL && !cubalt_cube_is_solved(sexy));
+    assert(cubalt_cube_from_alg("R X") == NULL);
+
+    CubaltCube *inverse = cubalt_cube_invert(sexy);
+    CubaltCube *both = cubalt_cube_compose(sexy, inverse);
+    assert(cubalt_cube_equal(both, solved));
+    assert(!cubalt_cube_equal(sexy, solved));
+
+    CubaltCube *copy = cubalt_cube_clone(sexy);
+    assert(cubalt_cube_apply_alg(copy, "U R U' R'") == 0);
+    assert(cubalt_cube_is_solved(copy));
+    assert(cubalt_cube_apply_alg(copy, "R X") == -1);
+    assert(cubalt_cube_is_solved(copy));
+
+    assert(cubalt_cube_facelets(sexy, buf, sizeof buf) == 54);
+    CubaltCube *parsed = cubalt_cube_from_facelets(buf);
+    assert(parsed != NULL && cubalt_cube_equal(parsed, sexy));
+    assert(cubalt_cube_from_facelets("UUU") == NULL);
+    assert(cubalt_cube_facelets(sexy, NULL, 0) == 54);
+
+    assert(cubalt_cube_bytes(solved, bytes, sizeof bytes) == 32);
+    assert(bytes[1] == 1 && bytes[17] == 1);
+
+    assert(cubalt_cube_one_hot(solved, 0, one_hot, 480) == 480);
+    assert(cubalt_cube_one_hot(solved, 1, NULL, 0) == 324);
+
+    CubaltSolver *solver = cubalt_solver_new();
+    size_t len = cubalt_solve(solver, sexy, 21, buf, sizeof buf);
+    assert(len != (size_t)-1 && len < sizeof buf);
+    assert(cubalt_cube_apply_alg(sexy, buf) == 0);
+    assert(cubalt_cube_is_solved(sexy));
+    cubalt_solver_free(solver);
+
+    cubalt_cube_free(parsed);
+    cubalt_cube_free(copy);
+    cubalt_cube_free(both);
+    cubalt_cube_free(inverse);
+    cubalt_cube_free(sexy);
+    cubalt_cube_free(solved);
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,34 @@
+//! Builds the library as a cdylib, then compiles a C program against
+//! `include/cubalt.h`, links it with the library and runs it, so the
+//! hand-written header cannot drift from `src/ffi.rs` unnoticed.
+#![cfg(feature = "ffi")]
+use std::path::Path;
+use std::process::Command;
+
+fn run(cmd: &mut Command) {
+    let status = cmd.status().expect("spawning a build step");
+    assert!(status.success(), "{:?} failed", cmd);
+}
+
+#[test]
+fn c_program_builds_and_runs_against_the_header() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    run(Command::new(env!("CARGO"))
+        .current_dir(manifest)
+        .args(["rustc", "--lib", "--release", "--features", "ffi"])
+        .args(["--crate-type", "cdylib", "--target-dir"])
+        .arg(&out));
+    let lib = out.join("release");
+    let exe = out.join("ffi");
+    run(Command::new("cc")
+        .arg(manifest.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg("-L")
+        .arg(&lib)
+        .arg(format!("-Wl,-rpath,{}", lib.display()))
+        .args(["-lcubalt", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&exe));
+    run(&mut Command::new(&exe));
+}